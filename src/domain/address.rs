@@ -1,14 +1,49 @@
 use serde::{Deserialize, Serialize};
 
+use super::{AddressType, FrenchDepartment, Recipient, RecipientKind, text::fold};
+
+/// ISO code of France, whose country sub-division can be derived from the postal code
+const FRANCE: &str = "FR";
+
 /// Represents a postal address in the internal model
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+///
+/// It covers every element of the ISO 20022 postal address so that an address can be stored and restored without loss.
+/// Optional fields missing from a stored address are read as `None`, and lists as empty.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
 pub struct Address {
     /// Name of the recipient
     pub name: Option<String>,
 
+    /// Structured recipient, which takes precedence over the name when rendering the address
+    pub recipient: Option<Recipient>,
+
+    /// Kind of recipient, giving the French lines 2 and 3 a private or a business meaning
+    pub recipient_kind: Option<RecipientKind>,
+
+    /// Person or entity the mail is delivered through (`Chez …`, `c/o …`)
+    pub care_of: Option<String>,
+
+    /// Type of the address (postal, post box, business, ...)
+    pub address_type: Option<AddressType>,
+
+    /// Division of a large organization or building
+    pub department: Option<String>,
+
+    /// Sub-division of a large organization or building
+    pub sub_department: Option<String>,
+
     /// Floor
     pub floor: Option<String>,
 
+    /// Apartment or unit within a building
+    pub unit: Option<String>,
+
+    /// Staircase leading to the unit (e.g. `Escalier B`)
+    pub staircase: Option<String>,
+
+    /// Entrance of the building (e.g. `Entrée A`)
+    pub entrance: Option<String>,
+
     /// Numbered box in a post office
     pub post_box: Option<String>,
 
@@ -18,33 +53,221 @@ pub struct Address {
     /// Name of a street
     pub street_name: String,
 
+    /// Number that identifies the position of a building on a street
+    pub building_number: Option<String>,
+
+    /// Name of the building or house
+    pub building_name: Option<String>,
+
     /// Name of the town
     pub town_name: String,
 
+    /// CEDEX suffix of a French destination locality, with its office number if any (e.g. `CEDEX 08`)
+    pub cedex: Option<String>,
+
     /// Specific location name within the town
     pub town_location_name: Option<String>,
 
+    /// Subdivision within a country sub-division
+    pub district_name: Option<String>,
+
     /// Postal code
     pub post_code: String,
 
+    /// Subdivision of a country such as state, region, county
+    pub country_sub_division: Option<String>,
+
     /// Country (ISO country code)
     pub country: String,
+
+    /// Unstructured address lines, as found in legacy or hybrid ISO 20022 addresses
+    #[serde(default)]
+    pub address_lines: Vec<String>,
+
+    /// References of the statements the address was imported from (e.g. the `<Stmt><Id>` of a camt.053)
+    #[serde(default)]
+    pub source_references: Vec<String>,
 }
 
 impl Address {
+    /// Structured recipient, or the one parsed from the name when none is stored
+    pub fn recipient(&self) -> Option<Recipient> {
+        self.recipient
+            .clone()
+            .or_else(|| self.name.as_deref().map(Recipient::parse))
+    }
+
+    /// Kind of recipient, or when none is stored, a business for a `BIZZ` address or a company recipient
+    pub fn recipient_kind(&self) -> RecipientKind {
+        self.recipient_kind.unwrap_or_else(|| {
+            let is_business = self.address_type == Some(AddressType::Business)
+                || self
+                    .recipient()
+                    .is_some_and(|recipient| recipient.is_company());
+
+            if is_business {
+                RecipientKind::Business
+            } else {
+                RecipientKind::Person
+            }
+        })
+    }
+
+    /// Address whose missing country sub-division is filled, for a French address, with the ISO 3166-2 code of the
+    /// department of its postal code (e.g. `FR-33` for 33380)
+    pub fn with_derived_country_sub_division(self) -> Address {
+        if self.country_sub_division.is_some() || self.country != FRANCE {
+            return self;
+        }
+
+        let country_sub_division = FrenchDepartment::from_post_code(&self.post_code)
+            .map(|department| department.iso_code.to_string());
+
+        Address {
+            country_sub_division,
+            ..self
+        }
+    }
+
+    /// Whether the address designates the same recipient at the same place as another one.
+    ///
+    /// Recipients are compared by their rendered name, so that `M. DURAND Jean` matches `Monsieur Jean DURAND`. Case,
+    /// accents and whitespace are ignored, as well as the address type, the country sub-division (which may be derived
+    /// from the postal code) and the source references.
+    pub fn is_duplicate_of(&self, other: &Address) -> bool {
+        self.comparison_key() == other.comparison_key()
+    }
+
+    fn comparison_key(&self) -> Vec<String> {
+        let recipient = self.recipient().map(|recipient| recipient.to_string());
+
+        [
+            recipient.as_deref(),
+            self.care_of.as_deref(),
+            self.department.as_deref(),
+            self.sub_department.as_deref(),
+            self.floor.as_deref(),
+            self.unit.as_deref(),
+            self.staircase.as_deref(),
+            self.entrance.as_deref(),
+            self.post_box.as_deref(),
+            self.room.as_deref(),
+            Some(self.street_name.as_str()),
+            self.building_number.as_deref(),
+            self.building_name.as_deref(),
+            Some(self.town_name.as_str()),
+            self.cedex.as_deref(),
+            self.town_location_name.as_deref(),
+            self.district_name.as_deref(),
+            Some(self.post_code.as_str()),
+            Some(self.country.as_str()),
+        ]
+        .into_iter()
+        .map(|value| fold(value.unwrap_or_default()))
+        .chain(self.address_lines.iter().map(|line| fold(line)))
+        .collect()
+    }
+
     #[cfg(test)]
     /// Create a dummy [Address]
     pub fn dummy() -> Self {
         Self {
             name: Some("John Doe".to_string()),
-            floor: None,
-            post_box: None,
-            room: None,
             street_name: "123 Main St".to_string(),
             town_name: "Anytown".to_string(),
-            town_location_name: None,
             post_code: "12345".to_string(),
             country: "FR".to_string(),
+            ..Default::default()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_derived_country_sub_division_fills_french_department_only() {
+        let corsica = Address {
+            post_code: "20200".to_string(),
+            ..Address::dummy()
+        };
+        let foreign = Address {
+            post_code: "33380".to_string(),
+            country: "BE".to_string(),
+            ..Address::dummy()
+        };
+        let given = Address {
+            country_sub_division: Some("Aquitaine".to_string()),
+            ..Address::dummy()
+        };
+
+        assert_eq!(
+            corsica
+                .with_derived_country_sub_division()
+                .country_sub_division,
+            Some("FR-2B".to_string())
+        );
+        assert_eq!(
+            foreign
+                .with_derived_country_sub_division()
+                .country_sub_division,
+            None
+        );
+        assert_eq!(
+            given
+                .with_derived_country_sub_division()
+                .country_sub_division,
+            Some("Aquitaine".to_string())
+        );
+    }
+
+    #[test]
+    fn is_duplicate_of_ignores_case_accents_and_source_references() {
+        let address = Address {
+            name: Some("Société DUPONT".to_string()),
+            source_references: vec!["STMT-1".to_string()],
+            ..Address::dummy()
+        };
+        let duplicate = Address {
+            name: Some("SOCIETE  dupont".to_string()),
+            street_name: "123 MAIN ST".to_string(),
+            country_sub_division: Some("FR-12".to_string()),
+            source_references: vec!["STMT-2".to_string()],
+            ..Address::dummy()
+        };
+
+        assert!(address.is_duplicate_of(&duplicate));
+        assert!(!address.is_duplicate_of(&Address::dummy()));
+    }
+
+    #[test]
+    fn is_duplicate_of_compares_recipients_by_their_rendered_name() {
+        let address = Address {
+            name: Some("Monsieur Jean DURAND".to_string()),
+            ..Address::dummy()
+        };
+
+        for name in ["Monsieur DURAND Jean", "M. Jean Durand"] {
+            let duplicate = Address {
+                name: Some(name.to_string()),
+                ..Address::dummy()
+            };
+
+            assert!(address.is_duplicate_of(&duplicate), "{name}");
+        }
+    }
+
+    #[test]
+    fn deserialize_reads_missing_optional_fields_as_none_and_lists_as_empty() {
+        let json = r#"{"name": "John Doe", "street_name": "123 Main St", "town_name": "Anytown", "post_code": "12345", "country": "FR"}"#;
+
+        let address: Address = serde_json::from_str(json).unwrap();
+
+        assert_eq!(address, Address::dummy());
+        let stored = serde_json::to_value(&address).unwrap();
+        assert_eq!(stored["recipient"], serde_json::Value::Null);
+        assert_eq!(stored["care_of"], serde_json::Value::Null);
+        assert_eq!(stored["address_lines"], serde_json::json!([]));
+    }
+}
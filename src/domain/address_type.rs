@@ -0,0 +1,120 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// Type of an address, as the ISO 20022 `<AdrTp>` element
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AddressType {
+    /// Complete postal address (`ADDR`)
+    #[serde(rename = "ADDR")]
+    Postal,
+
+    /// Post office box (`PBOX`)
+    #[serde(rename = "PBOX")]
+    PostBox,
+
+    /// Home address (`HOME`)
+    #[serde(rename = "HOME")]
+    Residential,
+
+    /// Business address (`BIZZ`)
+    #[serde(rename = "BIZZ")]
+    Business,
+
+    /// Address to which mail is sent (`MLTO`)
+    #[serde(rename = "MLTO")]
+    MailTo,
+
+    /// Address to which goods are delivered (`DLVY`)
+    #[serde(rename = "DLVY")]
+    DeliveryTo,
+
+    /// Type defined by a scheme other than ISO 20022
+    #[serde(rename = "Prtry")]
+    Proprietary(ProprietaryAddressType),
+}
+
+/// Address type defined by a proprietary scheme (`<Prtry>`)
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ProprietaryAddressType {
+    /// Identification of the type, 4 letters or digits
+    #[serde(rename = "Id")]
+    pub id: String,
+
+    /// Entity that assigns the identification
+    #[serde(rename = "Issr")]
+    pub issuer: String,
+
+    /// Name of the identification scheme
+    #[serde(rename = "SchmeNm")]
+    pub scheme_name: Option<String>,
+}
+
+impl AddressType {
+    /// Codes of the ISO 20022 address types, with their type
+    const CODES: [(&'static str, AddressType); 6] = [
+        ("ADDR", AddressType::Postal),
+        ("PBOX", AddressType::PostBox),
+        ("HOME", AddressType::Residential),
+        ("BIZZ", AddressType::Business),
+        ("MLTO", AddressType::MailTo),
+        ("DLVY", AddressType::DeliveryTo),
+    ];
+
+    /// Address type of an ISO 20022 code (e.g. `PBOX`), ignoring case
+    pub fn from_code(code: &str) -> Option<AddressType> {
+        Self::CODES
+            .into_iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(code.trim()))
+            .map(|(_, address_type)| address_type)
+    }
+
+    /// ISO 20022 code of the type, or `None` for a proprietary type
+    pub fn code(&self) -> Option<&'static str> {
+        Self::CODES
+            .iter()
+            .find(|(_, address_type)| address_type == self)
+            .map(|(code, _)| *code)
+    }
+}
+
+impl Display for AddressType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressType::Proprietary(proprietary) => {
+                write!(f, "{} ({})", proprietary.id, proprietary.issuer)
+            }
+            address_type => write!(f, "{}", address_type.code().unwrap_or_default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_code_ignores_case_and_code_round_trips() {
+        assert_eq!(AddressType::from_code("pbox"), Some(AddressType::PostBox));
+        assert_eq!(AddressType::from_code("XXXX"), None);
+        assert_eq!(AddressType::Business.code(), Some("BIZZ"));
+    }
+
+    #[test]
+    fn serializes_codes_as_strings_and_proprietary_types_as_objects() {
+        let proprietary = AddressType::Proprietary(ProprietaryAddressType {
+            id: "HQ01".to_string(),
+            issuer: "ACME".to_string(),
+            scheme_name: None,
+        });
+
+        assert_eq!(
+            serde_json::to_string(&AddressType::Business).unwrap(),
+            "\"BIZZ\""
+        );
+        assert_eq!(
+            serde_json::to_string(&proprietary).unwrap(),
+            r#"{"Prtry":{"Id":"HQ01","Issr":"ACME","SchmeNm":null}}"#
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// Kind of alteration made to a value during a conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlterationKind {
+    /// The value has no place in the target format and is lost
+    Dropped,
+
+    /// The value is kept but merged into, or stored in, a field with a different meaning
+    Moved,
+
+    /// The value does not come from the source and was made up to fill a mandatory field
+    Synthesized,
+
+    /// The value is kept as is but does not match the reference data
+    Unverified,
+}
+
+impl Display for AlterationKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AlterationKind::Dropped => write!(f, "dropped"),
+            AlterationKind::Moved => write!(f, "moved"),
+            AlterationKind::Synthesized => write!(f, "synthesized"),
+            AlterationKind::Unverified => write!(f, "unverified"),
+        }
+    }
+}
+
+/// Alteration of a single field during a conversion
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversionWarning {
+    /// Kind of alteration
+    pub kind: AlterationKind,
+
+    /// Name of the source field (or target field for synthesized values)
+    pub field: String,
+
+    /// Human readable explanation, including the altered value
+    pub detail: String,
+}
+
+impl Display for ConversionWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.kind, self.field, self.detail)
+    }
+}
+
+/// Structured report of every alteration made during a conversion
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ConversionReport {
+    /// Alterations in the order they were made
+    pub warnings: Vec<ConversionWarning>,
+}
+
+impl ConversionReport {
+    /// Record a value lost during the conversion
+    pub fn dropped(&mut self, field: &str, detail: impl Into<String>) {
+        self.push(AlterationKind::Dropped, field, detail);
+    }
+
+    /// Record a value stored in a field with a different meaning
+    pub fn moved(&mut self, field: &str, detail: impl Into<String>) {
+        self.push(AlterationKind::Moved, field, detail);
+    }
+
+    /// Record a value made up to fill a mandatory field
+    pub fn synthesized(&mut self, field: &str, detail: impl Into<String>) {
+        self.push(AlterationKind::Synthesized, field, detail);
+    }
+
+    /// Record a value kept as is although it does not match the reference data
+    pub fn unverified(&mut self, field: &str, detail: impl Into<String>) {
+        self.push(AlterationKind::Unverified, field, detail);
+    }
+
+    /// Append the warnings of a following conversion step
+    pub fn merge(&mut self, other: ConversionReport) {
+        self.warnings.extend(other.warnings);
+    }
+
+    /// Whether the conversion kept every value of the source without making any up.
+    ///
+    /// Moved and unverified values are kept, so they do not make a conversion lossy.
+    pub fn is_lossless(&self) -> bool {
+        self.warnings.iter().all(|warning| {
+            matches!(
+                warning.kind,
+                AlterationKind::Moved | AlterationKind::Unverified
+            )
+        })
+    }
+
+    /// Whether nothing was altered at all
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    fn push(&mut self, kind: AlterationKind, field: &str, detail: impl Into<String>) {
+        self.warnings.push(ConversionWarning {
+            kind,
+            field: field.to_string(),
+            detail: detail.into(),
+        });
+    }
+}
+
+impl Display for ConversionReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.warnings.iter().map(|w| w.to_string()).collect();
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Result of a conversion: the converted address and the report of its alterations
+#[derive(Debug, Clone, PartialEq)]
+pub struct Converted<T> {
+    /// Converted address
+    pub address: T,
+
+    /// Alterations made during the conversion
+    pub report: ConversionReport,
+}
+
+impl<T> Converted<T> {
+    /// Create a new [Converted]
+    pub fn new(address: T, report: ConversionReport) -> Self {
+        Self { address, report }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_with_only_moved_or_unverified_values_is_lossless() {
+        let mut report = ConversionReport::default();
+        report.moved("building_number", "'25' merged into line 4");
+        report.unverified("town_name", "'PARIS' is not served by postal code 33500");
+
+        assert!(report.is_lossless());
+        assert!(!report.is_empty());
+    }
+
+    #[test]
+    fn report_with_dropped_or_synthesized_values_is_lossy() {
+        let mut dropped = ConversionReport::default();
+        dropped.dropped("department", "'Service Comptabilité' has no French line");
+        let mut synthesized = ConversionReport::default();
+        synthesized.synthesized("name", "line 1 left empty");
+
+        assert!(!dropped.is_lossless());
+        assert!(!synthesized.is_lossless());
+    }
+
+    #[test]
+    fn merge_appends_warnings_in_order() {
+        let mut report = ConversionReport::default();
+        report.moved("room", "first");
+        let mut other = ConversionReport::default();
+        other.dropped("name", "second");
+
+        report.merge(other);
+
+        assert_eq!(
+            report.to_string(),
+            "moved room: first\ndropped name: second".to_string()
+        );
+    }
+}
@@ -1,5 +1,12 @@
 mod address;
+mod address_type;
+mod conversion_report;
+mod recipient;
 mod standards;
+mod text;
 
 pub use address::Address;
+pub use address_type::{AddressType, ProprietaryAddressType};
+pub use conversion_report::*;
+pub use recipient::{Civility, Recipient, RecipientKind};
 pub use standards::*;
@@ -0,0 +1,380 @@
+//! Recipient of an address: a person, with civility, given names and family name, or a company
+
+use std::fmt::{self, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use super::text::{collapse_whitespace, fold};
+
+/// Legal forms and their abbreviations, marking a company name when they are its first or last word
+const LEGAL_FORMS: [&str; 14] = [
+    "SA", "SAS", "SASU", "SARL", "EURL", "SCI", "SNC", "SCP", "SCOP", "SELARL", "GIE", "STE",
+    "ETS", "CIE",
+];
+
+/// Words marking a company name wherever they are
+const COMPANY_WORDS: [&str; 6] = [
+    "SOCIETE",
+    "ETABLISSEMENTS",
+    "COMPAGNIE",
+    "GROUPE",
+    "ASSOCIATION",
+    "BANQUE",
+];
+
+/// Lowercase particles that belong to the family name that follows them (e.g. `Charles de GAULLE`)
+const FAMILY_NAME_PARTICLES: [&str; 10] = [
+    "DE", "DU", "DES", "D'", "LE", "LA", "VAN", "VON", "DI", "DA",
+];
+
+/// Words joining two civilities (e.g. `M. et Mme`)
+const CIVILITY_CONJUNCTIONS: [&str; 2] = ["ET", "&"];
+
+/// Civility of a person
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Civility {
+    /// `M.`, `MR` or `Monsieur`
+    Monsieur,
+
+    /// `Mme`, `Madame`, and the former `Mlle` or `Mademoiselle`
+    Madame,
+
+    /// `M. et Mme` or `Monsieur et Madame`
+    MonsieurEtMadame,
+
+    /// `MM` or `Messieurs`
+    Messieurs,
+}
+
+impl Display for Civility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Civility::Monsieur => write!(f, "Monsieur"),
+            Civility::Madame => write!(f, "Madame"),
+            Civility::MonsieurEtMadame => write!(f, "Monsieur et Madame"),
+            Civility::Messieurs => write!(f, "Messieurs"),
+        }
+    }
+}
+
+impl Civility {
+    /// Civility written as a single word, abbreviated or not, with or without a trailing period
+    fn from_word(word: &str) -> Option<Civility> {
+        match fold(word).trim_end_matches('.') {
+            "M" | "MR" | "MONSIEUR" => Some(Civility::Monsieur),
+            "MME" | "MADAME" | "MLLE" | "MADEMOISELLE" => Some(Civility::Madame),
+            "MM" | "MESSIEURS" => Some(Civility::Messieurs),
+            _ => None,
+        }
+    }
+
+    /// Civility at the start of the words, with the number of words it spans
+    fn parse(words: &[&str]) -> Option<(Civility, usize)> {
+        let first = Civility::from_word(words.first()?)?;
+
+        let joined = words
+            .get(1)
+            .is_some_and(|word| CIVILITY_CONJUNCTIONS.contains(&fold(word).as_str()));
+        if joined {
+            let second = words.get(2).and_then(|word| Civility::from_word(word));
+            if matches!(
+                (first, second),
+                (Civility::Monsieur, Some(Civility::Madame))
+                    | (Civility::Madame, Some(Civility::Monsieur))
+            ) {
+                return Some((Civility::MonsieurEtMadame, 3));
+            }
+        }
+
+        Some((first, 1))
+    }
+}
+
+/// Kind of recipient, which gives the meaning of the French lines 2 and 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipientKind {
+    /// Private person: line 2 identifies the delivery point (room), line 3 the floor and building
+    #[default]
+    Person,
+
+    /// Legal entity: line 1 is the company name, line 2 the recipient person or department, line 3 the building or
+    /// zone
+    Business,
+}
+
+impl Display for RecipientKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipientKind::Person => write!(f, "person"),
+            RecipientKind::Business => write!(f, "business"),
+        }
+    }
+}
+
+/// Structured recipient of an address.
+///
+/// A person is named by a civility, given names and a family name, a company by its name. Both may be given when a
+/// person is reached within a company.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Recipient {
+    /// Civility of the person
+    pub civility: Option<Civility>,
+
+    /// Given names of the person, as written
+    pub given_names: Option<String>,
+
+    /// Family name of the person
+    pub family_name: Option<String>,
+
+    /// Name of the company, with its legal form
+    pub company_name: Option<String>,
+}
+
+impl Display for Recipient {
+    /// Name addressed on line 1: the company name if any, or else the person in the French order, family name in
+    /// uppercase (e.g. `Monsieur Jean DURAND`)
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (&self.company_name, self.person_name()) {
+            (Some(company_name), _) => write!(f, "{company_name}"),
+            (None, Some(person_name)) => write!(f, "{person_name}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl Recipient {
+    /// Parse a free-text recipient line such as `M. Jean DURAND`, `DURAND Jean` or `ACME SARL`.
+    ///
+    /// A line with a legal form or a word such as `SOCIETE` is a company name. Otherwise a leading civility is read,
+    /// and the family name is told apart from the given names by its uppercase, whether it comes last or first. When
+    /// the case does not tell, the last word is the family name.
+    pub fn parse(line: &str) -> Recipient {
+        let line = collapse_whitespace(line);
+        if is_company_name(&line) {
+            return Recipient {
+                company_name: Some(line),
+                ..Default::default()
+            };
+        }
+
+        let words: Vec<&str> = line.split(' ').filter(|word| !word.is_empty()).collect();
+        let (civility, start) = match Civility::parse(&words) {
+            Some((civility, length)) => (Some(civility), length),
+            None => (None, 0),
+        };
+        let names = &words[start..];
+        let (given_names, family_name) = split_family_name(names);
+
+        Recipient {
+            civility,
+            given_names: join_words(given_names),
+            family_name: join_words(family_name),
+            company_name: None,
+        }
+    }
+
+    /// Whether the recipient is a company
+    pub fn is_company(&self) -> bool {
+        self.company_name.is_some()
+    }
+
+    /// Person in the French order, family name in uppercase (e.g. `Monsieur Jean DURAND`), if any
+    pub fn person_name(&self) -> Option<String> {
+        let civility = self.civility.map(|civility| civility.to_string());
+        let family_name = self
+            .family_name
+            .as_deref()
+            .map(|family_name| family_name.to_uppercase());
+        let parts: Vec<String> = [civility, self.given_names.clone(), family_name]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.trim().is_empty())
+            .collect();
+
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+/// Name as given when rendering it from its recipient only changes its case, accents or spacing, or else the rendered
+/// name
+pub(crate) fn rendered_name(given: Option<&str>, rendered: String) -> String {
+    match given {
+        Some(given) if fold(given) == fold(&rendered) => given.to_string(),
+        _ => rendered,
+    }
+}
+
+/// Whether a name is a company name, by its legal form as first or last word (`SARL DUPONT`, `DUPONT & CIE`) or a
+/// word such as `SOCIETE`
+pub(crate) fn is_company_name(name: &str) -> bool {
+    let folded = fold(name);
+    let words: Vec<&str> = folded
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let has_legal_form = [words.first(), words.last()]
+        .into_iter()
+        .flatten()
+        .any(|word| LEGAL_FORMS.contains(word));
+
+    has_legal_form || words.iter().any(|word| COMPANY_WORDS.contains(word))
+}
+
+/// Split names into given names and family name
+fn split_family_name<'a>(names: &'a [&'a str]) -> (&'a [&'a str], &'a [&'a str]) {
+    let leading = names.iter().take_while(|word| is_uppercase(word)).count();
+    let trailing = names
+        .iter()
+        .rev()
+        .take_while(|word| is_uppercase(word))
+        .count();
+
+    if leading > 0 && trailing == 0 {
+        // Family name first (e.g. `DURAND Jean`)
+        let (family_name, given_names) = names.split_at(leading);
+        return (given_names, family_name);
+    }
+
+    // Family name last, or the last word when the case does not tell
+    let mut start = match trailing {
+        0 => names.len().saturating_sub(1),
+        trailing if trailing == names.len() => names.len() - 1,
+        trailing => names.len() - trailing,
+    };
+    // A particle before the family name belongs to it
+    while start > 0 && FAMILY_NAME_PARTICLES.contains(&fold(names[start - 1]).as_str()) {
+        start -= 1;
+    }
+
+    names.split_at(start)
+}
+
+/// Whether a word has letters, all of them uppercase
+fn is_uppercase(word: &str) -> bool {
+    word.chars().any(char::is_alphabetic) && !word.chars().any(char::is_lowercase)
+}
+
+fn join_words(words: &[&str]) -> Option<String> {
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person(civility: Option<Civility>, given_names: &str, family_name: &str) -> Recipient {
+        Recipient {
+            civility,
+            given_names: Some(given_names.to_string()),
+            family_name: Some(family_name.to_string()),
+            company_name: None,
+        }
+    }
+
+    #[test]
+    fn parse_reads_civility_given_names_and_uppercase_family_name() {
+        assert_eq!(
+            Recipient::parse("Monsieur Jean DURAND"),
+            person(Some(Civility::Monsieur), "Jean", "DURAND")
+        );
+        assert_eq!(
+            Recipient::parse("Mme Anne-Marie Louise D'ARC"),
+            person(Some(Civility::Madame), "Anne-Marie Louise", "D'ARC")
+        );
+        assert_eq!(
+            Recipient::parse("M. et Mme Jean DURAND"),
+            person(Some(Civility::MonsieurEtMadame), "Jean", "DURAND")
+        );
+    }
+
+    #[test]
+    fn parse_reads_family_name_first_and_keeps_particles() {
+        assert_eq!(
+            Recipient::parse("DURAND Jean"),
+            person(None, "Jean", "DURAND")
+        );
+        assert_eq!(
+            Recipient::parse("M. Charles de GAULLE"),
+            person(Some(Civility::Monsieur), "Charles", "de GAULLE")
+        );
+    }
+
+    #[test]
+    fn parse_takes_last_word_as_family_name_when_case_does_not_tell() {
+        assert_eq!(Recipient::parse("John Doe"), person(None, "John", "Doe"));
+        assert_eq!(
+            Recipient::parse("MADAME ANNE DURAND"),
+            person(Some(Civility::Madame), "ANNE", "DURAND")
+        );
+        assert_eq!(
+            Recipient::parse("DURAND"),
+            Recipient {
+                family_name: Some("DURAND".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_reads_company_name_whole() {
+        let recipient = Recipient::parse("Société  DUPONT SARL");
+
+        assert!(recipient.is_company());
+        assert_eq!(
+            recipient.company_name,
+            Some("Société DUPONT SARL".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_reads_legal_form_only_as_first_or_last_word() {
+        assert!(Recipient::parse("ETS DUPONT").is_company());
+        assert!(Recipient::parse("DUPONT & Cie").is_company());
+
+        let recipient = Recipient::parse("Madame Ana SA PEREIRA");
+
+        assert!(!recipient.is_company());
+        assert_eq!(recipient.family_name, Some("SA PEREIRA".to_string()));
+    }
+
+    #[test]
+    fn parse_reads_mm_as_messieurs() {
+        let recipient = Recipient::parse("MM DUPONT et MARTIN");
+
+        assert_eq!(recipient.civility, Some(Civility::Messieurs));
+        assert_eq!(
+            recipient.to_string(),
+            "Messieurs DUPONT et MARTIN".to_string()
+        );
+    }
+
+    #[test]
+    fn display_renders_full_civility_and_uppercase_family_name() {
+        assert_eq!(
+            Recipient::parse("mme anne de la fontaine").to_string(),
+            "Madame anne DE LA FONTAINE"
+        );
+        assert_eq!(Recipient::parse("DURAND Jean").to_string(), "Jean DURAND");
+        assert_eq!(
+            Recipient::parse("MR & MME DURAND").to_string(),
+            "Monsieur et Madame DURAND"
+        );
+    }
+
+    #[test]
+    fn display_prefers_company_name_to_person() {
+        let recipient = Recipient {
+            company_name: Some("ACME SARL".to_string()),
+            ..person(Some(Civility::Madame), "Anne", "Durand")
+        };
+
+        assert_eq!(recipient.to_string(), "ACME SARL");
+        assert_eq!(
+            recipient.person_name(),
+            Some("Madame Anne DURAND".to_string())
+        );
+    }
+}
@@ -4,7 +4,20 @@ use anyhow::{Context, anyhow};
 use celes::Country;
 use serde::{Deserialize, Serialize};
 
-use crate::{AnyhowError, AnyhowResult, domain::Address};
+use crate::{
+    AnyhowError, AnyhowResult,
+    domain::{
+        Address, AddressType, ConversionReport, Converted, FrenchDepartment, FrenchStreetLine,
+        Recipient, RecipientKind, recipient::rendered_name,
+    },
+};
+
+use super::{
+    french_care_of::{care_of_mention, split_care_of},
+    french_country::{DOMESTIC_COUNTRY, french_country_name, parse_country},
+    french_delivery_point::FrenchDeliveryPoint,
+    french_distribution::{has_post_box, split_cedex, split_post_box},
+};
 
 type TownName = String;
 type PostCode = String;
@@ -30,7 +43,8 @@ pub struct FrenchAddress {
     /// Line 6: Postal code and destination locality
     pub postal_info: String,
 
-    /// Line 7: Country name
+    /// Line 7: Country name, left empty for domestic mail
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub country: String,
 }
 
@@ -75,57 +89,359 @@ impl Display for FrenchAddress {
             writeln!(f, "{}", special_mentions)?;
         }
 
-        writeln!(f, "{}", self.postal_info)?;
-        write!(f, "{}", self.country)
+        write!(f, "{}", self.postal_info)?;
+
+        if !self.country.is_empty() {
+            write!(f, "\n{}", self.country)?;
+        }
+
+        Ok(())
     }
 }
 
-impl TryFrom<FrenchAddress> for Address {
-    type Error = AnyhowError;
+impl FrenchAddress {
+    /// Address type implied by the lines: a post box without street (`PBOX`), or else a company on line 1 (`BIZZ`)
+    pub fn address_type(&self) -> Option<AddressType> {
+        let is_post_box = self.street.trim().is_empty()
+            && self.special_mentions.as_deref().is_some_and(has_post_box);
 
-    fn try_from(french_address: FrenchAddress) -> AnyhowResult<Address> {
-        let (post_code, town_name) = split_postal_info(french_address.postal_info)
-            .with_context(|| "Failed to split postal info")?;
+        if is_post_box {
+            Some(AddressType::PostBox)
+        } else if self.recipient_kind() == RecipientKind::Business {
+            Some(AddressType::Business)
+        } else {
+            None
+        }
+    }
+
+    /// Kind of recipient implied by line 1: a business when it names a company, or else a person
+    pub fn recipient_kind(&self) -> RecipientKind {
+        if Recipient::parse(&self.name).is_company() {
+            RecipientKind::Business
+        } else {
+            RecipientKind::Person
+        }
+    }
+
+    /// Country of line 7, read in French, English or its own language or as an ISO code, France when the line is
+    /// empty
+    pub fn country_code(&self) -> AnyhowResult<String> {
+        if self.country.trim().is_empty() {
+            return Ok(DOMESTIC_COUNTRY.to_string());
+        }
+
+        parse_country(&self.country)
+            .map(|country| country.alpha2.to_string())
+            .ok_or_else(|| {
+                anyhow!("Unknown country '{}'", self.country)
+                    .context("Failed to convert country name to ISO code")
+            })
+    }
+
+    /// Whether the address is delivered within France, line 7 being empty or naming France
+    pub fn is_domestic(&self) -> bool {
+        self.country_code()
+            .is_ok_and(|country_code| country_code == DOMESTIC_COUNTRY)
+    }
+
+    /// Address without line 7 when it is delivered within France, as La Poste expects for domestic mail
+    pub fn without_domestic_country(self) -> FrenchAddress {
+        if self.is_domestic() {
+            FrenchAddress {
+                country: String::new(),
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+
+    /// Convert the address to the internal model and report the alterations made.
+    ///
+    /// A care-of mention starting line 2 (`Chez …`, `c/o …`) is mapped to the care-of party. The rest of line 2 of a
+    /// business is mapped to the department. The delivery point details of the other lines are read by their keyword
+    /// (`Appartement`, `Étage`, `Escalier`, `Entrée`, `Bâtiment`, ...), the text before any keyword being the room on
+    /// line 2 and the building name on line 3. The values read from lines 2, 3 and 5 are reported as unverified, the
+    /// reading being a guess.
+    pub fn into_internal(self) -> AnyhowResult<Converted<Address>> {
+        let address_type = self.address_type();
+        let recipient_kind = self.recipient_kind();
+        let country_code = self.country_code()?;
+        let (post_code, town_name) =
+            split_postal_info(self.postal_info).with_context(|| "Failed to split postal info")?;
+        let (town_name, cedex) = split_cedex(&town_name);
+
+        let (post_box, town_location_name) = self
+            .special_mentions
+            .as_deref()
+            .map(split_post_box)
+            .unwrap_or_default();
 
-        let country_code = Country::from_name(french_address.country)
-            .map_err(|e| anyhow!(e).context("Failed to convert country name to ISO code"))?
-            .alpha2
-            .to_string();
+        let street = FrenchStreetLine::parse(&self.street);
+
+        let (care_of, department, point) = read_delivery_lines(
+            recipient_kind,
+            self.recipient_info.as_deref(),
+            self.geographic_info.as_deref(),
+        );
+
+        // The details of lines 2, 3 and 5 are read by their keyword or position, which may guess the wrong field
+        let mut report = ConversionReport::default();
+        for (field, value) in [
+            ("care_of", &care_of),
+            ("department", &department),
+            ("unit", &point.unit),
+            ("room", &point.room),
+            ("floor", &point.floor),
+            ("staircase", &point.staircase),
+            ("entrance", &point.entrance),
+            ("building_name", &point.building_name),
+        ] {
+            let Some(value) = value else {
+                continue;
+            };
+            let line = if self
+                .recipient_info
+                .as_deref()
+                .is_some_and(|line| line.contains(value.as_str()))
+            {
+                2
+            } else {
+                3
+            };
+            report.unverified(field, format!("'{value}' read from line {line}"));
+        }
+        if let Some(post_box) = &post_box {
+            report.unverified("post_box", format!("'{post_box}' read from line 5"));
+        }
 
         let address = Address {
-            name: Some(french_address.name),
-            floor: french_address.geographic_info,
-            post_box: None,
-            room: french_address.recipient_info,
-            street_name: french_address.street,
+            name: Some(self.name),
+            care_of,
+            address_type,
+            department,
+            floor: point.floor,
+            unit: point.unit,
+            staircase: point.staircase,
+            entrance: point.entrance,
+            post_box,
+            room: point.room,
+            building_name: point.building_name,
+            street_name: street.street_name,
+            building_number: street.building_number,
             town_name,
-            town_location_name: french_address.special_mentions,
+            cedex,
+            town_location_name,
             post_code,
             country: country_code,
+            ..Default::default()
         };
 
-        Ok(address)
+        Ok(Converted::new(address, report))
     }
-}
 
-impl TryFrom<Address> for FrenchAddress {
-    type Error = AnyhowError;
+    /// Convert an internal address to the French format and report the alterations made.
+    ///
+    /// Line 1 is rendered from the recipient, with the full civility and the family name in uppercase. Line 2 starts
+    /// with the care-of party (`Chez …`). A business then has the person and departments reached on line 2 and the
+    /// whole delivery point on line 3, and a person the unit, room, floor and staircase on line 2 and the entrance and
+    /// building on line 3, in the NF Z10-011 order. Line 7 is the country name in French capitals (e.g. `ALLEMAGNE`).
+    /// Unlike [TryFrom], a missing name does not fail: line 1 falls back to the department, or is left empty.
+    pub fn from_internal(address: Address) -> AnyhowResult<Converted<FrenchAddress>> {
+        let mut report = ConversionReport::default();
 
-    fn try_from(address: Address) -> AnyhowResult<FrenchAddress> {
         let country = Country::from_alpha2(&address.country)
             .map_err(|e| anyhow!(e).context("Failed to convert country name to ISO code"))?;
+        let recipient = address.recipient();
+        let recipient_kind = address.recipient_kind();
+        let address_type = address.address_type;
+
+        let person_name = recipient
+            .as_ref()
+            .filter(|recipient| recipient.is_company())
+            .and_then(Recipient::person_name);
+
+        let mut department = address.department;
+        let name = match recipient
+            .map(|recipient| recipient.to_string())
+            .filter(|name| !name.is_empty())
+        {
+            Some(rendered) => {
+                let name = rendered_name(address.name.as_deref(), rendered);
+                if let Some(given) = address.name.as_ref().filter(|given| **given != name) {
+                    report.moved("name", format!("'{given}' rendered as '{name}' on line 1"));
+                }
+                name
+            }
+            None => match department.take() {
+                Some(department) => {
+                    report.moved("department", format!("'{department}' used as line 1"));
+                    department
+                }
+                None => {
+                    report
+                        .synthesized("name", "line 1 left empty as the recipient name is missing");
+                    String::new()
+                }
+            },
+        };
 
-        let postal_info = format!("{} {}", address.post_code, address.town_name);
+        let point = FrenchDeliveryPoint {
+            unit: address.unit,
+            room: address.room,
+            floor: address.floor,
+            staircase: address.staircase,
+            entrance: address.entrance,
+            building_name: address.building_name,
+        };
+        let reached = match recipient_kind {
+            // Line 2 reaches a person or a department within the company
+            RecipientKind::Business => {
+                for (field, value) in [
+                    ("recipient", &person_name),
+                    ("sub_department", &address.sub_department),
+                ] {
+                    if let Some(value) = value {
+                        report.moved(field, format!("'{value}' merged into line 2"));
+                    }
+                }
+
+                join_non_empty([person_name, department, address.sub_department])
+            }
+            RecipientKind::Person => {
+                if let Some(person_name) = person_name {
+                    report.dropped(
+                        "recipient",
+                        format!("'{person_name}' has no French address line"),
+                    );
+                }
+                for (field, value) in [
+                    ("department", department),
+                    ("sub_department", address.sub_department),
+                ] {
+                    if let Some(value) = value {
+                        report.dropped(field, format!("'{value}' has no French address line"));
+                    }
+                }
+
+                None
+            }
+        };
+        let (recipient_info, geographic_info) =
+            write_delivery_lines(recipient_kind, address.care_of.as_deref(), reached, &point);
+
+        // A value that is not read back into its own field from the lines is merged with its neighbours
+        let (care_of, _, read_point) = read_delivery_lines(
+            recipient_kind,
+            recipient_info.as_deref(),
+            geographic_info.as_deref(),
+        );
+        for (field, value, read_value) in [
+            ("care_of", address.care_of, care_of),
+            ("unit", point.unit, read_point.unit),
+            ("room", point.room, read_point.room),
+            ("floor", point.floor, read_point.floor),
+            ("staircase", point.staircase, read_point.staircase),
+            ("entrance", point.entrance, read_point.entrance),
+            (
+                "building_name",
+                point.building_name,
+                read_point.building_name,
+            ),
+        ] {
+            let Some(value) = value.filter(|value| Some(value) != read_value.as_ref()) else {
+                continue;
+            };
+            let line = if recipient_info
+                .as_deref()
+                .is_some_and(|line| line.contains(value.as_str()))
+            {
+                2
+            } else {
+                3
+            };
+            report.moved(field, format!("'{value}' merged into line {line}"));
+        }
+
+        // A subdivision derived from the postal code is not lost, it is derived again on the way back
+        let derived_sub_division = FrenchDepartment::from_post_code(&address.post_code)
+            .map(|department| department.iso_code);
+        let country_sub_division = address
+            .country_sub_division
+            .filter(|sub_division| Some(sub_division.as_str()) != derived_sub_division);
 
-        Ok(FrenchAddress::new(
-            address.name.ok_or(anyhow!("Name is required"))?,
-            address.room,
-            address.floor,
-            address.street_name,
-            address.town_location_name,
+        for (field, value) in [
+            ("district_name", address.district_name),
+            ("country_sub_division", country_sub_division),
+        ] {
+            if let Some(value) = value {
+                report.dropped(field, format!("'{value}' has no French address line"));
+            }
+        }
+
+        for line in &address.address_lines {
+            report.dropped(
+                "address_lines",
+                format!("'{line}' has no French address line"),
+            );
+        }
+
+        let postal_info = join_non_empty([
+            Some(address.post_code),
+            Some(address.town_name),
+            address.cedex,
+        ])
+        .unwrap_or_default();
+        let special_mentions = join_non_empty([address.post_box, address.town_location_name]);
+        let street = FrenchStreetLine::new(address.building_number, address.street_name);
+
+        let french_address = FrenchAddress::new(
+            name,
+            recipient_info,
+            geographic_info,
+            street.to_string(),
+            special_mentions,
             postal_info,
-            country.long_name.to_string(),
-        ))
+            french_country_name(&country),
+        );
+
+        // An address type implied by the lines is not lost, it is inferred again on the way back
+        let implied_type = french_address.address_type();
+        if let Some(address_type) = address_type.filter(|kind| Some(kind) != implied_type.as_ref())
+        {
+            report.dropped(
+                "address_type",
+                format!("'{address_type}' has no French address line"),
+            );
+        }
+        if recipient_kind != french_address.recipient_kind() {
+            report.dropped(
+                "recipient_kind",
+                format!("'{recipient_kind}' is not implied by line 1"),
+            );
+        }
+
+        Ok(Converted::new(french_address, report))
+    }
+}
+
+impl TryFrom<FrenchAddress> for Address {
+    type Error = AnyhowError;
+
+    fn try_from(french_address: FrenchAddress) -> AnyhowResult<Address> {
+        Ok(french_address.into_internal()?.address)
+    }
+}
+
+impl TryFrom<Address> for FrenchAddress {
+    type Error = AnyhowError;
+
+    fn try_from(address: Address) -> AnyhowResult<FrenchAddress> {
+        if address.recipient().is_none() {
+            return Err(anyhow!("Name is required"));
+        }
+
+        Ok(FrenchAddress::from_internal(address)?.address)
     }
 }
 
@@ -140,8 +456,95 @@ fn split_postal_info(postal_info: String) -> AnyhowResult<(PostCode, TownName)>
     Ok((post_code, town_name))
 }
 
+/// Read lines 2 and 3 into the care-of party, the department reached within a business and the delivery point
+fn read_delivery_lines(
+    recipient_kind: RecipientKind,
+    recipient_info: Option<&str>,
+    geographic_info: Option<&str>,
+) -> (Option<String>, Option<String>, FrenchDeliveryPoint) {
+    let (care_of, recipient_info) = recipient_info.map(split_care_of).unwrap_or_default();
+
+    let (mut point, other) = geographic_info
+        .map(FrenchDeliveryPoint::parse)
+        .unwrap_or_default();
+    point.building_name = join_non_empty([other, point.building_name.take()]);
+
+    match recipient_kind {
+        RecipientKind::Business => (care_of, recipient_info, point),
+        RecipientKind::Person => {
+            let (mut line_2_point, other) = recipient_info
+                .as_deref()
+                .map(FrenchDeliveryPoint::parse)
+                .unwrap_or_default();
+            line_2_point.room = join_non_empty([other, line_2_point.room.take()]);
+            line_2_point.merge(point);
+
+            (care_of, None, line_2_point)
+        }
+    }
+}
+
+/// Write the care-of party, the department reached within a business and the delivery point into lines 2 and 3.
+///
+/// The room and the building name are read back from the text before any keyword, so they come first when they have
+/// no keyword of their own.
+fn write_delivery_lines(
+    recipient_kind: RecipientKind,
+    care_of: Option<&str>,
+    reached: Option<String>,
+    point: &FrenchDeliveryPoint,
+) -> (Option<String>, Option<String>) {
+    let care_of = care_of.map(care_of_mention);
+    let [unit, room, floor, staircase] = point.inside();
+    let [entrance, building_name] = point.outside();
+
+    let (room, free_room) = split_free_text(room);
+    let (building_name, free_building_name) = split_free_text(building_name);
+
+    match recipient_kind {
+        RecipientKind::Business => (
+            join_non_empty([care_of, reached]),
+            join_non_empty([
+                free_building_name,
+                unit,
+                room,
+                free_room,
+                floor,
+                staircase,
+                entrance,
+                building_name,
+            ]),
+        ),
+        RecipientKind::Person => (
+            join_non_empty([care_of, free_room, unit, room, floor, staircase]),
+            join_non_empty([free_building_name, entrance, building_name]),
+        ),
+    }
+}
+
+/// Tell apart a value starting with a delivery point keyword from a free text one, returned second
+fn split_free_text(value: Option<String>) -> (Option<String>, Option<String>) {
+    match value {
+        Some(value) if FrenchDeliveryPoint::parse(&value).1.is_some() => (None, Some(value)),
+        value => (value, None),
+    }
+}
+
+/// Join the non-empty parts with a space, or return `None` when there is nothing to join
+pub(super) fn join_non_empty<const N: usize>(parts: [Option<String>; N]) -> Option<String> {
+    let parts: Vec<String> = parts
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.trim().is_empty())
+        .collect();
+
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::{AlterationKind, domain::Civility};
+
     use super::*;
 
     #[test]
@@ -177,7 +580,7 @@ mod tests {
             "25D RUE DES FLEURS".to_string(),
             None,
             "33500 LIBOURNE".to_string(),
-            "France".to_string(),
+            "FRANCE".to_string(),
         );
 
         let internal_address: Address = initial_french_address.clone().try_into().unwrap();
@@ -189,11 +592,13 @@ mod tests {
                 floor: None,
                 post_box: None,
                 room: None,
-                street_name: "25D RUE DES FLEURS".to_string(),
+                street_name: "RUE DES FLEURS".to_string(),
+                building_number: Some("25D".to_string()),
                 town_name: "LIBOURNE".to_string(),
                 town_location_name: None,
                 post_code: "33500".to_string(),
                 country: "FR".to_string(),
+                ..Default::default()
             }
         );
 
@@ -211,7 +616,7 @@ mod tests {
             "25 RUE DE L’EGLISE".to_string(),
             Some("CAUDOS".to_string()),
             "33380 MIOS".to_string(),
-            "France".to_string(),
+            "FRANCE".to_string(),
         );
 
         let internal: Address = initial_french_address.clone().try_into().unwrap();
@@ -220,14 +625,17 @@ mod tests {
             internal,
             Address {
                 name: Some("Monsieur Jean DELHOURME".to_string()),
-                floor: Some("Entrée A Bâtiment Jonquille".to_string()),
-                post_box: None,
-                room: Some("Chez Mireille COPEAU Appartement 2".to_string()),
-                street_name: "25 RUE DE L’EGLISE".to_string(),
+                care_of: Some("Mireille COPEAU".to_string()),
+                unit: Some("Appartement 2".to_string()),
+                entrance: Some("Entrée A".to_string()),
+                building_name: Some("Bâtiment Jonquille".to_string()),
+                street_name: "RUE DE L’EGLISE".to_string(),
+                building_number: Some("25".to_string()),
                 town_name: "MIOS".to_string(),
                 town_location_name: Some("CAUDOS".to_string()),
                 post_code: "33380".to_string(),
                 country: "FR".to_string(),
+                ..Default::default()
             },
         );
 
@@ -248,9 +656,513 @@ mod tests {
             town_location_name: None,
             post_code: "33500".to_string(),
             country: "FR".to_string(),
+            ..Default::default()
         };
 
         let result: AnyhowResult<FrenchAddress> = internal.try_into();
         assert!(result.is_err());
     }
+
+    #[test]
+    fn convert_internal_to_french_address_places_building_number_and_name() {
+        let internal = Address {
+            name: Some("Monsieur Jean DURAND".to_string()),
+            floor: Some("Etage 3".to_string()),
+            street_name: "RUE DES FLEURS".to_string(),
+            building_number: Some("25D".to_string()),
+            building_name: Some("Résidence Les Pins".to_string()),
+            town_name: "LIBOURNE".to_string(),
+            post_code: "33500".to_string(),
+            country: "FR".to_string(),
+            ..Default::default()
+        };
+
+        let french_address: FrenchAddress = internal.try_into().unwrap();
+
+        assert_eq!(french_address.recipient_info, Some("Etage 3".to_string()));
+        assert_eq!(
+            french_address.geographic_info,
+            Some("Résidence Les Pins".to_string())
+        );
+        assert_eq!(french_address.street, "25D RUE DES FLEURS".to_string());
+    }
+
+    #[test]
+    fn from_internal_renders_line_1_from_the_recipient() {
+        let internal = Address {
+            name: Some("mme DURAND Anne".to_string()),
+            ..Address::dummy()
+        };
+        assert_eq!(
+            FrenchAddress::from_internal(internal).unwrap().address.name,
+            "Madame Anne DURAND".to_string()
+        );
+
+        let internal = Address {
+            name: None,
+            recipient: Some(Recipient {
+                civility: Some(Civility::MonsieurEtMadame),
+                family_name: Some("Delhourme".to_string()),
+                ..Default::default()
+            }),
+            ..Address::dummy()
+        };
+        let french_address: FrenchAddress = internal.try_into().unwrap();
+        assert_eq!(
+            french_address.name,
+            "Monsieur et Madame DELHOURME".to_string()
+        );
+    }
+
+    #[test]
+    fn from_internal_keeps_line_1_or_reports_its_rendering() {
+        let unchanged = Address {
+            name: Some("John Doe".to_string()),
+            ..Address::dummy()
+        };
+        let converted = FrenchAddress::from_internal(unchanged).unwrap();
+        assert_eq!(converted.address.name, "John Doe".to_string());
+        assert!(converted.report.is_empty());
+
+        let reordered = Address {
+            name: Some("mme DURAND Anne".to_string()),
+            ..Address::dummy()
+        };
+        let converted = FrenchAddress::from_internal(reordered).unwrap();
+        assert_eq!(converted.address.name, "Madame Anne DURAND".to_string());
+        assert_eq!(converted.report.warnings[0].kind, AlterationKind::Moved);
+        assert_eq!(converted.report.warnings[0].field, "name".to_string());
+        assert!(converted.report.is_lossless());
+    }
+
+    #[test]
+    fn from_internal_reports_dropped_fields() {
+        let internal = Address {
+            name: Some("Monsieur Jean DURAND".to_string()),
+            department: Some("Service Comptabilité".to_string()),
+            post_box: Some("BP 40122".to_string()),
+            street_name: "RUE DES FLEURS".to_string(),
+            building_number: Some("25D".to_string()),
+            town_name: "LIBOURNE".to_string(),
+            post_code: "33500".to_string(),
+            country: "FR".to_string(),
+            ..Default::default()
+        };
+
+        let converted = FrenchAddress::from_internal(internal).unwrap();
+
+        let fields: Vec<(AlterationKind, &str)> = converted
+            .report
+            .warnings
+            .iter()
+            .map(|warning| (warning.kind, warning.field.as_str()))
+            .collect();
+        assert_eq!(fields, vec![(AlterationKind::Dropped, "department")]);
+        assert!(!converted.report.is_lossless());
+    }
+
+    #[test]
+    fn from_internal_does_not_report_country_sub_division_derived_from_postal_code() {
+        let derived = Address {
+            post_code: "33500".to_string(),
+            country_sub_division: Some("FR-33".to_string()),
+            ..Address::dummy()
+        };
+        let other = Address {
+            country_sub_division: Some("Aquitaine".to_string()),
+            ..derived.clone()
+        };
+
+        assert!(
+            FrenchAddress::from_internal(derived)
+                .unwrap()
+                .report
+                .is_empty()
+        );
+        assert_eq!(
+            FrenchAddress::from_internal(other).unwrap().report.warnings[0].field,
+            "country_sub_division"
+        );
+    }
+
+    #[test]
+    fn address_type_infers_post_box_and_business_addresses() {
+        let post_box = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            None,
+            "".to_string(),
+            Some("TSA 70011".to_string()),
+            "33506 LIBOURNE CEDEX".to_string(),
+            "FRANCE".to_string(),
+        );
+        let business = FrenchAddress {
+            name: "ACME SARL".to_string(),
+            street: "25 RUE DES FLEURS".to_string(),
+            special_mentions: None,
+            ..post_box.clone()
+        };
+        let person = FrenchAddress {
+            name: "Monsieur Jean DURAND".to_string(),
+            ..business.clone()
+        };
+
+        assert_eq!(post_box.address_type(), Some(AddressType::PostBox));
+        assert_eq!(business.address_type(), Some(AddressType::Business));
+        assert_eq!(person.address_type(), None);
+    }
+
+    #[test]
+    fn from_internal_reports_address_type_only_when_not_implied_by_the_lines() {
+        let business = Address {
+            name: Some("ACME SARL".to_string()),
+            address_type: Some(AddressType::Business),
+            ..Address::dummy()
+        };
+        let home = Address {
+            address_type: Some(AddressType::Residential),
+            ..Address::dummy()
+        };
+
+        assert!(
+            FrenchAddress::from_internal(business)
+                .unwrap()
+                .report
+                .is_empty()
+        );
+        assert_eq!(
+            FrenchAddress::from_internal(home).unwrap().report.warnings[0].detail,
+            "'HOME' has no French address line".to_string()
+        );
+    }
+
+    #[test]
+    fn from_internal_synthesizes_empty_name_when_missing() {
+        let internal = Address {
+            name: None,
+            ..Address::dummy()
+        };
+
+        let converted = FrenchAddress::from_internal(internal).unwrap();
+
+        assert_eq!(converted.address.name, "".to_string());
+        assert_eq!(
+            converted.report.warnings[0].kind,
+            AlterationKind::Synthesized
+        );
+    }
+
+    #[test]
+    fn from_internal_uses_department_as_name_when_missing() {
+        let internal = Address {
+            name: None,
+            department: Some("SERVICE CLIENTS".to_string()),
+            ..Address::dummy()
+        };
+
+        let converted = FrenchAddress::from_internal(internal).unwrap();
+
+        assert_eq!(converted.address.name, "SERVICE CLIENTS".to_string());
+        assert!(converted.report.is_lossless());
+    }
+
+    #[test]
+    fn convert_business_address_maps_line_2_to_department_and_back() {
+        let french_address = FrenchAddress::new(
+            "ACME SARL".to_string(),
+            Some("Service Comptabilité".to_string()),
+            Some("Bâtiment B".to_string()),
+            "25 RUE DES FLEURS".to_string(),
+            None,
+            "33500 LIBOURNE".to_string(),
+            "FRANCE".to_string(),
+        );
+
+        let internal = french_address.clone().into_internal().unwrap().address;
+
+        assert_eq!(internal.recipient_kind(), RecipientKind::Business);
+        assert_eq!(
+            internal.department,
+            Some("Service Comptabilité".to_string())
+        );
+        assert_eq!(internal.building_name, Some("Bâtiment B".to_string()));
+        assert_eq!(internal.room, None);
+
+        let converted = FrenchAddress::from_internal(internal).unwrap();
+        assert_eq!(converted.address, french_address);
+        assert!(converted.report.is_empty());
+    }
+
+    #[test]
+    fn convert_french_address_reads_delivery_point_and_rebuilds_lines_in_standard_order() {
+        let french_address = FrenchAddress::new(
+            "Madame Anne DURAND".to_string(),
+            Some("Escalier B 3e étage Appartement 12".to_string()),
+            Some("Bâtiment C Entrée A".to_string()),
+            "25 RUE DES FLEURS".to_string(),
+            None,
+            "33500 LIBOURNE".to_string(),
+            "FRANCE".to_string(),
+        );
+
+        let internal = french_address.into_internal().unwrap().address;
+
+        assert_eq!(internal.unit, Some("Appartement 12".to_string()));
+        assert_eq!(internal.floor, Some("3e étage".to_string()));
+        assert_eq!(internal.staircase, Some("Escalier B".to_string()));
+        assert_eq!(internal.entrance, Some("Entrée A".to_string()));
+        assert_eq!(internal.building_name, Some("Bâtiment C".to_string()));
+
+        let converted = FrenchAddress::from_internal(internal).unwrap();
+
+        assert_eq!(
+            converted.address.recipient_info,
+            Some("Appartement 12 3e étage Escalier B".to_string())
+        );
+        assert_eq!(
+            converted.address.geographic_info,
+            Some("Entrée A Bâtiment C".to_string())
+        );
+        assert!(converted.report.is_empty());
+    }
+
+    #[test]
+    fn from_internal_reports_values_not_read_back_into_their_field() {
+        let internal = Address {
+            floor: Some("3".to_string()),
+            building_name: Some("Les Pins".to_string()),
+            ..Address::dummy()
+        };
+
+        let converted = FrenchAddress::from_internal(internal).unwrap();
+
+        assert_eq!(converted.address.recipient_info, Some("3".to_string()));
+        assert_eq!(
+            converted.address.geographic_info,
+            Some("Les Pins".to_string())
+        );
+        let fields: Vec<&str> = converted
+            .report
+            .warnings
+            .iter()
+            .map(|warning| warning.field.as_str())
+            .collect();
+        assert_eq!(fields, vec!["floor"]);
+    }
+
+    #[test]
+    fn from_internal_merges_business_details_into_lines_2_and_3() {
+        let internal = Address {
+            name: None,
+            recipient: Some(Recipient {
+                civility: Some(Civility::Madame),
+                given_names: Some("Anne".to_string()),
+                family_name: Some("DURAND".to_string()),
+                company_name: Some("ACME".to_string()),
+            }),
+            recipient_kind: Some(RecipientKind::Business),
+            department: Some("Direction financière".to_string()),
+            sub_department: Some("Comptabilité".to_string()),
+            building_name: Some("Bâtiment B".to_string()),
+            floor: Some("Étage 2".to_string()),
+            ..Address::dummy()
+        };
+
+        let converted = FrenchAddress::from_internal(internal).unwrap();
+
+        assert_eq!(converted.address.name, "ACME".to_string());
+        assert_eq!(
+            converted.address.recipient_info,
+            Some("Madame Anne DURAND Direction financière Comptabilité".to_string())
+        );
+        assert_eq!(
+            converted.address.geographic_info,
+            Some("Étage 2 Bâtiment B".to_string())
+        );
+        // Without a legal form, line 1 does not tell a company on the way back
+        assert!(
+            converted
+                .report
+                .warnings
+                .iter()
+                .any(|warning| warning.field == "recipient_kind")
+        );
+    }
+
+    #[test]
+    fn convert_french_address_internal_with_post_box_and_cedex() {
+        let initial_french_address = FrenchAddress::new(
+            "SOCIETE DUPONT".to_string(),
+            None,
+            Some("Zone industrielle de la Ballastrierre Ouest".to_string()),
+            "22BIS RUE DES FLEURS".to_string(),
+            Some("BP 40122".to_string()),
+            "33506 LIBOURNE CEDEX 12".to_string(),
+            "FRANCE".to_string(),
+        );
+
+        let converted = initial_french_address.clone().into_internal().unwrap();
+
+        assert!(converted.report.is_lossless());
+        let fields: Vec<&str> = converted
+            .report
+            .warnings
+            .iter()
+            .map(|warning| warning.field.as_str())
+            .collect();
+        assert_eq!(fields, vec!["building_name", "post_box"]);
+        assert_eq!(
+            converted.address,
+            Address {
+                name: Some("SOCIETE DUPONT".to_string()),
+                address_type: Some(AddressType::Business),
+                post_box: Some("BP 40122".to_string()),
+                street_name: "RUE DES FLEURS".to_string(),
+                building_number: Some("22BIS".to_string()),
+                building_name: Some("Zone industrielle de la Ballastrierre Ouest".to_string()),
+                town_name: "LIBOURNE".to_string(),
+                cedex: Some("CEDEX 12".to_string()),
+                post_code: "33506".to_string(),
+                country: "FR".to_string(),
+                ..Default::default()
+            }
+        );
+
+        let french_address_from_internal: FrenchAddress = converted.address.try_into().unwrap();
+
+        assert_eq!(french_address_from_internal, initial_french_address);
+    }
+
+    #[test]
+    fn convert_french_address_internal_with_post_box_and_locality() {
+        let initial_french_address = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            None,
+            "".to_string(),
+            Some("BP 12 CAUDOS".to_string()),
+            "33380 MIOS".to_string(),
+            "FRANCE".to_string(),
+        );
+
+        let internal: Address = initial_french_address.clone().try_into().unwrap();
+
+        assert_eq!(internal.post_box, Some("BP 12".to_string()));
+        assert_eq!(internal.town_location_name, Some("CAUDOS".to_string()));
+
+        let french_address_from_internal: FrenchAddress = internal.try_into().unwrap();
+
+        assert_eq!(french_address_from_internal, initial_french_address);
+    }
+
+    #[test]
+    fn convert_french_address_reads_country_in_any_language_and_renders_it_in_french_capitals() {
+        for country in ["Allemagne", "Germany", "DEUTSCHLAND", "DEU", "276"] {
+            let french_address = FrenchAddress::new(
+                "Herrn Hans MÜLLER".to_string(),
+                None,
+                None,
+                "Hauptstraße 5".to_string(),
+                None,
+                "10115 BERLIN".to_string(),
+                country.to_string(),
+            );
+
+            let internal: Address = french_address.try_into().unwrap();
+            assert_eq!(internal.country, "DE".to_string());
+
+            let french_address: FrenchAddress = internal.try_into().unwrap();
+            assert_eq!(french_address.country, "ALLEMAGNE".to_string());
+        }
+    }
+
+    #[test]
+    fn convert_french_address_without_line_7_as_domestic() {
+        let french_address = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            None,
+            "25D RUE DES FLEURS".to_string(),
+            None,
+            "33500 LIBOURNE".to_string(),
+            "".to_string(),
+        );
+        assert_eq!(
+            french_address.to_string(),
+            "Monsieur Jean DURAND\n25D RUE DES FLEURS\n33500 LIBOURNE"
+        );
+
+        let internal: Address = french_address.clone().try_into().unwrap();
+        assert_eq!(internal.country, "FR".to_string());
+
+        let rendered: FrenchAddress = internal.try_into().unwrap();
+        assert_eq!(rendered.country, "FRANCE".to_string());
+        assert_eq!(rendered.without_domestic_country(), french_address);
+    }
+
+    #[test]
+    fn without_domestic_country_keeps_line_7_of_international_mail() {
+        let french_address = FrenchAddress::new(
+            "Mr John SMITH".to_string(),
+            None,
+            None,
+            "10 Downing Street".to_string(),
+            None,
+            "LONDON SW1A 2AA".to_string(),
+            "ROYAUME-UNI".to_string(),
+        );
+
+        assert_eq!(
+            french_address.clone().without_domestic_country(),
+            french_address
+        );
+    }
+
+    #[test]
+    fn into_internal_reports_details_read_from_lines_2_and_3() {
+        let french_address = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            Some("Chez Mireille COPEAU Appartement 2".to_string()),
+            Some("Entrée A Bâtiment Jonquille".to_string()),
+            "25 RUE DE L EGLISE".to_string(),
+            None,
+            "33380 MIOS".to_string(),
+            "FRANCE".to_string(),
+        );
+
+        let converted = french_address.into_internal().unwrap();
+
+        assert!(converted.report.is_lossless());
+        let warnings: Vec<(&str, &str)> = converted
+            .report
+            .warnings
+            .iter()
+            .inspect(|warning| assert_eq!(warning.kind, AlterationKind::Unverified))
+            .map(|warning| (warning.field.as_str(), warning.detail.as_str()))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                ("care_of", "'Mireille COPEAU' read from line 2"),
+                ("unit", "'Appartement 2' read from line 2"),
+                ("entrance", "'Entrée A' read from line 3"),
+                ("building_name", "'Bâtiment Jonquille' read from line 3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn into_internal_fails_on_unknown_country() {
+        let french_address = FrenchAddress::new(
+            "Mr John SMITH".to_string(),
+            None,
+            None,
+            "10 Downing Street".to_string(),
+            None,
+            "LONDON SW1A 2AA".to_string(),
+            "Atlantide".to_string(),
+        );
+
+        assert!(french_address.into_internal().is_err());
+    }
 }
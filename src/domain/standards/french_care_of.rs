@@ -0,0 +1,88 @@
+//! Care-of mention of French line 2 (`Chez Mireille COPEAU`, `c/o ACME`), naming whom the mail is delivered through
+
+use crate::domain::text::fold;
+
+use super::french_delivery_point::is_delivery_point_keyword;
+
+/// Mention written before the care-of party on line 2
+pub(crate) const CARE_OF_MENTION: &str = "Chez";
+
+/// Words introducing a care-of party, folded
+const CARE_OF_PREFIXES: [&[&str]; 3] = [&["CHEZ"], &["C/O"], &["AUX", "BONS", "SOINS", "DE"]];
+
+/// Split a line 2 into its care-of party and the delivery point that may follow it.
+///
+/// A line without a care-of prefix is returned whole as the delivery point.
+pub(crate) fn split_care_of(line: &str) -> (Option<String>, Option<String>) {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    let prefix_length = CARE_OF_PREFIXES
+        .iter()
+        .find(|prefix| {
+            prefix.len() < words.len()
+                && prefix
+                    .iter()
+                    .zip(&words)
+                    .all(|(prefix_word, word)| fold(word) == *prefix_word)
+        })
+        .map_or(0, |prefix| prefix.len());
+    if prefix_length == 0 {
+        return (None, join_words(&words));
+    }
+
+    let end = words[prefix_length..]
+        .iter()
+        .position(|word| is_delivery_point_keyword(word))
+        .map_or(words.len(), |position| prefix_length + position);
+
+    (
+        join_words(&words[prefix_length..end]),
+        join_words(&words[end..]),
+    )
+}
+
+/// Line 2 mention of a care-of party (e.g. `Chez Mireille COPEAU`)
+pub(crate) fn care_of_mention(care_of: &str) -> String {
+    format!("{CARE_OF_MENTION} {care_of}")
+}
+
+fn join_words(words: &[&str]) -> Option<String> {
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_care_of_separates_care_of_party_from_delivery_point() {
+        assert_eq!(
+            split_care_of("Chez Mireille COPEAU Appartement 2"),
+            (
+                Some("Mireille COPEAU".to_string()),
+                Some("Appartement 2".to_string())
+            )
+        );
+        assert_eq!(
+            split_care_of("c/o ACME SARL"),
+            (Some("ACME SARL".to_string()), None)
+        );
+        assert_eq!(
+            split_care_of("aux bons soins de Mme DURAND"),
+            (Some("Mme DURAND".to_string()), None)
+        );
+    }
+
+    #[test]
+    fn split_care_of_keeps_lines_without_prefix_whole() {
+        assert_eq!(
+            split_care_of("Appartement 2"),
+            (None, Some("Appartement 2".to_string()))
+        );
+        assert_eq!(split_care_of("Chez"), (None, Some("Chez".to_string())));
+        assert_eq!(
+            split_care_of("Chezmoi Appartement 2"),
+            (None, Some("Chezmoi Appartement 2".to_string()))
+        );
+    }
+}
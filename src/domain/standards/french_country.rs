@@ -0,0 +1,405 @@
+//! Country names of French line 7: rendered in French capitals (`ALLEMAGNE`, `ROYAUME-UNI`) and read in French,
+//! English or the native language, or as an ISO 3166 code
+
+use celes::Country;
+
+use crate::domain::text::{fold, strip_diacritics};
+
+/// ISO code of the country whose mail is domestic, and has no line 7
+pub(crate) const DOMESTIC_COUNTRY: &str = "FR";
+
+/// French name of every country, by ISO alpha-2 code, with the other names it is known by in French or in its own
+/// languages. English names are read by [celes].
+const COUNTRY_NAMES: [(&str, &str, &[&str]); 250] = [
+    ("AD", "Andorre", &["Andorra"]),
+    ("AE", "Émirats arabes unis", &[]),
+    ("AF", "Afghanistan", &[]),
+    ("AG", "Antigua-et-Barbuda", &[]),
+    ("AI", "Anguilla", &[]),
+    ("AL", "Albanie", &["Shqipëria"]),
+    ("AM", "Arménie", &["Hayastan"]),
+    ("AO", "Angola", &[]),
+    ("AQ", "Antarctique", &[]),
+    ("AR", "Argentine", &["Argentina"]),
+    ("AS", "Samoa américaines", &[]),
+    ("AT", "Autriche", &["Österreich"]),
+    ("AU", "Australie", &[]),
+    ("AW", "Aruba", &[]),
+    ("AX", "Îles Åland", &["Åland", "Ahvenanmaa"]),
+    ("AZ", "Azerbaïdjan", &["Azərbaycan"]),
+    ("BA", "Bosnie-Herzégovine", &["Bosna i Hercegovina"]),
+    ("BB", "Barbade", &[]),
+    ("BD", "Bangladesh", &[]),
+    ("BE", "Belgique", &["België", "Belgien"]),
+    ("BF", "Burkina Faso", &[]),
+    ("BG", "Bulgarie", &["България", "Bulgaria"]),
+    ("BH", "Bahreïn", &[]),
+    ("BI", "Burundi", &[]),
+    ("BJ", "Bénin", &[]),
+    ("BL", "Saint-Barthélemy", &[]),
+    ("BM", "Bermudes", &[]),
+    ("BN", "Brunei", &["Brunei Darussalam"]),
+    ("BO", "Bolivie", &["Bolivia"]),
+    (
+        "BQ",
+        "Bonaire, Saint-Eustache et Saba",
+        &["Caribisch Nederland"],
+    ),
+    ("BR", "Brésil", &["Brasil"]),
+    ("BS", "Bahamas", &[]),
+    ("BT", "Bhoutan", &[]),
+    ("BV", "Île Bouvet", &[]),
+    ("BW", "Botswana", &[]),
+    ("BY", "Biélorussie", &["Bélarus", "Беларусь"]),
+    ("BZ", "Belize", &[]),
+    ("CA", "Canada", &[]),
+    ("CC", "Îles Cocos", &[]),
+    ("CD", "République démocratique du Congo", &["RD Congo"]),
+    ("CF", "République centrafricaine", &["Centrafrique"]),
+    ("CG", "Congo", &["République du Congo"]),
+    ("CH", "Suisse", &["Schweiz", "Svizzera", "Svizra"]),
+    ("CI", "Côte d'Ivoire", &[]),
+    ("CK", "Îles Cook", &[]),
+    ("CL", "Chili", &["Chile"]),
+    ("CM", "Cameroun", &[]),
+    ("CN", "Chine", &["中国", "Zhongguo"]),
+    ("CO", "Colombie", &["Colombia"]),
+    ("CR", "Costa Rica", &[]),
+    ("CU", "Cuba", &[]),
+    ("CV", "Cap-Vert", &["Cabo Verde"]),
+    ("CW", "Curaçao", &["Kòrsou"]),
+    ("CX", "Île Christmas", &[]),
+    ("CY", "Chypre", &["Κύπρος", "Kıbrıs"]),
+    (
+        "CZ",
+        "Tchéquie",
+        &["République tchèque", "Česko", "Česká republika"],
+    ),
+    ("DE", "Allemagne", &["Deutschland"]),
+    ("DJ", "Djibouti", &[]),
+    ("DK", "Danemark", &["Danmark"]),
+    ("DM", "Dominique", &[]),
+    ("DO", "République dominicaine", &["República Dominicana"]),
+    ("DZ", "Algérie", &[]),
+    ("EC", "Équateur", &["Ecuador"]),
+    ("EE", "Estonie", &["Eesti"]),
+    ("EG", "Égypte", &[]),
+    ("EH", "Sahara occidental", &[]),
+    ("ER", "Érythrée", &[]),
+    ("ES", "Espagne", &["España"]),
+    ("ET", "Éthiopie", &[]),
+    ("FI", "Finlande", &["Suomi"]),
+    ("FJ", "Fidji", &[]),
+    ("FK", "Îles Malouines", &["Îles Falkland"]),
+    ("FM", "Micronésie", &[]),
+    ("FO", "Îles Féroé", &["Føroyar"]),
+    ("FR", "France", &[]),
+    ("GA", "Gabon", &[]),
+    ("GB", "Royaume-Uni", &["Grande-Bretagne"]),
+    ("GD", "Grenade", &[]),
+    ("GE", "Géorgie", &["Sakartvelo"]),
+    ("GF", "Guyane", &["Guyane française"]),
+    ("GG", "Guernesey", &[]),
+    ("GH", "Ghana", &[]),
+    ("GI", "Gibraltar", &[]),
+    ("GL", "Groenland", &["Kalaallit Nunaat"]),
+    ("GM", "Gambie", &[]),
+    ("GN", "Guinée", &[]),
+    ("GP", "Guadeloupe", &[]),
+    ("GQ", "Guinée équatoriale", &["Guinea Ecuatorial"]),
+    ("GR", "Grèce", &["Ελλάδα", "Hellas"]),
+    ("GS", "Géorgie du Sud-et-les îles Sandwich du Sud", &[]),
+    ("GT", "Guatemala", &[]),
+    ("GU", "Guam", &[]),
+    ("GW", "Guinée-Bissau", &["Guiné-Bissau"]),
+    ("GY", "Guyana", &[]),
+    ("HK", "Hong Kong", &[]),
+    ("HM", "Îles Heard-et-MacDonald", &[]),
+    ("HN", "Honduras", &[]),
+    ("HR", "Croatie", &["Hrvatska"]),
+    ("HT", "Haïti", &["Ayiti"]),
+    ("HU", "Hongrie", &["Magyarország"]),
+    ("ID", "Indonésie", &[]),
+    ("IE", "Irlande", &["Éire"]),
+    ("IL", "Israël", &[]),
+    ("IM", "Île de Man", &[]),
+    ("IN", "Inde", &["Bharat"]),
+    ("IO", "Territoire britannique de l'océan Indien", &[]),
+    ("IQ", "Irak", &[]),
+    ("IR", "Iran", &[]),
+    ("IS", "Islande", &["Ísland"]),
+    ("IT", "Italie", &["Italia"]),
+    ("JE", "Jersey", &[]),
+    ("JM", "Jamaïque", &[]),
+    ("JO", "Jordanie", &[]),
+    ("JP", "Japon", &["日本", "Nippon"]),
+    ("KE", "Kenya", &[]),
+    ("KG", "Kirghizistan", &[]),
+    ("KH", "Cambodge", &["Kampuchea"]),
+    ("KI", "Kiribati", &[]),
+    ("KM", "Comores", &[]),
+    (
+        "KN",
+        "Saint-Christophe-et-Niévès",
+        &["Saint-Kitts-et-Nevis"],
+    ),
+    ("KP", "Corée du Nord", &[]),
+    ("KR", "Corée du Sud", &[]),
+    ("KW", "Koweït", &[]),
+    ("KY", "Îles Caïmans", &[]),
+    ("KZ", "Kazakhstan", &[]),
+    ("LA", "Laos", &[]),
+    ("LB", "Liban", &[]),
+    ("LC", "Sainte-Lucie", &[]),
+    ("LI", "Liechtenstein", &[]),
+    ("LK", "Sri Lanka", &[]),
+    ("LR", "Liberia", &[]),
+    ("LS", "Lesotho", &[]),
+    ("LT", "Lituanie", &["Lietuva"]),
+    ("LU", "Luxembourg", &["Lëtzebuerg", "Luxemburg"]),
+    ("LV", "Lettonie", &["Latvija"]),
+    ("LY", "Libye", &[]),
+    ("MA", "Maroc", &[]),
+    ("MC", "Monaco", &[]),
+    ("MD", "Moldavie", &["Moldova"]),
+    ("ME", "Monténégro", &["Crna Gora"]),
+    ("MF", "Saint-Martin", &[]),
+    ("MG", "Madagascar", &[]),
+    ("MH", "Îles Marshall", &[]),
+    ("MK", "Macédoine du Nord", &["Северна Македонија"]),
+    ("ML", "Mali", &[]),
+    ("MM", "Myanmar", &["Birmanie"]),
+    ("MN", "Mongolie", &[]),
+    ("MO", "Macao", &[]),
+    ("MP", "Îles Mariannes du Nord", &[]),
+    ("MQ", "Martinique", &[]),
+    ("MR", "Mauritanie", &[]),
+    ("MS", "Montserrat", &[]),
+    ("MT", "Malte", &[]),
+    ("MU", "Maurice", &["Île Maurice"]),
+    ("MV", "Maldives", &[]),
+    ("MW", "Malawi", &[]),
+    ("MX", "Mexique", &["México"]),
+    ("MY", "Malaisie", &[]),
+    ("MZ", "Mozambique", &["Moçambique"]),
+    ("NA", "Namibie", &[]),
+    ("NC", "Nouvelle-Calédonie", &[]),
+    ("NE", "Niger", &[]),
+    ("NF", "Île Norfolk", &[]),
+    ("NG", "Nigeria", &[]),
+    ("NI", "Nicaragua", &[]),
+    ("NL", "Pays-Bas", &["Nederland", "Hollande"]),
+    ("NO", "Norvège", &["Norge", "Noreg"]),
+    ("NP", "Népal", &[]),
+    ("NR", "Nauru", &[]),
+    ("NU", "Niue", &[]),
+    ("NZ", "Nouvelle-Zélande", &["Aotearoa"]),
+    ("OM", "Oman", &[]),
+    ("PA", "Panama", &["Panamá"]),
+    ("PE", "Pérou", &["Perú"]),
+    ("PF", "Polynésie française", &[]),
+    ("PG", "Papouasie-Nouvelle-Guinée", &[]),
+    ("PH", "Philippines", &["Pilipinas"]),
+    ("PK", "Pakistan", &[]),
+    ("PL", "Pologne", &["Polska"]),
+    ("PM", "Saint-Pierre-et-Miquelon", &[]),
+    ("PN", "Îles Pitcairn", &[]),
+    ("PR", "Porto Rico", &["Puerto Rico"]),
+    ("PS", "Palestine", &[]),
+    ("PT", "Portugal", &[]),
+    ("PW", "Palaos", &[]),
+    ("PY", "Paraguay", &[]),
+    ("QA", "Qatar", &[]),
+    ("RE", "La Réunion", &["Réunion"]),
+    ("RO", "Roumanie", &["România"]),
+    ("RS", "Serbie", &["Србија", "Srbija"]),
+    ("RU", "Russie", &["Россия", "Rossiya"]),
+    ("RW", "Rwanda", &[]),
+    ("SA", "Arabie saoudite", &[]),
+    ("SB", "Îles Salomon", &[]),
+    ("SC", "Seychelles", &[]),
+    ("SD", "Soudan", &[]),
+    ("SE", "Suède", &["Sverige"]),
+    ("SG", "Singapour", &[]),
+    ("SH", "Sainte-Hélène, Ascension et Tristan da Cunha", &[]),
+    ("SI", "Slovénie", &["Slovenija"]),
+    ("SJ", "Svalbard et Jan Mayen", &[]),
+    ("SK", "Slovaquie", &["Slovensko"]),
+    ("SL", "Sierra Leone", &[]),
+    ("SM", "Saint-Marin", &["San Marino"]),
+    ("SN", "Sénégal", &[]),
+    ("SO", "Somalie", &["Soomaaliya"]),
+    ("SR", "Suriname", &[]),
+    ("SS", "Soudan du Sud", &[]),
+    ("ST", "Sao Tomé-et-Principe", &["São Tomé e Príncipe"]),
+    ("SV", "Salvador", &["El Salvador"]),
+    (
+        "SX",
+        "Saint-Martin (partie néerlandaise)",
+        &["Sint Maarten"],
+    ),
+    ("SY", "Syrie", &[]),
+    ("SZ", "Eswatini", &["Swaziland"]),
+    ("TC", "Îles Turques-et-Caïques", &[]),
+    ("TD", "Tchad", &[]),
+    (
+        "TF",
+        "Terres australes et antarctiques françaises",
+        &["TAAF"],
+    ),
+    ("TG", "Togo", &[]),
+    ("TH", "Thaïlande", &["Prathet Thai"]),
+    ("TJ", "Tadjikistan", &[]),
+    ("TK", "Tokelau", &[]),
+    ("TL", "Timor oriental", &["Timor-Leste"]),
+    ("TM", "Turkménistan", &[]),
+    ("TN", "Tunisie", &[]),
+    ("TO", "Tonga", &[]),
+    ("TR", "Turquie", &["Türkiye"]),
+    ("TT", "Trinité-et-Tobago", &[]),
+    ("TV", "Tuvalu", &[]),
+    ("TW", "Taïwan", &[]),
+    ("TZ", "Tanzanie", &[]),
+    ("UA", "Ukraine", &["Україна", "Ukraina"]),
+    ("UG", "Ouganda", &[]),
+    ("UM", "Îles mineures éloignées des États-Unis", &[]),
+    ("US", "États-Unis", &["États-Unis d'Amérique"]),
+    ("UY", "Uruguay", &[]),
+    ("UZ", "Ouzbékistan", &["Oʻzbekiston"]),
+    ("VA", "Vatican", &["Saint-Siège", "Città del Vaticano"]),
+    ("VC", "Saint-Vincent-et-les-Grenadines", &[]),
+    ("VE", "Venezuela", &[]),
+    ("VG", "Îles Vierges britanniques", &[]),
+    ("VI", "Îles Vierges des États-Unis", &[]),
+    ("VN", "Viêt Nam", &["Việt Nam"]),
+    ("VU", "Vanuatu", &[]),
+    ("WF", "Wallis-et-Futuna", &[]),
+    ("WS", "Samoa", &[]),
+    ("XK", "Kosovo", &["Kosova"]),
+    ("YE", "Yémen", &[]),
+    ("YT", "Mayotte", &[]),
+    ("ZA", "Afrique du Sud", &["Suid-Afrika"]),
+    ("ZM", "Zambie", &[]),
+    ("ZW", "Zimbabwe", &[]),
+];
+
+/// Country written on line 7: its name in French, English or its own language, or its ISO 3166 alpha-2, alpha-3 or
+/// numeric code, ignoring case, accents and punctuation (e.g. `Allemagne`, `Germany`, `DEUTSCHLAND`, `DE`, `DEU`,
+/// `276`)
+pub(crate) fn parse_country(text: &str) -> Option<Country> {
+    country_from_name(text).or_else(|| country_from_code(text))
+}
+
+/// Country named in French, English or its own language, ignoring case, accents and punctuation
+pub(crate) fn country_from_name(text: &str) -> Option<Country> {
+    let key = name_key(text);
+    if key.is_empty() {
+        return None;
+    }
+
+    let alpha2 = COUNTRY_NAMES
+        .iter()
+        .find(|(_, french_name, other_names)| {
+            name_key(french_name) == key || other_names.iter().any(|name| name_key(name) == key)
+        })
+        .map(|(alpha2, _, _)| *alpha2);
+    if let Some(alpha2) = alpha2 {
+        return Country::from_alpha2(alpha2).ok();
+    }
+
+    // celes knows the English names in lowercase, without spaces, some of them with a leading `the`
+    let key = key.to_lowercase();
+    Country::from_name(&key)
+        .or_else(|_| Country::from_alias(&key))
+        .or_else(|_| Country::from_name(format!("the{key}")))
+        .ok()
+}
+
+/// Line 7 of a country: its French name in unaccented capitals (e.g. `ALLEMAGNE`, `ETATS-UNIS`)
+pub(crate) fn french_country_name(country: &Country) -> String {
+    let name = COUNTRY_NAMES
+        .iter()
+        .find(|(alpha2, _, _)| alpha2.eq_ignore_ascii_case(country.alpha2))
+        .map_or(country.long_name, |(_, french_name, _)| french_name);
+
+    strip_diacritics(name).to_uppercase()
+}
+
+/// Country of an ISO 3166 alpha-2, alpha-3 or numeric code
+fn country_from_code(text: &str) -> Option<Country> {
+    let code = text.trim();
+
+    match code.len() {
+        2 if code.chars().all(|c| c.is_ascii_alphabetic()) => Country::from_alpha2(code).ok(),
+        3 if code.chars().all(|c| c.is_ascii_alphabetic()) => Country::from_alpha3(code).ok(),
+        3 if code.chars().all(|c| c.is_ascii_digit()) => Country::from_code(code).ok(),
+        _ => None,
+    }
+}
+
+/// Folded letters and digits of a name, so that `Royaume Uni`, `ROYAUME-UNI` and `royaume-uni` compare equal
+fn name_key(name: &str) -> String {
+    fold(name).chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alpha2(text: &str) -> Option<&'static str> {
+        parse_country(text).map(|country| country.alpha2)
+    }
+
+    #[test]
+    fn parse_country_reads_french_english_and_native_names() {
+        assert_eq!(alpha2("Allemagne"), Some("DE"));
+        assert_eq!(alpha2("Germany"), Some("DE"));
+        assert_eq!(alpha2("DEUTSCHLAND"), Some("DE"));
+        assert_eq!(alpha2("royaume uni"), Some("GB"));
+        assert_eq!(alpha2("United Kingdom"), Some("GB"));
+        assert_eq!(alpha2("ETATS-UNIS"), Some("US"));
+        assert_eq!(alpha2("Netherlands"), Some("NL"));
+        assert_eq!(alpha2("España"), Some("ES"));
+        assert_eq!(alpha2("Côte d’Ivoire"), Some("CI"));
+    }
+
+    #[test]
+    fn parse_country_reads_iso_codes() {
+        assert_eq!(alpha2("de"), Some("DE"));
+        assert_eq!(alpha2("DEU"), Some("DE"));
+        assert_eq!(alpha2("276"), Some("DE"));
+        assert_eq!(alpha2("250"), Some("FR"));
+    }
+
+    #[test]
+    fn parse_country_rejects_unknown_text() {
+        assert_eq!(alpha2(""), None);
+        assert_eq!(alpha2("Atlantide"), None);
+        assert_eq!(alpha2("ZZ"), None);
+        assert_eq!(alpha2("33500 LIBOURNE"), None);
+    }
+
+    #[test]
+    fn french_country_name_renders_french_capitals() {
+        let name = |code| french_country_name(&Country::from_alpha2(code).unwrap());
+
+        assert_eq!(name("DE"), "ALLEMAGNE");
+        assert_eq!(name("GB"), "ROYAUME-UNI");
+        assert_eq!(name("US"), "ETATS-UNIS");
+        assert_eq!(name("FO"), "ILES FEROE");
+        assert_eq!(name("FR"), "FRANCE");
+    }
+
+    #[test]
+    fn every_country_has_a_french_name() {
+        for country in Country::get_countries() {
+            assert!(
+                COUNTRY_NAMES
+                    .iter()
+                    .any(|(alpha2, _, _)| alpha2.eq_ignore_ascii_case(country.alpha2)),
+                "{} has no French name",
+                country.alpha2
+            );
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Delivery point details of French lines 2 and 3: unit, room, floor, staircase, entrance and building
+
+use crate::domain::text::fold;
+
+use super::french_address::join_non_empty;
+
+/// Element of the delivery point introduced by a keyword
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Element {
+    Unit,
+    Room,
+    Floor,
+    Staircase,
+    Entrance,
+    Building,
+}
+
+/// Keywords introducing each element, folded, with the La Poste abbreviations
+const KEYWORDS: [(&str, Element); 33] = [
+    ("APPARTEMENT", Element::Unit),
+    ("APP", Element::Unit),
+    ("APPT", Element::Unit),
+    ("APT", Element::Unit),
+    ("LOGEMENT", Element::Unit),
+    ("LOGT", Element::Unit),
+    ("STUDIO", Element::Unit),
+    ("PORTE", Element::Unit),
+    ("BAL", Element::Unit),
+    ("CHAMBRE", Element::Room),
+    ("BUREAU", Element::Room),
+    ("SALLE", Element::Room),
+    ("ETAGE", Element::Floor),
+    ("ETG", Element::Floor),
+    ("RDC", Element::Floor),
+    ("REZ-DE-CHAUSSEE", Element::Floor),
+    ("SOUS-SOL", Element::Floor),
+    ("ESCALIER", Element::Staircase),
+    ("ESC", Element::Staircase),
+    ("ENTREE", Element::Entrance),
+    ("HALL", Element::Entrance),
+    ("BATIMENT", Element::Building),
+    ("BAT", Element::Building),
+    ("IMMEUBLE", Element::Building),
+    ("IMM", Element::Building),
+    ("RESIDENCE", Element::Building),
+    ("RES", Element::Building),
+    ("TOUR", Element::Building),
+    ("VILLA", Element::Building),
+    ("PAVILLON", Element::Building),
+    ("LOTISSEMENT", Element::Building),
+    ("ZI", Element::Building),
+    ("ZA", Element::Building),
+];
+
+/// Suffixes of a French ordinal number (e.g. `3e`, `1er`, `2ème`)
+const ORDINAL_SUFFIXES: [&str; 5] = ["E", "EME", "ER", "ERE", "ND"];
+
+/// Delivery point details read from French lines 2 and 3
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct FrenchDeliveryPoint {
+    /// Apartment or unit (e.g. `Appartement 2`, `BAL 12`)
+    pub(crate) unit: Option<String>,
+
+    /// Room (e.g. `Bureau 12`)
+    pub(crate) room: Option<String>,
+
+    /// Floor (e.g. `Étage 3`, `3e étage`, `RDC`)
+    pub(crate) floor: Option<String>,
+
+    /// Staircase (e.g. `Escalier B`)
+    pub(crate) staircase: Option<String>,
+
+    /// Entrance (e.g. `Entrée A`)
+    pub(crate) entrance: Option<String>,
+
+    /// Building, residence or zone (e.g. `Bâtiment Jonquille`, `Résidence Les Pins`)
+    pub(crate) building_name: Option<String>,
+}
+
+impl FrenchDeliveryPoint {
+    /// Read the elements of a line, each one running from its keyword to the next keyword, and return apart the text
+    /// found before the first keyword
+    pub(crate) fn parse(line: &str) -> (FrenchDeliveryPoint, Option<String>) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        let mut starts: Vec<(usize, Element)> = Vec::new();
+        for (index, word) in words.iter().enumerate() {
+            let Some(element) = keyword_element(word) else {
+                continue;
+            };
+
+            // An ordinal belongs to the floor it numbers (e.g. `3e étage`)
+            let start = if element == Element::Floor && index > 0 && is_ordinal(words[index - 1]) {
+                index - 1
+            } else {
+                index
+            };
+            if starts.last().is_none_or(|(last, _)| *last < start) {
+                starts.push((start, element));
+            }
+        }
+
+        let mut point = FrenchDeliveryPoint::default();
+        let other_end = starts.first().map_or(words.len(), |(start, _)| *start);
+
+        for (position, (start, element)) in starts.iter().enumerate() {
+            let end = starts
+                .get(position + 1)
+                .map_or(words.len(), |(next, _)| *next);
+            let value = words[*start..end].join(" ");
+
+            let field = point.field_mut(*element);
+            *field = join_non_empty([field.take(), Some(value)]);
+        }
+
+        (point, join_non_empty([Some(words[..other_end].join(" "))]))
+    }
+
+    /// Add the elements of another line, joining the values found on both
+    pub(crate) fn merge(&mut self, other: FrenchDeliveryPoint) {
+        for (field, value) in [
+            (&mut self.unit, other.unit),
+            (&mut self.room, other.room),
+            (&mut self.floor, other.floor),
+            (&mut self.staircase, other.staircase),
+            (&mut self.entrance, other.entrance),
+            (&mut self.building_name, other.building_name),
+        ] {
+            *field = join_non_empty([field.take(), value]);
+        }
+    }
+
+    /// Elements within the building, in the NF Z10-011 order: unit, room, floor and staircase
+    pub(crate) fn inside(&self) -> [Option<String>; 4] {
+        [
+            self.unit.clone(),
+            self.room.clone(),
+            self.floor.clone(),
+            self.staircase.clone(),
+        ]
+    }
+
+    /// Elements outside the building, in the NF Z10-011 order: entrance and building
+    pub(crate) fn outside(&self) -> [Option<String>; 2] {
+        [self.entrance.clone(), self.building_name.clone()]
+    }
+
+    fn field_mut(&mut self, element: Element) -> &mut Option<String> {
+        match element {
+            Element::Unit => &mut self.unit,
+            Element::Room => &mut self.room,
+            Element::Floor => &mut self.floor,
+            Element::Staircase => &mut self.staircase,
+            Element::Entrance => &mut self.entrance,
+            Element::Building => &mut self.building_name,
+        }
+    }
+}
+
+/// Whether a word introduces a delivery point element (e.g. `Appartement`, `Bât.`)
+pub(crate) fn is_delivery_point_keyword(word: &str) -> bool {
+    keyword_element(word).is_some()
+}
+
+fn keyword_element(word: &str) -> Option<Element> {
+    let word = fold(word);
+    let word = word.trim_end_matches(['.', ',', ':']);
+
+    KEYWORDS
+        .iter()
+        .find(|(keyword, _)| *keyword == word)
+        .map(|(_, element)| *element)
+}
+
+fn is_ordinal(word: &str) -> bool {
+    let word = fold(word);
+    let digits = word.chars().take_while(char::is_ascii_digit).count();
+
+    digits > 0 && ORDINAL_SUFFIXES.contains(&&word[digits..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_splits_line_at_each_keyword() {
+        let (point, other) = FrenchDeliveryPoint::parse("Escalier B 3e étage Appartement 12");
+
+        assert_eq!(
+            point,
+            FrenchDeliveryPoint {
+                unit: Some("Appartement 12".to_string()),
+                floor: Some("3e étage".to_string()),
+                staircase: Some("Escalier B".to_string()),
+                ..Default::default()
+            }
+        );
+        assert_eq!(other, None);
+    }
+
+    #[test]
+    fn parse_reads_entrance_and_building_and_returns_leading_text() {
+        let (point, other) = FrenchDeliveryPoint::parse("Les Hauts Entrée A Bât. Jonquille");
+
+        assert_eq!(point.entrance, Some("Entrée A".to_string()));
+        assert_eq!(point.building_name, Some("Bât. Jonquille".to_string()));
+        assert_eq!(other, Some("Les Hauts".to_string()));
+
+        let (point, other) = FrenchDeliveryPoint::parse("Résidence Les Pins");
+        assert_eq!(point.building_name, Some("Résidence Les Pins".to_string()));
+        assert_eq!(other, None);
+    }
+
+    #[test]
+    fn merge_joins_elements_found_on_both_lines() {
+        let (mut point, _) = FrenchDeliveryPoint::parse("Appartement 2");
+        point.merge(FrenchDeliveryPoint::parse("Étage 3 Bâtiment C").0);
+
+        assert_eq!(
+            point.inside(),
+            [
+                Some("Appartement 2".to_string()),
+                None,
+                Some("Étage 3".to_string()),
+                None
+            ]
+        );
+        assert_eq!(point.outside(), [None, Some("Bâtiment C".to_string())]);
+    }
+}
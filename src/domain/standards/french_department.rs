@@ -0,0 +1,869 @@
+//! French departments and regions, derived from the postal code
+
+use std::fmt::{self, Display, Formatter};
+
+/// Prefix of the postal codes of Monaco, which La Poste serves although it is a different country
+const MONACO_POST_CODE_PREFIX: &str = "980";
+
+/// Region of France
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrenchRegion {
+    /// Name of the region
+    pub name: &'static str,
+
+    /// ISO 3166-2 code of the region (e.g. `FR-NAQ`)
+    pub iso_code: &'static str,
+}
+
+/// Department of France, or overseas collectivity with its own postal code prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrenchDepartment {
+    /// Code of the department (e.g. `33`, `2A` or `974`)
+    pub code: &'static str,
+
+    /// Name of the department
+    pub name: &'static str,
+
+    /// ISO 3166-2 code of the department (e.g. `FR-33`)
+    pub iso_code: &'static str,
+
+    /// Region of the department
+    pub region: &'static FrenchRegion,
+}
+
+const ARA: FrenchRegion = FrenchRegion {
+    name: "Auvergne-Rhône-Alpes",
+    iso_code: "FR-ARA",
+};
+
+const BFC: FrenchRegion = FrenchRegion {
+    name: "Bourgogne-Franche-Comté",
+    iso_code: "FR-BFC",
+};
+
+const BRE: FrenchRegion = FrenchRegion {
+    name: "Bretagne",
+    iso_code: "FR-BRE",
+};
+
+const CVL: FrenchRegion = FrenchRegion {
+    name: "Centre-Val de Loire",
+    iso_code: "FR-CVL",
+};
+
+const COR: FrenchRegion = FrenchRegion {
+    name: "Corse",
+    iso_code: "FR-20R",
+};
+
+const GES: FrenchRegion = FrenchRegion {
+    name: "Grand Est",
+    iso_code: "FR-GES",
+};
+
+const HDF: FrenchRegion = FrenchRegion {
+    name: "Hauts-de-France",
+    iso_code: "FR-HDF",
+};
+
+const IDF: FrenchRegion = FrenchRegion {
+    name: "Île-de-France",
+    iso_code: "FR-IDF",
+};
+
+const NOR: FrenchRegion = FrenchRegion {
+    name: "Normandie",
+    iso_code: "FR-NOR",
+};
+
+const NAQ: FrenchRegion = FrenchRegion {
+    name: "Nouvelle-Aquitaine",
+    iso_code: "FR-NAQ",
+};
+
+const OCC: FrenchRegion = FrenchRegion {
+    name: "Occitanie",
+    iso_code: "FR-OCC",
+};
+
+const PDL: FrenchRegion = FrenchRegion {
+    name: "Pays de la Loire",
+    iso_code: "FR-PDL",
+};
+
+const PAC: FrenchRegion = FrenchRegion {
+    name: "Provence-Alpes-Côte d'Azur",
+    iso_code: "FR-PAC",
+};
+
+const GUADELOUPE: FrenchRegion = FrenchRegion {
+    name: "Guadeloupe",
+    iso_code: "FR-971",
+};
+
+const MARTINIQUE: FrenchRegion = FrenchRegion {
+    name: "Martinique",
+    iso_code: "FR-972",
+};
+
+const GUYANE: FrenchRegion = FrenchRegion {
+    name: "Guyane",
+    iso_code: "FR-973",
+};
+
+const LA_REUNION: FrenchRegion = FrenchRegion {
+    name: "La Réunion",
+    iso_code: "FR-974",
+};
+
+const SAINT_PIERRE_ET_MIQUELON: FrenchRegion = FrenchRegion {
+    name: "Saint-Pierre-et-Miquelon",
+    iso_code: "FR-PM",
+};
+
+const MAYOTTE: FrenchRegion = FrenchRegion {
+    name: "Mayotte",
+    iso_code: "FR-976",
+};
+
+/// Departments of France, in code order
+static DEPARTMENTS: [FrenchDepartment; 102] = [
+    FrenchDepartment {
+        code: "01",
+        name: "Ain",
+        iso_code: "FR-01",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "02",
+        name: "Aisne",
+        iso_code: "FR-02",
+        region: &HDF,
+    },
+    FrenchDepartment {
+        code: "03",
+        name: "Allier",
+        iso_code: "FR-03",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "04",
+        name: "Alpes-de-Haute-Provence",
+        iso_code: "FR-04",
+        region: &PAC,
+    },
+    FrenchDepartment {
+        code: "05",
+        name: "Hautes-Alpes",
+        iso_code: "FR-05",
+        region: &PAC,
+    },
+    FrenchDepartment {
+        code: "06",
+        name: "Alpes-Maritimes",
+        iso_code: "FR-06",
+        region: &PAC,
+    },
+    FrenchDepartment {
+        code: "07",
+        name: "Ardèche",
+        iso_code: "FR-07",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "08",
+        name: "Ardennes",
+        iso_code: "FR-08",
+        region: &GES,
+    },
+    FrenchDepartment {
+        code: "09",
+        name: "Ariège",
+        iso_code: "FR-09",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "10",
+        name: "Aube",
+        iso_code: "FR-10",
+        region: &GES,
+    },
+    FrenchDepartment {
+        code: "11",
+        name: "Aude",
+        iso_code: "FR-11",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "12",
+        name: "Aveyron",
+        iso_code: "FR-12",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "13",
+        name: "Bouches-du-Rhône",
+        iso_code: "FR-13",
+        region: &PAC,
+    },
+    FrenchDepartment {
+        code: "14",
+        name: "Calvados",
+        iso_code: "FR-14",
+        region: &NOR,
+    },
+    FrenchDepartment {
+        code: "15",
+        name: "Cantal",
+        iso_code: "FR-15",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "16",
+        name: "Charente",
+        iso_code: "FR-16",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "17",
+        name: "Charente-Maritime",
+        iso_code: "FR-17",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "18",
+        name: "Cher",
+        iso_code: "FR-18",
+        region: &CVL,
+    },
+    FrenchDepartment {
+        code: "19",
+        name: "Corrèze",
+        iso_code: "FR-19",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "2A",
+        name: "Corse-du-Sud",
+        iso_code: "FR-2A",
+        region: &COR,
+    },
+    FrenchDepartment {
+        code: "2B",
+        name: "Haute-Corse",
+        iso_code: "FR-2B",
+        region: &COR,
+    },
+    FrenchDepartment {
+        code: "21",
+        name: "Côte-d'Or",
+        iso_code: "FR-21",
+        region: &BFC,
+    },
+    FrenchDepartment {
+        code: "22",
+        name: "Côtes-d'Armor",
+        iso_code: "FR-22",
+        region: &BRE,
+    },
+    FrenchDepartment {
+        code: "23",
+        name: "Creuse",
+        iso_code: "FR-23",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "24",
+        name: "Dordogne",
+        iso_code: "FR-24",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "25",
+        name: "Doubs",
+        iso_code: "FR-25",
+        region: &BFC,
+    },
+    FrenchDepartment {
+        code: "26",
+        name: "Drôme",
+        iso_code: "FR-26",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "27",
+        name: "Eure",
+        iso_code: "FR-27",
+        region: &NOR,
+    },
+    FrenchDepartment {
+        code: "28",
+        name: "Eure-et-Loir",
+        iso_code: "FR-28",
+        region: &CVL,
+    },
+    FrenchDepartment {
+        code: "29",
+        name: "Finistère",
+        iso_code: "FR-29",
+        region: &BRE,
+    },
+    FrenchDepartment {
+        code: "30",
+        name: "Gard",
+        iso_code: "FR-30",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "31",
+        name: "Haute-Garonne",
+        iso_code: "FR-31",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "32",
+        name: "Gers",
+        iso_code: "FR-32",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "33",
+        name: "Gironde",
+        iso_code: "FR-33",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "34",
+        name: "Hérault",
+        iso_code: "FR-34",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "35",
+        name: "Ille-et-Vilaine",
+        iso_code: "FR-35",
+        region: &BRE,
+    },
+    FrenchDepartment {
+        code: "36",
+        name: "Indre",
+        iso_code: "FR-36",
+        region: &CVL,
+    },
+    FrenchDepartment {
+        code: "37",
+        name: "Indre-et-Loire",
+        iso_code: "FR-37",
+        region: &CVL,
+    },
+    FrenchDepartment {
+        code: "38",
+        name: "Isère",
+        iso_code: "FR-38",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "39",
+        name: "Jura",
+        iso_code: "FR-39",
+        region: &BFC,
+    },
+    FrenchDepartment {
+        code: "40",
+        name: "Landes",
+        iso_code: "FR-40",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "41",
+        name: "Loir-et-Cher",
+        iso_code: "FR-41",
+        region: &CVL,
+    },
+    FrenchDepartment {
+        code: "42",
+        name: "Loire",
+        iso_code: "FR-42",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "43",
+        name: "Haute-Loire",
+        iso_code: "FR-43",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "44",
+        name: "Loire-Atlantique",
+        iso_code: "FR-44",
+        region: &PDL,
+    },
+    FrenchDepartment {
+        code: "45",
+        name: "Loiret",
+        iso_code: "FR-45",
+        region: &CVL,
+    },
+    FrenchDepartment {
+        code: "46",
+        name: "Lot",
+        iso_code: "FR-46",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "47",
+        name: "Lot-et-Garonne",
+        iso_code: "FR-47",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "48",
+        name: "Lozère",
+        iso_code: "FR-48",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "49",
+        name: "Maine-et-Loire",
+        iso_code: "FR-49",
+        region: &PDL,
+    },
+    FrenchDepartment {
+        code: "50",
+        name: "Manche",
+        iso_code: "FR-50",
+        region: &NOR,
+    },
+    FrenchDepartment {
+        code: "51",
+        name: "Marne",
+        iso_code: "FR-51",
+        region: &GES,
+    },
+    FrenchDepartment {
+        code: "52",
+        name: "Haute-Marne",
+        iso_code: "FR-52",
+        region: &GES,
+    },
+    FrenchDepartment {
+        code: "53",
+        name: "Mayenne",
+        iso_code: "FR-53",
+        region: &PDL,
+    },
+    FrenchDepartment {
+        code: "54",
+        name: "Meurthe-et-Moselle",
+        iso_code: "FR-54",
+        region: &GES,
+    },
+    FrenchDepartment {
+        code: "55",
+        name: "Meuse",
+        iso_code: "FR-55",
+        region: &GES,
+    },
+    FrenchDepartment {
+        code: "56",
+        name: "Morbihan",
+        iso_code: "FR-56",
+        region: &BRE,
+    },
+    FrenchDepartment {
+        code: "57",
+        name: "Moselle",
+        iso_code: "FR-57",
+        region: &GES,
+    },
+    FrenchDepartment {
+        code: "58",
+        name: "Nièvre",
+        iso_code: "FR-58",
+        region: &BFC,
+    },
+    FrenchDepartment {
+        code: "59",
+        name: "Nord",
+        iso_code: "FR-59",
+        region: &HDF,
+    },
+    FrenchDepartment {
+        code: "60",
+        name: "Oise",
+        iso_code: "FR-60",
+        region: &HDF,
+    },
+    FrenchDepartment {
+        code: "61",
+        name: "Orne",
+        iso_code: "FR-61",
+        region: &NOR,
+    },
+    FrenchDepartment {
+        code: "62",
+        name: "Pas-de-Calais",
+        iso_code: "FR-62",
+        region: &HDF,
+    },
+    FrenchDepartment {
+        code: "63",
+        name: "Puy-de-Dôme",
+        iso_code: "FR-63",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "64",
+        name: "Pyrénées-Atlantiques",
+        iso_code: "FR-64",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "65",
+        name: "Hautes-Pyrénées",
+        iso_code: "FR-65",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "66",
+        name: "Pyrénées-Orientales",
+        iso_code: "FR-66",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "67",
+        name: "Bas-Rhin",
+        iso_code: "FR-67",
+        region: &GES,
+    },
+    FrenchDepartment {
+        code: "68",
+        name: "Haut-Rhin",
+        iso_code: "FR-68",
+        region: &GES,
+    },
+    FrenchDepartment {
+        code: "69",
+        name: "Rhône",
+        iso_code: "FR-69",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "70",
+        name: "Haute-Saône",
+        iso_code: "FR-70",
+        region: &BFC,
+    },
+    FrenchDepartment {
+        code: "71",
+        name: "Saône-et-Loire",
+        iso_code: "FR-71",
+        region: &BFC,
+    },
+    FrenchDepartment {
+        code: "72",
+        name: "Sarthe",
+        iso_code: "FR-72",
+        region: &PDL,
+    },
+    FrenchDepartment {
+        code: "73",
+        name: "Savoie",
+        iso_code: "FR-73",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "74",
+        name: "Haute-Savoie",
+        iso_code: "FR-74",
+        region: &ARA,
+    },
+    FrenchDepartment {
+        code: "75",
+        name: "Paris",
+        iso_code: "FR-75C",
+        region: &IDF,
+    },
+    FrenchDepartment {
+        code: "76",
+        name: "Seine-Maritime",
+        iso_code: "FR-76",
+        region: &NOR,
+    },
+    FrenchDepartment {
+        code: "77",
+        name: "Seine-et-Marne",
+        iso_code: "FR-77",
+        region: &IDF,
+    },
+    FrenchDepartment {
+        code: "78",
+        name: "Yvelines",
+        iso_code: "FR-78",
+        region: &IDF,
+    },
+    FrenchDepartment {
+        code: "79",
+        name: "Deux-Sèvres",
+        iso_code: "FR-79",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "80",
+        name: "Somme",
+        iso_code: "FR-80",
+        region: &HDF,
+    },
+    FrenchDepartment {
+        code: "81",
+        name: "Tarn",
+        iso_code: "FR-81",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "82",
+        name: "Tarn-et-Garonne",
+        iso_code: "FR-82",
+        region: &OCC,
+    },
+    FrenchDepartment {
+        code: "83",
+        name: "Var",
+        iso_code: "FR-83",
+        region: &PAC,
+    },
+    FrenchDepartment {
+        code: "84",
+        name: "Vaucluse",
+        iso_code: "FR-84",
+        region: &PAC,
+    },
+    FrenchDepartment {
+        code: "85",
+        name: "Vendée",
+        iso_code: "FR-85",
+        region: &PDL,
+    },
+    FrenchDepartment {
+        code: "86",
+        name: "Vienne",
+        iso_code: "FR-86",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "87",
+        name: "Haute-Vienne",
+        iso_code: "FR-87",
+        region: &NAQ,
+    },
+    FrenchDepartment {
+        code: "88",
+        name: "Vosges",
+        iso_code: "FR-88",
+        region: &GES,
+    },
+    FrenchDepartment {
+        code: "89",
+        name: "Yonne",
+        iso_code: "FR-89",
+        region: &BFC,
+    },
+    FrenchDepartment {
+        code: "90",
+        name: "Territoire de Belfort",
+        iso_code: "FR-90",
+        region: &BFC,
+    },
+    FrenchDepartment {
+        code: "91",
+        name: "Essonne",
+        iso_code: "FR-91",
+        region: &IDF,
+    },
+    FrenchDepartment {
+        code: "92",
+        name: "Hauts-de-Seine",
+        iso_code: "FR-92",
+        region: &IDF,
+    },
+    FrenchDepartment {
+        code: "93",
+        name: "Seine-Saint-Denis",
+        iso_code: "FR-93",
+        region: &IDF,
+    },
+    FrenchDepartment {
+        code: "94",
+        name: "Val-de-Marne",
+        iso_code: "FR-94",
+        region: &IDF,
+    },
+    FrenchDepartment {
+        code: "95",
+        name: "Val-d'Oise",
+        iso_code: "FR-95",
+        region: &IDF,
+    },
+    FrenchDepartment {
+        code: "971",
+        name: "Guadeloupe",
+        iso_code: "FR-971",
+        region: &GUADELOUPE,
+    },
+    FrenchDepartment {
+        code: "972",
+        name: "Martinique",
+        iso_code: "FR-972",
+        region: &MARTINIQUE,
+    },
+    FrenchDepartment {
+        code: "973",
+        name: "Guyane",
+        iso_code: "FR-973",
+        region: &GUYANE,
+    },
+    FrenchDepartment {
+        code: "974",
+        name: "La Réunion",
+        iso_code: "FR-974",
+        region: &LA_REUNION,
+    },
+    FrenchDepartment {
+        code: "975",
+        name: "Saint-Pierre-et-Miquelon",
+        iso_code: "FR-PM",
+        region: &SAINT_PIERRE_ET_MIQUELON,
+    },
+    FrenchDepartment {
+        code: "976",
+        name: "Mayotte",
+        iso_code: "FR-976",
+        region: &MAYOTTE,
+    },
+];
+
+impl FrenchDepartment {
+    /// Every department, in code order
+    pub fn all() -> &'static [FrenchDepartment] {
+        &DEPARTMENTS
+    }
+
+    /// Department with the given code (e.g. `33`, `2A` or `974`), ignoring case
+    pub fn from_code(code: &str) -> Option<&'static FrenchDepartment> {
+        DEPARTMENTS
+            .iter()
+            .find(|department| department.code.eq_ignore_ascii_case(code.trim()))
+    }
+
+    /// Department of a French 5-digit postal code.
+    ///
+    /// The department is given by the first 2 digits, or the first 3 overseas (`97x`). Corsican codes (`20xxx`) are
+    /// split between Corse-du-Sud (`200xx` and `201xx`) and Haute-Corse. Monaco codes (`980xx`) and the other overseas
+    /// collectivities have no department.
+    pub fn from_post_code(post_code: &str) -> Option<&'static FrenchDepartment> {
+        let post_code = post_code.trim();
+        if post_code.len() != 5 || !post_code.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let code = match &post_code[..2] {
+            "20" if post_code.as_bytes()[2] < b'2' => "2A",
+            "20" => "2B",
+            "97" | "98" => &post_code[..3],
+            code => code,
+        };
+
+        Self::from_code(code)
+    }
+
+    /// Whether a postal code belongs to Monaco (`980xx`) rather than to France
+    pub fn is_monaco_post_code(post_code: &str) -> bool {
+        let post_code = post_code.trim();
+
+        post_code.len() == 5 && post_code.starts_with(MONACO_POST_CODE_PREFIX)
+    }
+}
+
+impl Display for FrenchDepartment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}), {}", self.name, self.iso_code, self.region.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_post_code_finds_department_and_region() {
+        let department = FrenchDepartment::from_post_code("33380").unwrap();
+
+        assert_eq!(department.code, "33");
+        assert_eq!(department.name, "Gironde");
+        assert_eq!(department.iso_code, "FR-33");
+        assert_eq!(department.region.name, "Nouvelle-Aquitaine");
+        assert_eq!(department.region.iso_code, "FR-NAQ");
+        assert_eq!(
+            department.to_string(),
+            "Gironde (FR-33), Nouvelle-Aquitaine".to_string()
+        );
+    }
+
+    #[test]
+    fn from_post_code_splits_corsica() {
+        assert_eq!(
+            FrenchDepartment::from_post_code("20000").unwrap().code,
+            "2A"
+        );
+        assert_eq!(
+            FrenchDepartment::from_post_code("20167").unwrap().code,
+            "2A"
+        );
+        assert_eq!(
+            FrenchDepartment::from_post_code("20200").unwrap().code,
+            "2B"
+        );
+        assert_eq!(
+            FrenchDepartment::from_post_code("20600")
+                .unwrap()
+                .region
+                .iso_code,
+            "FR-20R"
+        );
+    }
+
+    #[test]
+    fn from_post_code_finds_overseas_departments() {
+        for (post_code, code) in [
+            ("97100", "971"),
+            ("97200", "972"),
+            ("97300", "973"),
+            ("97400", "974"),
+            ("97500", "975"),
+            ("97600", "976"),
+        ] {
+            assert_eq!(
+                FrenchDepartment::from_post_code(post_code).unwrap().code,
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn from_post_code_has_no_department_for_monaco_or_invalid_codes() {
+        assert!(FrenchDepartment::is_monaco_post_code("98000"));
+        assert!(!FrenchDepartment::is_monaco_post_code("33380"));
+        assert_eq!(FrenchDepartment::from_post_code("98000"), None);
+        assert_eq!(FrenchDepartment::from_post_code("3338"), None);
+        assert_eq!(FrenchDepartment::from_post_code("ABCDE"), None);
+    }
+
+    #[test]
+    fn from_code_ignores_case() {
+        assert_eq!(
+            FrenchDepartment::from_code("2b").unwrap().name,
+            "Haute-Corse"
+        );
+        assert_eq!(FrenchDepartment::all().len(), 102);
+    }
+}
@@ -0,0 +1,117 @@
+//! Special distribution mentions of French addresses: post boxes (BP, TSA, CS) on line 5 and CEDEX on line 6
+
+use crate::domain::text::fold;
+
+/// Kinds of post box mention: boîte postale, tri service arrivée and course spéciale
+const POST_BOX_KINDS: [&str; 3] = ["BP", "TSA", "CS"];
+
+/// Suffix of the destination locality of a CEDEX (courrier d'entreprise à distribution exceptionnelle)
+pub(crate) const CEDEX: &str = "CEDEX";
+
+/// Split a line 5 into its post box mention (e.g. `BP 40122`) and the locality that may follow it
+pub(crate) fn split_post_box(line: &str) -> (Option<String>, Option<String>) {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    let post_box_length = match words.as_slice() {
+        [kind, number, ..] if is_post_box_kind(kind) && is_number(number) => 2,
+        [kind_and_number, ..] if is_attached_post_box(kind_and_number) => 1,
+        _ => 0,
+    };
+
+    let post_box = (post_box_length > 0).then(|| words[..post_box_length].join(" "));
+    let locality = (words.len() > post_box_length).then(|| words[post_box_length..].join(" "));
+
+    (post_box, locality)
+}
+
+/// Split a destination locality into the town name and its CEDEX suffix with the office number (e.g. `CEDEX 08`)
+pub(crate) fn split_cedex(town: &str) -> (String, Option<String>) {
+    let words: Vec<&str> = town.split_whitespace().collect();
+
+    let Some(index) = words.iter().rposition(|word| fold(word) == CEDEX) else {
+        return (town.to_string(), None);
+    };
+
+    match &words[index + 1..] {
+        [] => (words[..index].join(" "), Some(words[index].to_string())),
+        [office] if is_number(office) => (
+            words[..index].join(" "),
+            Some(format!("{} {}", words[index], office)),
+        ),
+        _ => (town.to_string(), None),
+    }
+}
+
+/// Whether a line 5 starts with a post box mention
+pub(crate) fn has_post_box(line: &str) -> bool {
+    split_post_box(line).0.is_some()
+}
+
+fn is_post_box_kind(word: &str) -> bool {
+    POST_BOX_KINDS.contains(&fold(word).replace('.', "").as_str())
+}
+
+fn is_attached_post_box(word: &str) -> bool {
+    let digits_start = word
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(word.len());
+
+    digits_start > 0 && is_post_box_kind(&word[..digits_start]) && is_number(&word[digits_start..])
+}
+
+fn is_number(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_post_box_recognizes_every_kind() {
+        for (line, post_box) in [
+            ("BP 40122", "BP 40122"),
+            ("TSA 70011", "TSA 70011"),
+            ("CS 30001", "CS 30001"),
+            ("B.P. 12", "B.P. 12"),
+            ("BP40122", "BP40122"),
+        ] {
+            assert_eq!(split_post_box(line), (Some(post_box.to_string()), None));
+        }
+    }
+
+    #[test]
+    fn split_post_box_keeps_the_following_locality() {
+        assert_eq!(
+            split_post_box("BP 40122 CAUDOS"),
+            (Some("BP 40122".to_string()), Some("CAUDOS".to_string()))
+        );
+    }
+
+    #[test]
+    fn split_post_box_keeps_locality_without_post_box() {
+        assert_eq!(split_post_box("CAUDOS"), (None, Some("CAUDOS".to_string())));
+        assert_eq!(split_post_box("BP"), (None, Some("BP".to_string())));
+    }
+
+    #[test]
+    fn split_cedex_extracts_suffix_and_office_number() {
+        assert_eq!(
+            split_cedex("LIBOURNE CEDEX"),
+            ("LIBOURNE".to_string(), Some("CEDEX".to_string()))
+        );
+        assert_eq!(
+            split_cedex("PARIS CEDEX 12"),
+            ("PARIS".to_string(), Some("CEDEX 12".to_string()))
+        );
+    }
+
+    #[test]
+    fn split_cedex_keeps_town_without_cedex() {
+        assert_eq!(split_cedex("LIBOURNE"), ("LIBOURNE".to_string(), None));
+        assert_eq!(
+            split_cedex("LIBOURNE CEDEX CENTRE"),
+            ("LIBOURNE CEDEX CENTRE".to_string(), None)
+        );
+    }
+}
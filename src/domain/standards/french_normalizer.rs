@@ -0,0 +1,237 @@
+use crate::domain::{
+    FrenchAddress, MAX_LINE_LENGTH,
+    text::{collapse_whitespace, fold, normalize_apostrophes, strip_diacritics},
+};
+
+/// Official La Poste abbreviations of street types, titles and common words
+const ABBREVIATIONS: [(&str, &str); 58] = [
+    // Titles
+    ("MONSIEUR", "M"),
+    ("MADAME", "MME"),
+    ("MADEMOISELLE", "MLLE"),
+    ("MESSIEURS", "MM"),
+    ("MESDAMES", "MMES"),
+    ("DOCTEUR", "DR"),
+    ("MAITRE", "ME"),
+    ("PROFESSEUR", "PR"),
+    ("GENERAL", "GAL"),
+    ("MARECHAL", "MAL"),
+    ("PRESIDENT", "PDT"),
+    ("SAINT", "ST"),
+    ("SAINTE", "STE"),
+    // Street types
+    ("ALLEE", "ALL"),
+    ("AVENUE", "AV"),
+    ("BOULEVARD", "BD"),
+    ("CARREFOUR", "CAR"),
+    ("CHAUSSEE", "CHS"),
+    ("CHEMIN", "CHE"),
+    ("CORNICHE", "COR"),
+    ("COURS", "CRS"),
+    ("DOMAINE", "DOM"),
+    ("DESCENTE", "DSC"),
+    ("ECART", "ECA"),
+    ("ESPLANADE", "ESP"),
+    ("FAUBOURG", "FG"),
+    ("HAMEAU", "HAM"),
+    ("HALLE", "HLE"),
+    ("IMPASSE", "IMP"),
+    ("LOTISSEMENT", "LOT"),
+    ("MARCHE", "MAR"),
+    ("MONTEE", "MTE"),
+    ("PASSAGE", "PAS"),
+    ("PLACE", "PL"),
+    ("PLAINE", "PLN"),
+    ("PLATEAU", "PLT"),
+    ("PROMENADE", "PRO"),
+    ("PARVIS", "PRV"),
+    ("QUARTIER", "QUA"),
+    ("RESIDENCE", "RES"),
+    ("RUELLE", "RLE"),
+    ("ROCADE", "ROC"),
+    ("ROUTE", "RTE"),
+    ("SENTIER", "SEN"),
+    ("SQUARE", "SQ"),
+    ("TRAVERSE", "TRA"),
+    ("VILLA", "VLA"),
+    ("VILLAGE", "VLGE"),
+    // Building and delivery point
+    ("APPARTEMENT", "APP"),
+    ("BATIMENT", "BAT"),
+    ("ESCALIER", "ESC"),
+    ("ETAGE", "ETG"),
+    ("IMMEUBLE", "IMM"),
+    ("ZONE INDUSTRIELLE", "ZI"),
+    ("ZONE ARTISANALE", "ZA"),
+    ("CENTRE COMMERCIAL", "CCAL"),
+    ("LIEU DIT", "LD"),
+    ("ROND POINT", "RPT"),
+];
+
+impl FrenchAddress {
+    /// Rewrite the address into its NF Z10-011 postal-compliant form.
+    ///
+    /// Apostrophe variants are normalized on every line. Lines 4 to 7 are uppercased and stripped of accents and
+    /// punctuation. Lines longer than 38 characters are shortened with the La Poste abbreviations, word by word,
+    /// until they fit.
+    pub fn normalize(&self) -> FrenchAddress {
+        FrenchAddress::new(
+            normalize_recipient_line(&self.name),
+            self.recipient_info.as_deref().map(normalize_recipient_line),
+            self.geographic_info
+                .as_deref()
+                .map(normalize_recipient_line),
+            normalize_postal_line(&self.street),
+            self.special_mentions.as_deref().map(normalize_postal_line),
+            normalize_postal_line(&self.postal_info),
+            normalize_postal_line(&self.country),
+        )
+    }
+}
+
+/// Lines 1 to 3 keep their case and accents
+fn normalize_recipient_line(line: &str) -> String {
+    abbreviate(&collapse_whitespace(&normalize_apostrophes(line)))
+}
+
+/// Lines 4 to 7 are uppercase, without accents nor punctuation (hyphens of compound names are kept)
+fn normalize_postal_line(line: &str) -> String {
+    let line: String = strip_diacritics(line)
+        .to_uppercase()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect();
+
+    abbreviate(&collapse_whitespace(&line))
+}
+
+/// Replace words by their abbreviation, from the start of the line, until it fits in 38 characters
+fn abbreviate(line: &str) -> String {
+    let mut words: Vec<String> = line.split(' ').map(str::to_string).collect();
+    let mut index = 0;
+
+    while words.join(" ").chars().count() > MAX_LINE_LENGTH && index < words.len() {
+        let abbreviation = ABBREVIATIONS.iter().find_map(|(term, abbreviation)| {
+            // A multi-word term may be written as separate words or as a single hyphenated word
+            [term.split(' ').count(), 1].into_iter().find_map(|length| {
+                let candidate = words
+                    .get(index..index + length)?
+                    .join(" ")
+                    .replace('-', " ");
+
+                (fold(&candidate) == *term).then_some((length, *abbreviation))
+            })
+        });
+
+        if let Some((length, abbreviation)) = abbreviation {
+            words.splice(index..index + length, [abbreviation.to_string()]);
+        }
+
+        index += 1;
+    }
+
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_uppercases_and_strips_accents_and_punctuation_of_lines_4_to_7() {
+        let address = FrenchAddress::new(
+            "Monsieur Jean DELHOURME".to_string(),
+            Some("Chez Mireille COPEAU Appartement 2".to_string()),
+            Some("Entrée A Bâtiment Jonquille".to_string()),
+            "25, rue de l’Église".to_string(),
+            Some("Caudos".to_string()),
+            "33380 Mios".to_string(),
+            "France".to_string(),
+        );
+
+        assert_eq!(
+            address.normalize(),
+            FrenchAddress::new(
+                "Monsieur Jean DELHOURME".to_string(),
+                Some("Chez Mireille COPEAU Appartement 2".to_string()),
+                Some("Entrée A Bâtiment Jonquille".to_string()),
+                "25 RUE DE L EGLISE".to_string(),
+                Some("CAUDOS".to_string()),
+                "33380 MIOS".to_string(),
+                "FRANCE".to_string(),
+            )
+        );
+    }
+
+    #[test]
+    fn normalize_normalizes_apostrophes_of_lines_1_to_3() {
+        let address = FrenchAddress::new(
+            "Madame Anne D’ARC".to_string(),
+            None,
+            None,
+            "1 RUE DES FLEURS".to_string(),
+            None,
+            "33500 LIBOURNE".to_string(),
+            "FRANCE".to_string(),
+        );
+
+        assert_eq!(address.normalize().name, "Madame Anne D'ARC".to_string());
+    }
+
+    #[test]
+    fn normalize_abbreviates_only_lines_longer_than_38_characters() {
+        let address = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            Some("Résidence des Grands Chênes du Bois Joli".to_string()),
+            "125 BOULEVARD DU MARECHAL DE LATTRE DE TASSIGNY".to_string(),
+            None,
+            "33500 LIBOURNE".to_string(),
+            "FRANCE".to_string(),
+        );
+
+        let normalized = address.normalize();
+
+        assert_eq!(normalized.name, "Monsieur Jean DURAND".to_string());
+        assert_eq!(
+            normalized.geographic_info,
+            Some("RES des Grands Chênes du Bois Joli".to_string())
+        );
+        assert_eq!(
+            normalized.street,
+            "125 BD DU MAL DE LATTRE DE TASSIGNY".to_string()
+        );
+    }
+
+    #[test]
+    fn normalize_abbreviates_multi_word_and_hyphenated_terms() {
+        let line = "ZONE INDUSTRIELLE DE LA BALLASTRIERE OUEST";
+
+        assert_eq!(abbreviate(line), "ZI DE LA BALLASTRIERE OUEST".to_string());
+        assert_eq!(
+            abbreviate("LIEU-DIT LES GRANDES VIGNES DU HAUT PLATEAU"),
+            "LD LES GRANDES VIGNES DU HAUT PLATEAU".to_string()
+        );
+    }
+
+    #[test]
+    fn normalized_address_passes_character_rules() {
+        let address = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            None,
+            "25 rue de l’Église".to_string(),
+            None,
+            "33380 Mios".to_string(),
+            "France".to_string(),
+        );
+
+        assert!(address.normalize().is_valid());
+    }
+}
@@ -0,0 +1,396 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use celes::Country;
+
+use crate::{
+    AnyhowError, AnyhowResult,
+    domain::{FrenchAddress, FrenchStreetLine, text::fold},
+};
+
+use super::{
+    french_country::{country_from_name, french_country_name},
+    french_distribution::{CEDEX, has_post_box},
+};
+
+/// Minimum number of lines of a French address block
+const MIN_LINES: usize = 3;
+
+/// Maximum number of lines of a French address block
+const MAX_LINES: usize = 7;
+
+/// Street types recognizing a line 4 that has no street number
+const STREET_TYPES: [&str; 28] = [
+    "RUE",
+    "AVENUE",
+    "AV",
+    "BOULEVARD",
+    "BD",
+    "PLACE",
+    "PL",
+    "CHEMIN",
+    "CHE",
+    "ROUTE",
+    "RTE",
+    "IMPASSE",
+    "IMP",
+    "ALLEE",
+    "ALL",
+    "QUAI",
+    "COURS",
+    "CRS",
+    "PASSAGE",
+    "SQUARE",
+    "SQ",
+    "FAUBOURG",
+    "FG",
+    "LIEU-DIT",
+    "LD",
+    "HAMEAU",
+    "ESPLANADE",
+    "PROMENADE",
+];
+
+/// Keywords of line 3 (building, entrance, residence or zone)
+const GEOGRAPHIC_KEYWORDS: [&str; 16] = [
+    "ENTREE",
+    "BATIMENT",
+    "BAT",
+    "IMMEUBLE",
+    "IMM",
+    "RESIDENCE",
+    "RES",
+    "TOUR",
+    "ZONE",
+    "ZI",
+    "ZA",
+    "ZAC",
+    "LOTISSEMENT",
+    "LOT",
+    "DOMAINE",
+    "VILLA",
+];
+
+/// Result of parsing a free-text French address block
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedFrenchAddress {
+    /// Parsed address
+    pub address: FrenchAddress,
+
+    /// Confidence in the line assignment, from 0 (guess) to 1 (every line anchored)
+    pub confidence: f32,
+
+    /// Lines that could not be assigned to a NF Z10-011 line and are not in the address
+    pub unclassified_lines: Vec<String>,
+}
+
+impl FrenchAddress {
+    /// Parse a free-text block of 3 to 7 lines into a [FrenchAddress].
+    ///
+    /// The postal code line anchors line 6 (the following line being the country) and a line starting with a
+    /// street number, or a street type, anchors line 4. The remaining lines are assigned around these anchors.
+    pub fn parse_text(text: &str) -> AnyhowResult<ParsedFrenchAddress> {
+        let lines: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        if !(MIN_LINES..=MAX_LINES).contains(&lines.len()) {
+            return Err(anyhow!(
+                "A French address has {} to {} lines, found {}",
+                MIN_LINES,
+                MAX_LINES,
+                lines.len()
+            ));
+        }
+
+        let mut parser = TextParser::default();
+        let postal_index = parser.find_postal_line(&lines);
+
+        let country = match &lines[postal_index + 1..] {
+            // A block without a country line is domestic
+            [] => french_country_name(&Country::france()),
+            [country, unclassified @ ..] => {
+                parser.unclassify(unclassified);
+                country.to_string()
+            }
+        };
+
+        let name = lines[0].to_string();
+        let between = &lines[1..postal_index];
+        let street_index = parser.find_street_line(between);
+
+        let street = street_index.map_or("", |index| between[index]);
+        let (before_street, after_street) = match street_index {
+            Some(index) => (&between[..index], &between[index + 1..]),
+            // Without a street, the last line is a special mention such as a post box
+            None => between.split_at(between.len().saturating_sub(1)),
+        };
+
+        let special_mentions = match after_street {
+            [] => None,
+            [mentions, unclassified @ ..] => {
+                parser.unclassify(unclassified);
+                Some(mentions.to_string())
+            }
+        };
+
+        let (recipient_info, geographic_info) = parser.assign_lines_2_and_3(before_street);
+
+        Ok(ParsedFrenchAddress {
+            address: FrenchAddress::new(
+                name,
+                recipient_info,
+                geographic_info,
+                street.to_string(),
+                special_mentions,
+                lines[postal_index].to_string(),
+                country,
+            ),
+            confidence: parser.confidence.clamp(0.0, 1.0),
+            unclassified_lines: parser.unclassified_lines,
+        })
+    }
+}
+
+impl FromStr for FrenchAddress {
+    type Err = AnyhowError;
+
+    fn from_str(text: &str) -> AnyhowResult<Self> {
+        Ok(Self::parse_text(text)?.address)
+    }
+}
+
+/// State accumulated while assigning the lines
+struct TextParser {
+    confidence: f32,
+    unclassified_lines: Vec<String>,
+}
+
+impl Default for TextParser {
+    fn default() -> Self {
+        Self {
+            confidence: 1.0,
+            unclassified_lines: Vec::new(),
+        }
+    }
+}
+
+impl TextParser {
+    /// Index of line 6: the last line starting with a postal code, or else the line before a known country name
+    fn find_postal_line(&mut self, lines: &[&str]) -> usize {
+        // Line 1 is the recipient, so a postal code there cannot anchor line 6
+        if let Some(index) = lines
+            .iter()
+            .rposition(|line| starts_with_postal_code(line))
+            .filter(|index| *index > 0)
+        {
+            return index;
+        }
+
+        self.confidence -= 0.4;
+
+        let last = lines.len() - 1;
+        if country_from_name(lines[last]).is_some() {
+            last - 1
+        } else {
+            last
+        }
+    }
+
+    /// Index of line 4 among the lines between line 1 and line 6
+    fn find_street_line(&mut self, lines: &[&str]) -> Option<usize> {
+        if let Some(index) = lines
+            .iter()
+            .rposition(|line| FrenchStreetLine::parse(line).building_number.is_some())
+        {
+            return Some(index);
+        }
+
+        if let Some(index) = lines.iter().rposition(|line| {
+            first_word(line).is_some_and(|word| STREET_TYPES.contains(&word.as_str()))
+        }) {
+            self.confidence -= 0.1;
+            return Some(index);
+        }
+
+        // Without any anchor, the line just before line 6 is the most likely street, unless it is a special mention
+        match lines.last() {
+            Some(line) if !has_post_box(line) && !has_keyword(line, &[CEDEX]) => {
+                self.confidence -= 0.3;
+                Some(lines.len() - 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Assign the lines between line 1 and line 4 to line 2 (recipient) and line 3 (geographic point)
+    fn assign_lines_2_and_3(&mut self, lines: &[&str]) -> (Option<String>, Option<String>) {
+        match lines {
+            [] => (None, None),
+            [line] if has_keyword(line, &GEOGRAPHIC_KEYWORDS) => (None, Some(line.to_string())),
+            [line] => (Some(line.to_string()), None),
+            [recipient, geographic, unclassified @ ..] => {
+                if !has_keyword(geographic, &GEOGRAPHIC_KEYWORDS) {
+                    self.confidence -= 0.05;
+                }
+                self.unclassify(unclassified);
+
+                (Some(recipient.to_string()), Some(geographic.to_string()))
+            }
+        }
+    }
+
+    fn unclassify(&mut self, lines: &[&str]) {
+        self.confidence -= 0.1 * lines.len() as f32;
+        self.unclassified_lines
+            .extend(lines.iter().map(|line| line.to_string()));
+    }
+}
+
+fn starts_with_postal_code(line: &str) -> bool {
+    line.split_whitespace()
+        .next()
+        .is_some_and(|code| code.len() == 5 && code.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn first_word(line: &str) -> Option<String> {
+    line.split_whitespace().next().map(fold)
+}
+
+fn has_keyword(line: &str, keywords: &[&str]) -> bool {
+    fold(line)
+        .split(|c: char| !c.is_alphanumeric() && c != '-')
+        .any(|word| keywords.contains(&word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_text_assigns_every_line_of_a_full_address() {
+        let text = "Monsieur Jean DELHOURME
+            Chez Mireille COPEAU Appartement 2
+            Entrée A Bâtiment Jonquille
+            25 RUE DE L’EGLISE
+            CAUDOS
+            33380 MIOS
+            France";
+
+        let parsed = FrenchAddress::parse_text(text).unwrap();
+
+        assert_eq!(
+            parsed.address,
+            FrenchAddress::new(
+                "Monsieur Jean DELHOURME".to_string(),
+                Some("Chez Mireille COPEAU Appartement 2".to_string()),
+                Some("Entrée A Bâtiment Jonquille".to_string()),
+                "25 RUE DE L’EGLISE".to_string(),
+                Some("CAUDOS".to_string()),
+                "33380 MIOS".to_string(),
+                "France".to_string(),
+            )
+        );
+        assert_eq!(parsed.confidence, 1.0);
+        assert!(parsed.unclassified_lines.is_empty());
+    }
+
+    #[test]
+    fn parse_text_round_trips_display_output() {
+        let address = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            None,
+            "25D RUE DES FLEURS".to_string(),
+            None,
+            "33500 LIBOURNE".to_string(),
+            "France".to_string(),
+        );
+
+        let parsed: FrenchAddress = address.to_string().parse().unwrap();
+
+        assert_eq!(parsed, address);
+    }
+
+    #[test]
+    fn parse_text_defaults_country_to_france() {
+        let parsed =
+            FrenchAddress::parse_text("Madame Anne MARTIN\n3 PLACE DU MARCHE\n33500 LIBOURNE")
+                .unwrap();
+
+        assert_eq!(parsed.address.country, "FRANCE".to_string());
+        assert_eq!(parsed.address.street, "3 PLACE DU MARCHE".to_string());
+    }
+
+    #[test]
+    fn parse_text_uses_keywords_for_single_line_between_name_and_street() {
+        let parsed = FrenchAddress::parse_text(
+            "Madame Anne MARTIN\nRésidence Les Pins\n3 PLACE DU MARCHE\n33500 LIBOURNE",
+        )
+        .unwrap();
+
+        assert_eq!(parsed.address.recipient_info, None);
+        assert_eq!(
+            parsed.address.geographic_info,
+            Some("Résidence Les Pins".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_text_recognizes_street_type_without_number() {
+        let parsed = FrenchAddress::parse_text(
+            "SOCIETE DUPONT\nAVENUE DES CHAMPS\nBP 40122\n33506 LIBOURNE CEDEX",
+        )
+        .unwrap();
+
+        assert_eq!(parsed.address.street, "AVENUE DES CHAMPS".to_string());
+        assert_eq!(
+            parsed.address.special_mentions,
+            Some("BP 40122".to_string())
+        );
+        assert!(parsed.confidence < 1.0);
+    }
+
+    #[test]
+    fn parse_text_keeps_post_box_on_line_5_when_there_is_no_street() {
+        let parsed =
+            FrenchAddress::parse_text("SOCIETE DUPONT\nBP 40122\n33506 LIBOURNE CEDEX").unwrap();
+
+        assert_eq!(parsed.address.street, "".to_string());
+        assert_eq!(parsed.address.recipient_info, None);
+        assert_eq!(
+            parsed.address.special_mentions,
+            Some("BP 40122".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_text_reports_unclassified_lines() {
+        let parsed = FrenchAddress::parse_text(
+            "Monsieur Jean DURAND\n25D RUE DES FLEURS\nCAUDOS\nLIEU INCONNU\n33500 LIBOURNE\nFrance",
+        )
+        .unwrap();
+
+        assert_eq!(parsed.address.special_mentions, Some("CAUDOS".to_string()));
+        assert_eq!(parsed.unclassified_lines, vec!["LIEU INCONNU".to_string()]);
+        assert!(parsed.confidence < 1.0);
+    }
+
+    #[test]
+    fn parse_text_lowers_confidence_without_postal_code() {
+        let parsed =
+            FrenchAddress::parse_text("Monsieur Jean DURAND\n25D RUE DES FLEURS\nLIBOURNE\nFrance")
+                .unwrap();
+
+        assert_eq!(parsed.address.postal_info, "LIBOURNE".to_string());
+        assert!(parsed.confidence < 0.7);
+    }
+
+    #[test]
+    fn parse_text_returns_error_when_line_count_is_out_of_range() {
+        FrenchAddress::parse_text("Monsieur Jean DURAND\n33500 LIBOURNE")
+            .expect_err("Should return an error with fewer than 3 lines");
+    }
+}
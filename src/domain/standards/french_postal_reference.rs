@@ -0,0 +1,317 @@
+//! Offline La Poste reference of postal codes and communes (base officielle des codes postaux)
+
+use std::{collections::BTreeMap, fs, io::Read, path::Path};
+
+use anyhow::{Context, anyhow};
+
+use crate::{
+    AnyhowResult,
+    domain::text::{collapse_whitespace, edit_distance, fold},
+};
+
+/// Snapshot of the La Poste reference embedded in the binary (excerpt of the main communes)
+const EMBEDDED_SNAPSHOT: &str = include_str!("../../../assets/reference/laposte_hexasmal.csv");
+
+/// Commune served by a postal code, as listed in the La Poste reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostalCommune {
+    /// INSEE code of the commune
+    pub insee_code: String,
+
+    /// Name of the commune
+    pub name: String,
+
+    /// 5-digit postal code
+    pub post_code: String,
+
+    /// Locality to print on line 6 after the postal code (libellé d'acheminement)
+    pub routing_label: String,
+
+    /// Line 5 mention (former commune or locality), if any
+    pub line_5: Option<String>,
+}
+
+/// Result of checking a postal code and town name against the reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostalCodeCheck {
+    /// The town is one of the communes of the postal code
+    Match,
+
+    /// The town is a misspelling of a commune of the postal code
+    Misspelled {
+        /// Reference spelling of the commune
+        spelling: String,
+    },
+
+    /// The town is not a commune of the postal code
+    Mismatch {
+        /// Localities of the postal code
+        communes: Vec<String>,
+    },
+
+    /// The postal code is not in the reference
+    UnknownPostCode,
+}
+
+/// La Poste reference of the communes served by each postal code, loaded without network access
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FrenchPostalReference {
+    communes: BTreeMap<String, Vec<PostalCommune>>,
+}
+
+impl FrenchPostalReference {
+    /// Reference snapshot embedded in the binary.
+    ///
+    /// It is only an excerpt: load the full La Poste file with [FrenchPostalReference::load] to check every code.
+    pub fn embedded() -> AnyhowResult<Self> {
+        Self::from_csv(EMBEDDED_SNAPSHOT.as_bytes())
+            .with_context(|| "Failed to load the embedded La Poste reference")
+    }
+
+    /// Load the La Poste CSV file (`laposte_hexasmal.csv`) from a local path
+    pub fn load(path: &Path) -> AnyhowResult<Self> {
+        let file = fs::File::open(path)
+            .with_context(|| format!("Failed to open La Poste reference {:?}", path))?;
+
+        Self::from_csv(file)
+            .with_context(|| format!("Failed to load La Poste reference {:?}", path))
+    }
+
+    /// Read the La Poste CSV.
+    ///
+    /// Columns are found by their header, so both the `;` separated export (UTF-8 or Latin-1) and the `,` separated
+    /// one of the open data portal are accepted.
+    pub fn from_csv(mut reader: impl Read) -> AnyhowResult<Self> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .with_context(|| "Failed to read La Poste reference")?;
+        let content = decode(bytes);
+
+        let header = content.lines().next().unwrap_or_default();
+        let delimiter = if header.contains(';') { b';' } else { b',' };
+
+        let mut csv = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_reader(content.as_bytes());
+
+        let columns = Columns::find(csv.headers()?)?;
+        let mut communes: BTreeMap<String, Vec<PostalCommune>> = BTreeMap::new();
+
+        for record in csv.records() {
+            let record = record.with_context(|| "Failed to read La Poste reference record")?;
+            let field = |index: usize| collapse_whitespace(record.get(index).unwrap_or_default());
+
+            let commune = PostalCommune {
+                insee_code: field(columns.insee_code),
+                name: field(columns.name),
+                post_code: field(columns.post_code),
+                routing_label: field(columns.routing_label),
+                line_5: columns
+                    .line_5
+                    .map(field)
+                    .filter(|line_5| !line_5.is_empty()),
+            };
+
+            communes
+                .entry(commune.post_code.clone())
+                .or_default()
+                .push(commune);
+        }
+
+        Ok(Self { communes })
+    }
+
+    /// Communes served by a postal code
+    pub fn communes(&self, post_code: &str) -> &[PostalCommune] {
+        self.communes
+            .get(post_code.trim())
+            .map_or(&[], |communes| communes.as_slice())
+    }
+
+    /// Check that a town is one of the communes of a postal code, ignoring case, accents and punctuation
+    pub fn check(&self, post_code: &str, town: &str) -> PostalCodeCheck {
+        let communes = self.communes(post_code);
+        if communes.is_empty() {
+            return PostalCodeCheck::UnknownPostCode;
+        }
+
+        let town = match_key(town);
+        let spellings = || {
+            communes
+                .iter()
+                .flat_map(|commune| [&commune.routing_label, &commune.name])
+        };
+
+        if spellings().any(|spelling| match_key(spelling) == town) {
+            return PostalCodeCheck::Match;
+        }
+
+        let closest = spellings()
+            .map(|spelling| (edit_distance(&match_key(spelling), &town), spelling))
+            .min_by_key(|(distance, _)| *distance);
+
+        match closest {
+            Some((distance, spelling)) if distance <= (spelling.chars().count() / 4).max(1) => {
+                PostalCodeCheck::Misspelled {
+                    spelling: spelling.to_string(),
+                }
+            }
+            _ => {
+                let mut labels: Vec<String> = communes
+                    .iter()
+                    .map(|commune| commune.routing_label.clone())
+                    .collect();
+                labels.dedup();
+
+                PostalCodeCheck::Mismatch { communes: labels }
+            }
+        }
+    }
+}
+
+/// Indexes of the reference columns
+struct Columns {
+    insee_code: usize,
+    name: usize,
+    post_code: usize,
+    routing_label: usize,
+    line_5: Option<usize>,
+}
+
+impl Columns {
+    fn find(headers: &csv::StringRecord) -> AnyhowResult<Self> {
+        let headers: Vec<String> = headers
+            .iter()
+            .map(|header| fold(header.trim_start_matches(['#', '\u{feff}'])).replace(' ', "_"))
+            .collect();
+        let find = |prefix: &str| headers.iter().position(|header| header.starts_with(prefix));
+        let require = |prefix: &str| {
+            find(prefix).ok_or_else(|| anyhow!("Missing La Poste reference column '{prefix}'"))
+        };
+
+        Ok(Self {
+            insee_code: require("CODE_COMMUNE_INSEE")?,
+            name: require("NOM_")?,
+            post_code: require("CODE_POSTAL")?,
+            routing_label: require("LIBELLE_D_ACHEMINEMENT")?,
+            line_5: find("LIGNE_5"),
+        })
+    }
+}
+
+/// Decode the file as UTF-8, falling back to Latin-1 used by the older La Poste exports
+fn decode(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes)
+        .unwrap_or_else(|error| error.into_bytes().into_iter().map(char::from).collect())
+}
+
+/// Comparison form of a commune name: folded, without punctuation, with `SAINT` abbreviated as on line 6
+fn match_key(name: &str) -> String {
+    let name: String = fold(name)
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    name.split_whitespace()
+        .map(|word| match word {
+            "SAINT" => "ST",
+            "SAINTE" => "STE",
+            _ => word,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference() -> FrenchPostalReference {
+        FrenchPostalReference::embedded().unwrap()
+    }
+
+    #[test]
+    fn embedded_lists_communes_of_a_postal_code() {
+        let names: Vec<String> = reference()
+            .communes("33380")
+            .iter()
+            .map(|commune| commune.routing_label.clone())
+            .collect();
+
+        assert_eq!(names, vec!["BIGANOS", "MIOS", "MARCHEPRIME"]);
+    }
+
+    #[test]
+    fn check_matches_routing_label_or_name_ignoring_case_accents_and_punctuation() {
+        let reference = reference();
+
+        assert_eq!(reference.check("33380", "MIOS"), PostalCodeCheck::Match);
+        assert_eq!(reference.check("33380", "Mios"), PostalCodeCheck::Match);
+        assert_eq!(
+            reference.check("33330", "Saint-Émilion"),
+            PostalCodeCheck::Match
+        );
+    }
+
+    #[test]
+    fn check_suggests_the_spelling_of_a_misspelled_commune() {
+        assert_eq!(
+            reference().check("33500", "LIBOUNRE"),
+            PostalCodeCheck::Misspelled {
+                spelling: "LIBOURNE".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn check_lists_communes_when_town_does_not_match_the_postal_code() {
+        assert_eq!(
+            reference().check("33500", "PARIS"),
+            PostalCodeCheck::Mismatch {
+                communes: vec![
+                    "ARVEYRES".to_string(),
+                    "LES BILLAUX".to_string(),
+                    "LIBOURNE".to_string(),
+                    "POMEROL".to_string(),
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn check_reports_unknown_postal_code() {
+        assert_eq!(
+            reference().check("00000", "NULLE PART"),
+            PostalCodeCheck::UnknownPostCode
+        );
+    }
+
+    #[test]
+    fn from_csv_reads_comma_separated_latin_1_export() {
+        let mut csv =
+            b"code_commune_insee,nom_de_la_commune,code_postal,libelle_d_acheminement,ligne_5\n"
+                .to_vec();
+        csv.extend(b"33394,Saint-\xc9milion,33330,ST EMILION,\n");
+
+        let reference = FrenchPostalReference::from_csv(csv.as_slice()).unwrap();
+
+        assert_eq!(
+            reference.communes("33330"),
+            &[PostalCommune {
+                insee_code: "33394".to_string(),
+                name: "Saint-Émilion".to_string(),
+                post_code: "33330".to_string(),
+                routing_label: "ST EMILION".to_string(),
+                line_5: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn from_csv_returns_error_when_a_column_is_missing() {
+        FrenchPostalReference::from_csv("Code_postal;Nom_commune\n33380;MIOS\n".as_bytes())
+            .expect_err("Should return an error without the routing label column");
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt::{self, Display};
+
+/// Repetition indexes written after a house number, longest first
+const REPETITION_INDEXES: [&str; 4] = ["QUINQUIES", "QUATER", "TER", "BIS"];
+
+/// NF Z10-011 line 4 split into the building number and the street
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrenchStreetLine {
+    /// House number with its repetition index, as written on the line (e.g. `25D`, `22BIS`, `22 BIS`)
+    pub building_number: Option<String>,
+
+    /// Street type and name (e.g. `RUE DES FLEURS`)
+    pub street_name: String,
+}
+
+impl FrenchStreetLine {
+    /// Create a new [FrenchStreetLine]
+    pub fn new(building_number: Option<String>, street_name: String) -> Self {
+        Self {
+            building_number,
+            street_name,
+        }
+    }
+
+    /// Split a line 4 into its building number and street.
+    ///
+    /// The building number is a leading house number, optionally followed by a repetition index
+    /// (BIS, TER, QUATER, QUINQUIES or a single letter), attached or separated by a space.
+    /// A line without a leading number is kept whole as the street name.
+    pub fn parse(line: &str) -> Self {
+        let line = line.trim();
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        let Some(first) = words.first() else {
+            return Self::new(None, String::new());
+        };
+
+        let first = first.trim_end_matches(',');
+        let digits_end = first
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(first.len());
+
+        if digits_end == 0 || !is_repetition_index(&first[digits_end..], true) {
+            return Self::new(None, line.to_string());
+        }
+
+        let mut building_number = first.to_string();
+        let mut street_start = 1;
+
+        // Repetition index separated from the number by a space, only when a street follows it
+        if digits_end == first.len() && words.len() > 2 {
+            let next = words[1].trim_end_matches(',');
+
+            if is_repetition_index(next, false) {
+                building_number = format!("{building_number} {next}");
+                street_start = 2;
+            }
+        }
+
+        Self::new(Some(building_number), words[street_start..].join(" "))
+    }
+
+    /// House number without its repetition index
+    pub fn number(&self) -> Option<&str> {
+        self.building_number.as_deref().map(|building_number| {
+            let end = building_number
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(building_number.len());
+
+            &building_number[..end]
+        })
+    }
+
+    /// Repetition index in uppercase (e.g. `BIS` or `D`), if any
+    pub fn repetition_index(&self) -> Option<String> {
+        let number = self.number()?;
+        let building_number = self.building_number.as_deref()?;
+        let index = building_number[number.len()..].trim();
+
+        (!index.is_empty()).then(|| index.to_uppercase())
+    }
+}
+
+impl Display for FrenchStreetLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.building_number.as_deref() {
+            Some(building_number) if !building_number.is_empty() => {
+                write!(f, "{} {}", building_number, self.street_name)
+            }
+            _ => write!(f, "{}", self.street_name),
+        }
+    }
+}
+
+/// Whether a suffix is a repetition index; an empty suffix is accepted only when attached to the number
+fn is_repetition_index(suffix: &str, attached: bool) -> bool {
+    let suffix = suffix.to_uppercase();
+
+    if suffix.is_empty() {
+        return attached;
+    }
+
+    REPETITION_INDEXES.contains(&suffix.as_str())
+        || (suffix.len() == 1 && suffix.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_splits_number_with_attached_letter_index() {
+        let line = FrenchStreetLine::parse("25D RUE DES FLEURS");
+
+        assert_eq!(line.building_number, Some("25D".to_string()));
+        assert_eq!(line.street_name, "RUE DES FLEURS".to_string());
+        assert_eq!(line.number(), Some("25"));
+        assert_eq!(line.repetition_index(), Some("D".to_string()));
+    }
+
+    #[test]
+    fn parse_splits_number_with_attached_word_index() {
+        let line = FrenchStreetLine::parse("22BIS RUE DES FLEURS");
+
+        assert_eq!(line.building_number, Some("22BIS".to_string()));
+        assert_eq!(line.street_name, "RUE DES FLEURS".to_string());
+        assert_eq!(line.repetition_index(), Some("BIS".to_string()));
+    }
+
+    #[test]
+    fn parse_splits_number_with_separated_index() {
+        let line = FrenchStreetLine::parse("3 ter avenue Victor Hugo");
+
+        assert_eq!(line.building_number, Some("3 ter".to_string()));
+        assert_eq!(line.street_name, "avenue Victor Hugo".to_string());
+        assert_eq!(line.repetition_index(), Some("TER".to_string()));
+    }
+
+    #[test]
+    fn parse_splits_number_without_index() {
+        let line = FrenchStreetLine::parse("25, RUE DE L’EGLISE");
+
+        assert_eq!(line.building_number, Some("25".to_string()));
+        assert_eq!(line.street_name, "RUE DE L’EGLISE".to_string());
+        assert_eq!(line.repetition_index(), None);
+    }
+
+    #[test]
+    fn parse_keeps_line_without_number_as_street_name() {
+        let line = FrenchStreetLine::parse("LIEU-DIT LES GRANGES");
+
+        assert_eq!(line.building_number, None);
+        assert_eq!(line.street_name, "LIEU-DIT LES GRANGES".to_string());
+    }
+
+    #[test]
+    fn parse_does_not_take_a_number_followed_by_a_word_as_building_number() {
+        let line = FrenchStreetLine::parse("8MAI RUE");
+
+        assert_eq!(line.building_number, None);
+        assert_eq!(line.street_name, "8MAI RUE".to_string());
+    }
+
+    #[test]
+    fn display_rebuilds_the_line() {
+        for line in [
+            "25D RUE DES FLEURS",
+            "22BIS RUE DES FLEURS",
+            "3 TER AVENUE VICTOR HUGO",
+            "RUE DES FLEURS",
+        ] {
+            assert_eq!(FrenchStreetLine::parse(line).to_string(), line.to_string());
+        }
+    }
+}
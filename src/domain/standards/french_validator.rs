@@ -0,0 +1,475 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{FrenchAddress, FrenchPostalReference, PostalCodeCheck};
+
+use super::french_distribution::{has_post_box, split_cedex};
+
+/// Maximum number of characters on a line (NF Z10-011)
+pub const MAX_LINE_LENGTH: usize = 38;
+
+/// Maximum number of lines of a domestic address (NF Z10-011)
+pub const MAX_DOMESTIC_LINES: usize = 6;
+
+/// Rule of the NF Z10-011 standard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrenchRule {
+    /// A line has more than 38 characters
+    LineTooLong,
+
+    /// A domestic address has more than 6 lines
+    TooManyLines,
+
+    /// Lines 4 to 6 must be in uppercase
+    NotUppercase,
+
+    /// Lines 4 to 6 must not contain punctuation
+    Punctuation,
+
+    /// Lines 4 to 6 must not contain accents
+    Accent,
+
+    /// Line 6 must start with a 5-digit postal code followed by the locality
+    InvalidPostalCode,
+
+    /// A mandatory line is empty
+    EmptyMandatoryLine,
+
+    /// A CEDEX locality on line 6 needs a BP, TSA or CS delivery mention on line 5
+    CedexWithoutDeliveryMention,
+
+    /// The postal code of line 6 is not in the La Poste reference
+    UnknownPostalCode,
+
+    /// The locality of line 6 is not a commune of its postal code in the La Poste reference
+    CommuneMismatch,
+}
+
+/// Violation of a NF Z10-011 rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrenchViolation {
+    /// Number of the offending line (1 to 7)
+    pub line: u8,
+
+    /// Violated rule
+    pub rule: FrenchRule,
+
+    /// Offending text
+    pub text: String,
+
+    /// Human readable explanation
+    pub message: String,
+}
+
+impl Display for FrenchViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}: '{}'", self.line, self.message, self.text)
+    }
+}
+
+impl FrenchAddress {
+    /// Check the address against the NF Z10-011 rules and return every violation, in line order
+    pub fn validate(&self) -> Vec<FrenchViolation> {
+        let mut violations = Vec::new();
+        let lines = self.numbered_lines();
+
+        for (line, text) in &lines {
+            let (line, text) = (*line, *text);
+
+            if is_mandatory(line, &lines) && text.trim().is_empty() {
+                violations.push(violation(
+                    line,
+                    FrenchRule::EmptyMandatoryLine,
+                    text,
+                    "mandatory line is empty",
+                ));
+            }
+
+            let length = text.chars().count();
+            if length > MAX_LINE_LENGTH {
+                violations.push(violation(
+                    line,
+                    FrenchRule::LineTooLong,
+                    text,
+                    format!("line has {length} characters, more than {MAX_LINE_LENGTH}"),
+                ));
+            }
+
+            if (4..=6).contains(&line) {
+                check_postal_line_characters(line, text, &mut violations);
+            }
+
+            if line == 6
+                && self.is_domestic()
+                && !text.trim().is_empty()
+                && !has_valid_postal_code(text)
+            {
+                violations.push(violation(
+                    line,
+                    FrenchRule::InvalidPostalCode,
+                    text,
+                    "line does not start with a 5-digit postal code followed by the locality",
+                ));
+            }
+
+            if line == 6 && split_cedex(text).1.is_some() && !self.has_delivery_mention() {
+                violations.push(violation(
+                    line,
+                    FrenchRule::CedexWithoutDeliveryMention,
+                    text,
+                    "CEDEX locality without a BP, TSA or CS delivery mention on line 5",
+                ));
+            }
+        }
+
+        let line_count = lines
+            .iter()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .count();
+        if self.is_domestic() && line_count > MAX_DOMESTIC_LINES {
+            violations.push(violation(
+                7,
+                FrenchRule::TooManyLines,
+                &self.country,
+                format!("domestic address has {line_count} lines, more than {MAX_DOMESTIC_LINES}"),
+            ));
+        }
+
+        violations.sort_by_key(|violation| violation.line);
+
+        violations
+    }
+
+    /// Check the address against the NF Z10-011 rules and the La Poste reference of postal codes and communes.
+    ///
+    /// The locality of a domestic line 6 must be one of the communes of its postal code. CEDEX postal codes are not
+    /// in the reference and are not checked.
+    pub fn validate_with_reference(
+        &self,
+        reference: &FrenchPostalReference,
+    ) -> Vec<FrenchViolation> {
+        let mut violations = self.validate();
+        let text = self.postal_info.as_str();
+
+        if !self.is_domestic() || !has_valid_postal_code(text) || split_cedex(text).1.is_some() {
+            return violations;
+        }
+
+        let (post_code, town) = text.trim().split_once(' ').unwrap_or_default();
+        match reference.check(post_code, town) {
+            PostalCodeCheck::Match => {}
+            PostalCodeCheck::Misspelled { spelling } => violations.push(violation(
+                6,
+                FrenchRule::CommuneMismatch,
+                text,
+                format!("locality is misspelled, La Poste spells it '{spelling}'"),
+            )),
+            PostalCodeCheck::Mismatch { communes } => violations.push(violation(
+                6,
+                FrenchRule::CommuneMismatch,
+                text,
+                format!(
+                    "locality is not served by postal code {post_code}, expected {}",
+                    communes.join(", ")
+                ),
+            )),
+            PostalCodeCheck::UnknownPostCode => violations.push(violation(
+                6,
+                FrenchRule::UnknownPostalCode,
+                text,
+                format!("postal code {post_code} is not in the La Poste reference"),
+            )),
+        }
+
+        violations.sort_by_key(|violation| violation.line);
+
+        violations
+    }
+
+    /// Whether the address complies with every NF Z10-011 rule
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_empty()
+    }
+
+    /// Present lines with their NF Z10-011 number
+    fn numbered_lines(&self) -> Vec<(u8, &str)> {
+        [
+            (1, Some(self.name.as_str())),
+            (2, self.recipient_info.as_deref()),
+            (3, self.geographic_info.as_deref()),
+            (4, Some(self.street.as_str())),
+            (5, self.special_mentions.as_deref()),
+            (6, Some(self.postal_info.as_str())),
+            (7, Some(self.country.as_str())),
+        ]
+        .into_iter()
+        .filter_map(|(line, text)| text.map(|text| (line, text)))
+        .collect()
+    }
+
+    /// Whether line 5 carries a post box mention (BP, TSA or CS)
+    fn has_delivery_mention(&self) -> bool {
+        self.special_mentions.as_deref().is_some_and(has_post_box)
+    }
+}
+
+/// Lines 1 and 6 are always mandatory, line 4 is mandatory unless line 5 gives the delivery point
+fn is_mandatory(line: u8, lines: &[(u8, &str)]) -> bool {
+    let has_special_mentions = lines
+        .iter()
+        .any(|(line, text)| *line == 5 && !text.trim().is_empty());
+
+    match line {
+        1 | 6 => true,
+        4 => !has_special_mentions,
+        _ => false,
+    }
+}
+
+fn check_postal_line_characters(line: u8, text: &str, violations: &mut Vec<FrenchViolation>) {
+    if text.chars().any(char::is_lowercase) {
+        violations.push(violation(
+            line,
+            FrenchRule::NotUppercase,
+            text,
+            "line is not in uppercase",
+        ));
+    }
+
+    if text.chars().any(|c| c.is_alphabetic() && !c.is_ascii()) {
+        violations.push(violation(
+            line,
+            FrenchRule::Accent,
+            text,
+            "line contains accents",
+        ));
+    }
+
+    if text.chars().any(is_punctuation) {
+        violations.push(violation(
+            line,
+            FrenchRule::Punctuation,
+            text,
+            "line contains punctuation",
+        ));
+    }
+}
+
+/// Any symbol other than a letter, a digit, a space or a hyphen (used in compound names)
+fn is_punctuation(c: char) -> bool {
+    !c.is_alphanumeric() && !c.is_whitespace() && c != '-'
+}
+
+fn has_valid_postal_code(text: &str) -> bool {
+    let mut words = text.split_whitespace();
+
+    words
+        .next()
+        .is_some_and(|code| code.len() == 5 && code.chars().all(|c| c.is_ascii_digit()))
+        && words.next().is_some()
+}
+
+fn violation(
+    line: u8,
+    rule: FrenchRule,
+    text: &str,
+    message: impl Into<String>,
+) -> FrenchViolation {
+    FrenchViolation {
+        line,
+        rule,
+        text: text.to_string(),
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(address: &FrenchAddress) -> Vec<(u8, FrenchRule)> {
+        address
+            .validate()
+            .into_iter()
+            .map(|violation| (violation.line, violation.rule))
+            .collect()
+    }
+
+    #[test]
+    fn validate_accepts_compliant_address() {
+        let address = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            None,
+            "25D RUE DES FLEURS".to_string(),
+            None,
+            "33500 LIBOURNE".to_string(),
+            "France".to_string(),
+        );
+
+        assert!(address.is_valid());
+    }
+
+    #[test]
+    fn validate_reports_characters_forbidden_in_lines_4_to_6() {
+        let address = FrenchAddress::new(
+            "Monsieur Jean DELHOURME".to_string(),
+            Some("Chez Mireille COPEAU Appartement 2".to_string()),
+            Some("Entrée A Bâtiment Jonquille".to_string()),
+            "25 RUE DE L’EGLISE".to_string(),
+            Some("Caudos".to_string()),
+            "33380 MIOS".to_string(),
+            "France".to_string(),
+        );
+
+        assert_eq!(
+            rules(&address),
+            vec![
+                (4, FrenchRule::Punctuation),
+                (5, FrenchRule::NotUppercase),
+                (7, FrenchRule::TooManyLines),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_reports_accents() {
+        let address = FrenchAddress::new(
+            "Madame Anne MARTIN".to_string(),
+            None,
+            None,
+            "3 RUE DE L ÉGLISE".to_string(),
+            None,
+            "33380 MIOS".to_string(),
+            "France".to_string(),
+        );
+
+        let violations = address.validate();
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, FrenchRule::Accent);
+        assert_eq!(violations[0].text, "3 RUE DE L ÉGLISE".to_string());
+    }
+
+    #[test]
+    fn validate_reports_long_lines_with_their_text() {
+        let long_line = "RESIDENCE DES GRANDS CHENES DU BOIS JOLI".to_string();
+        let address = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            Some(long_line.clone()),
+            "25D RUE DES FLEURS".to_string(),
+            None,
+            "33500 LIBOURNE".to_string(),
+            "France".to_string(),
+        );
+
+        let violations = address.validate();
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, 3);
+        assert_eq!(violations[0].rule, FrenchRule::LineTooLong);
+        assert_eq!(violations[0].text, long_line);
+    }
+
+    #[test]
+    fn validate_reports_invalid_postal_code_and_empty_mandatory_lines() {
+        let address = FrenchAddress::new(
+            "".to_string(),
+            None,
+            None,
+            "25D RUE DES FLEURS".to_string(),
+            None,
+            "3350 LIBOURNE".to_string(),
+            "France".to_string(),
+        );
+
+        assert_eq!(
+            rules(&address),
+            vec![
+                (1, FrenchRule::EmptyMandatoryLine),
+                (6, FrenchRule::InvalidPostalCode),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_accepts_missing_street_when_line_5_is_present() {
+        let address = FrenchAddress::new(
+            "SOCIETE DUPONT".to_string(),
+            None,
+            None,
+            "".to_string(),
+            Some("BP 40122".to_string()),
+            "33506 LIBOURNE CEDEX".to_string(),
+            "France".to_string(),
+        );
+
+        assert!(address.is_valid());
+    }
+
+    #[test]
+    fn validate_allows_seven_lines_for_international_mail() {
+        let address = FrenchAddress::new(
+            "Herr Hans MULLER".to_string(),
+            Some("APPARTEMENT 2".to_string()),
+            Some("GEBAUDE B".to_string()),
+            "12 HAUPTSTRASSE".to_string(),
+            Some("MITTE".to_string()),
+            "10115 BERLIN".to_string(),
+            "Germany".to_string(),
+        );
+
+        assert!(address.is_valid());
+    }
+
+    #[test]
+    fn validate_reports_cedex_without_delivery_mention() {
+        let address = FrenchAddress::new(
+            "SOCIETE DUPONT".to_string(),
+            None,
+            None,
+            "22 RUE DES FLEURS".to_string(),
+            Some("CAUDOS".to_string()),
+            "33506 LIBOURNE CEDEX 12".to_string(),
+            "France".to_string(),
+        );
+
+        assert_eq!(
+            rules(&address),
+            vec![(6, FrenchRule::CedexWithoutDeliveryMention)]
+        );
+    }
+
+    #[test]
+    fn validate_with_reference_reports_commune_mismatch_and_unknown_postal_code() {
+        let reference = FrenchPostalReference::embedded().unwrap();
+        let address = |postal_info: &str| {
+            FrenchAddress::new(
+                "Monsieur Jean DURAND".to_string(),
+                None,
+                None,
+                "25D RUE DES FLEURS".to_string(),
+                None,
+                postal_info.to_string(),
+                "France".to_string(),
+            )
+        };
+
+        assert!(
+            address("33500 LIBOURNE")
+                .validate_with_reference(&reference)
+                .is_empty()
+        );
+
+        let mismatch = address("33500 PARIS").validate_with_reference(&reference);
+        assert_eq!(mismatch.len(), 1);
+        assert_eq!(mismatch[0].rule, FrenchRule::CommuneMismatch);
+        assert!(mismatch[0].message.contains("LIBOURNE"));
+
+        let unknown = address("00000 NULLE PART").validate_with_reference(&reference);
+        assert_eq!(unknown[0].rule, FrenchRule::UnknownPostalCode);
+    }
+}
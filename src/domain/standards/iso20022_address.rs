@@ -4,11 +4,33 @@ use anyhow::anyhow;
 use celes::Country;
 use serde::{Deserialize, Serialize};
 
-use crate::{AnyhowError, AnyhowResult, domain::Address};
+use crate::{
+    AnyhowError, AnyhowResult,
+    domain::{
+        Address, AddressType, ConversionReport, Converted, FrenchDepartment, FrenchStreetLine,
+        ISO20022Version,
+    },
+};
+
+use super::{
+    french_delivery_point::FrenchDeliveryPoint, french_distribution::split_cedex,
+    iso20022_rendering::ELEMENT_SEPARATOR,
+};
+
+/// ISO code of France, whose street lines are split with the French street line parser
+const FRANCE: &str = "FR";
 
 /// Represents an ISO 20022 postal address
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct ISO20022Address {
+    /// Type of the address (postal, post box, business, ...)
+    #[serde(rename = "AdrTp")]
+    pub address_type: Option<AddressType>,
+
+    /// Party in whose care the mail is delivered (PostalAddress27 onwards)
+    #[serde(rename = "CareOf")]
+    pub care_of: Option<String>,
+
     /// Identification of a division of a large organization or building
     #[serde(rename = "Dept")]
     pub department: Option<String>,
@@ -33,6 +55,10 @@ pub struct ISO20022Address {
     #[serde(rename = "Flr")]
     pub floor: Option<String>,
 
+    /// Number of the unit (apartment, suite) within a building (PostalAddress27 onwards)
+    #[serde(rename = "UnitNb")]
+    pub unit_number: Option<String>,
+
     /// Numbered box in a post office, assigned to a person or organization
     #[serde(rename = "PstBx")]
     pub post_box: Option<String>,
@@ -64,59 +90,191 @@ pub struct ISO20022Address {
     /// Nation with its own government (ISO country code)
     #[serde(rename = "Ctry")]
     pub country: String,
+
+    /// Unstructured lines of the address, alone (unstructured) or along with the town and country (hybrid)
+    #[serde(rename = "AdrLine", default, skip_serializing_if = "Vec::is_empty")]
+    pub address_lines: Vec<String>,
 }
 
 impl Display for ISO20022Address {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "<PstlAdr>\n  <StrtNm>{}</StrtNm>\n  <PstCd>{}</PstCd>\n  <TwnNm>{}</TwnNm>\n  <Ctry>{}</Ctry>\n</PstlAdr>",
-            self.street_name, self.post_code, self.town_name, self.country
-        )
+        write!(f, "{}", self.to_xml())
     }
 }
 
-impl From<Address> for ISO20022Address {
-    fn from(address: Address) -> ISO20022Address {
-        ISO20022Address {
-            department: None,
-            sub_department: None,
-            street_name: address.street_name,
-            building_number: None,
-            building_name: None,
+impl ISO20022Address {
+    /// Convert an internal address to ISO 20022 and report the alterations made
+    pub fn from_internal(address: Address) -> Converted<ISO20022Address> {
+        let mut report = ConversionReport::default();
+
+        if let Some(name) = address.recipient() {
+            report.dropped("name", format!("'{name}' has no <PstlAdr> element"));
+        }
+
+        let mut street = FrenchStreetLine::new(address.building_number, address.street_name);
+
+        // A French street name may still carry the building number (e.g. "25D RUE DES FLEURS")
+        if street.building_number.is_none() && address.country == FRANCE {
+            street = FrenchStreetLine::parse(&street.street_name);
+
+            if let Some(ref building_number) = street.building_number {
+                report.moved(
+                    "street_name",
+                    format!("building number '{building_number}' extracted into <BldgNb>"),
+                );
+            }
+        }
+
+        // ISO 20022 has no staircase nor entrance element, they stay with the building they lead into
+        for (field, value) in [
+            ("entrance", &address.entrance),
+            ("staircase", &address.staircase),
+        ] {
+            if let Some(value) = value {
+                report.moved(field, format!("'{value}' folded into <BldgNm>"));
+            }
+        }
+        let building_name = [address.building_name, address.entrance, address.staircase]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        let building_name =
+            (!building_name.is_empty()).then(|| building_name.join(ELEMENT_SEPARATOR));
+
+        if address.country == FRANCE && FrenchDepartment::is_monaco_post_code(&address.post_code) {
+            report.unverified(
+                "country",
+                format!(
+                    "postal code {} belongs to Monaco (MC), not to France",
+                    address.post_code
+                ),
+            );
+        }
+
+        let iso_address = ISO20022Address {
+            address_type: address.address_type,
+            care_of: address.care_of,
+            department: address.department,
+            sub_department: address.sub_department,
+            street_name: street.street_name,
+            building_number: street.building_number,
+            building_name,
             floor: address.floor,
+            unit_number: address.unit,
             post_box: address.post_box,
             room: address.room,
             post_code: address.post_code,
-            town_name: address.town_name,
+            // ISO 20022 has no CEDEX element, the suffix stays part of the town name as printed on line 6
+            town_name: [Some(address.town_name), address.cedex]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" "),
             town_location_name: address.town_location_name,
-            district_name: None,
-            country_sub_division: None,
+            district_name: address.district_name,
+            country_sub_division: address.country_sub_division,
             country: address.country,
-        }
+            address_lines: address.address_lines,
+        };
+
+        Converted::new(iso_address, report)
     }
-}
 
-impl TryFrom<ISO20022Address> for Address {
-    type Error = AnyhowError;
+    /// Convert the address to the internal model and report the alterations made
+    pub fn into_internal(self) -> AnyhowResult<Converted<Address>> {
+        verify_country_code(&self.country)?;
 
-    fn try_from(iso_address: ISO20022Address) -> AnyhowResult<Address> {
-        verify_country_code(&iso_address.country)?;
+        // The internal model follows PostalAddress27
+        let Converted {
+            address: iso_address,
+            report,
+        } = self.to_version(ISO20022Version::PostalAddress27);
+
+        let (town_name, cedex) = if iso_address.country == FRANCE {
+            split_cedex(&iso_address.town_name)
+        } else {
+            (iso_address.town_name, None)
+        };
+        let (building_name, entrance, staircase) = split_building_name(iso_address.building_name);
 
-        Ok(Address {
+        let address = Address {
             name: None,
+            recipient: None,
+            recipient_kind: None,
+            care_of: iso_address.care_of,
+            address_type: iso_address.address_type,
+            department: iso_address.department,
+            sub_department: iso_address.sub_department,
             floor: iso_address.floor,
+            unit: iso_address.unit_number,
+            staircase,
+            entrance,
             post_box: iso_address.post_box,
             room: iso_address.room,
             street_name: iso_address.street_name,
-            town_name: iso_address.town_name,
+            building_number: iso_address.building_number,
+            building_name,
+            town_name,
+            cedex,
             town_location_name: iso_address.town_location_name,
+            district_name: iso_address.district_name,
             post_code: iso_address.post_code,
+            country_sub_division: iso_address.country_sub_division,
             country: iso_address.country,
-        })
+            address_lines: iso_address.address_lines,
+            source_references: Vec::new(),
+        };
+
+        Ok(Converted::new(address, report))
     }
 }
 
+impl From<Address> for ISO20022Address {
+    fn from(address: Address) -> ISO20022Address {
+        ISO20022Address::from_internal(address).address
+    }
+}
+
+impl TryFrom<ISO20022Address> for Address {
+    type Error = AnyhowError;
+
+    fn try_from(iso_address: ISO20022Address) -> AnyhowResult<Address> {
+        Ok(iso_address.into_internal()?.address)
+    }
+}
+
+/// Split the entrance and staircase folded into `<BldgNm>` out of the building name, the other parts staying in it
+fn split_building_name(
+    building_name: Option<String>,
+) -> (Option<String>, Option<String>, Option<String>) {
+    let mut building = Vec::new();
+    let (mut entrance, mut staircase) = (None, None);
+
+    for part in building_name
+        .iter()
+        .flat_map(|name| name.split(ELEMENT_SEPARATOR))
+    {
+        let (point, other) = FrenchDeliveryPoint::parse(part);
+        let elements = point
+            .inside()
+            .into_iter()
+            .chain(point.outside())
+            .flatten()
+            .count();
+        let alone = other.is_none() && elements == 1;
+
+        match (point.entrance, point.staircase) {
+            (Some(value), None) if alone && entrance.is_none() => entrance = Some(value),
+            (None, Some(value)) if alone && staircase.is_none() => staircase = Some(value),
+            _ => building.push(part),
+        }
+    }
+
+    let building_name = (!building.is_empty()).then(|| building.join(ELEMENT_SEPARATOR));
+
+    (building_name, entrance, staircase)
+}
+
 fn verify_country_code(country: &str) -> AnyhowResult<()> {
     Country::from_alpha2(country).map_err(|e| {
         anyhow!(e).context(format!(
@@ -143,6 +301,7 @@ mod tests {
             town_location_name: None,
             post_code: "33506".to_string(),
             country: "FR".to_string(),
+            ..Default::default()
         };
 
         let iso_address: ISO20022Address = address.into();
@@ -150,12 +309,15 @@ mod tests {
         assert_eq!(
             iso_address,
             ISO20022Address {
+                address_type: None,
+                care_of: None,
                 department: None,
                 sub_department: None,
-                street_name: "22BIS RUE DES FLEURS".to_string(),
-                building_number: None,
+                street_name: "RUE DES FLEURS".to_string(),
+                building_number: Some("22BIS".to_string()),
                 building_name: None,
                 floor: Some("Zone industrielle de la Ballastrierre Ouest".to_string()),
+                unit_number: None,
                 post_box: Some("BP 40122".to_string()),
                 room: None,
                 post_code: "33506".to_string(),
@@ -164,6 +326,7 @@ mod tests {
                 district_name: None,
                 country_sub_division: None,
                 country: "FR".to_string(),
+                address_lines: Vec::new(),
             }
         );
     }
@@ -171,12 +334,15 @@ mod tests {
     #[test]
     fn convert_iso20022_internal_simple() {
         let initial_iso_address = ISO20022Address {
+            address_type: None,
+            care_of: None,
             department: None,
             sub_department: None,
-            street_name: "25D RUE DES FLEURS".to_string(),
-            building_number: None,
+            street_name: "RUE DES FLEURS".to_string(),
+            building_number: Some("25D".to_string()),
             building_name: None,
             floor: None,
+            unit_number: None,
             post_box: None,
             room: None,
             post_code: "33500".to_string(),
@@ -185,6 +351,7 @@ mod tests {
             district_name: None,
             country_sub_division: None,
             country: "FR".to_string(),
+            address_lines: Vec::new(),
         };
 
         let internal: Address = initial_iso_address.clone().try_into().unwrap();
@@ -196,11 +363,14 @@ mod tests {
                 floor: None,
                 post_box: None,
                 room: None,
-                street_name: "25D RUE DES FLEURS".to_string(),
+                street_name: "RUE DES FLEURS".to_string(),
+                building_number: Some("25D".to_string()),
                 town_name: "LIBOURNE".to_string(),
                 town_location_name: None,
                 post_code: "33500".to_string(),
+                country_sub_division: None,
                 country: "FR".to_string(),
+                ..Default::default()
             }
         );
 
@@ -212,12 +382,15 @@ mod tests {
     #[test]
     fn convert_iso20022_internal_extended() {
         let initial_iso_address = ISO20022Address {
+            address_type: None,
+            care_of: None,
             department: None,
             sub_department: None,
-            street_name: "25 RUE DES FLEURS".to_string(),
-            building_number: None,
+            street_name: "RUE DES FLEURS".to_string(),
+            building_number: Some("25".to_string()),
             building_name: None,
             floor: Some("Entrée A Bâtiment Jonquille".to_string()),
+            unit_number: None,
             post_box: None,
             room: Some("Chez Mireille COPEAU Appartement 2".to_string()),
             post_code: "33380".to_string(),
@@ -226,6 +399,7 @@ mod tests {
             district_name: None,
             country_sub_division: None,
             country: "FR".to_string(),
+            address_lines: Vec::new(),
         };
 
         let internal: Address = initial_iso_address.clone().try_into().unwrap();
@@ -237,11 +411,75 @@ mod tests {
                 floor: Some("Entrée A Bâtiment Jonquille".to_string()),
                 post_box: None,
                 room: Some("Chez Mireille COPEAU Appartement 2".to_string()),
-                street_name: "25 RUE DES FLEURS".to_string(),
+                street_name: "RUE DES FLEURS".to_string(),
+                building_number: Some("25".to_string()),
+                town_name: "MIOS".to_string(),
+                town_location_name: Some("CAUDOS".to_string()),
+                post_code: "33380".to_string(),
+                country_sub_division: None,
+                country: "FR".to_string(),
+                ..Default::default()
+            }
+        );
+
+        let iso_address_from_internal: ISO20022Address = internal.into();
+
+        assert_eq!(iso_address_from_internal, initial_iso_address);
+    }
+
+    #[test]
+    fn convert_iso20022_internal_with_every_element() {
+        let initial_iso_address = ISO20022Address {
+            address_type: None,
+            care_of: Some("Mireille COPEAU".to_string()),
+            department: Some("Service Comptabilité".to_string()),
+            sub_department: Some("Fournisseurs".to_string()),
+            street_name: "RUE DES FLEURS".to_string(),
+            building_number: Some("25".to_string()),
+            building_name: Some("Bâtiment Jonquille".to_string()),
+            floor: Some("Entrée A".to_string()),
+            unit_number: Some("B12".to_string()),
+            post_box: Some("BP 40122".to_string()),
+            room: Some("Appartement 2".to_string()),
+            post_code: "33380".to_string(),
+            town_name: "MIOS".to_string(),
+            town_location_name: Some("CAUDOS".to_string()),
+            district_name: Some("Val de l'Eyre".to_string()),
+            country_sub_division: Some("FR-33".to_string()),
+            country: "FR".to_string(),
+            address_lines: vec!["Lieu-dit Le Bourg".to_string()],
+        };
+
+        let internal: Address = initial_iso_address.clone().try_into().unwrap();
+
+        assert_eq!(
+            internal,
+            Address {
+                name: None,
+                recipient: None,
+                recipient_kind: None,
+                care_of: Some("Mireille COPEAU".to_string()),
+                address_type: None,
+                department: Some("Service Comptabilité".to_string()),
+                sub_department: Some("Fournisseurs".to_string()),
+                floor: Some("Entrée A".to_string()),
+                unit: Some("B12".to_string()),
+                staircase: None,
+                entrance: None,
+                post_box: Some("BP 40122".to_string()),
+                room: Some("Appartement 2".to_string()),
+                street_name: "RUE DES FLEURS".to_string(),
+                building_number: Some("25".to_string()),
+                building_name: Some("Bâtiment Jonquille".to_string()),
                 town_name: "MIOS".to_string(),
+                cedex: None,
                 town_location_name: Some("CAUDOS".to_string()),
+                district_name: Some("Val de l'Eyre".to_string()),
                 post_code: "33380".to_string(),
+                country_sub_division: Some("FR-33".to_string()),
                 country: "FR".to_string(),
+                address_lines: vec!["Lieu-dit Le Bourg".to_string()],
+                source_references: Vec::new(),
             }
         );
 
@@ -260,4 +498,119 @@ mod tests {
         verify_country_code("France")
             .expect_err("Country code should fail with an invalid ISO code");
     }
+
+    #[test]
+    fn from_internal_reports_dropped_name() {
+        let converted = ISO20022Address::from_internal(Address::dummy());
+
+        assert_eq!(converted.report.warnings[0].field, "name");
+        assert!(!converted.report.is_lossless());
+    }
+
+    #[test]
+    fn from_internal_extracts_building_number_from_french_street_name() {
+        let address = Address {
+            street_name: "25D RUE DES FLEURS".to_string(),
+            ..Address::dummy()
+        };
+
+        let converted = ISO20022Address::from_internal(address);
+
+        assert_eq!(converted.address.building_number, Some("25D".to_string()));
+        assert_eq!(converted.address.street_name, "RUE DES FLEURS".to_string());
+        assert!(
+            converted
+                .report
+                .warnings
+                .iter()
+                .any(|warning| warning.field == "street_name")
+        );
+    }
+
+    #[test]
+    fn from_internal_keeps_foreign_street_name_whole() {
+        let address = Address {
+            street_name: "221B Baker Street".to_string(),
+            country: "GB".to_string(),
+            ..Address::dummy()
+        };
+
+        let converted = ISO20022Address::from_internal(address);
+
+        assert_eq!(converted.address.building_number, None);
+        assert_eq!(
+            converted.address.street_name,
+            "221B Baker Street".to_string()
+        );
+    }
+
+    #[test]
+    fn convert_iso20022_internal_splits_cedex_from_french_town_name() {
+        let initial_iso_address = ISO20022Address {
+            street_name: "RUE DES FLEURS".to_string(),
+            building_number: Some("22BIS".to_string()),
+            post_box: Some("BP 40122".to_string()),
+            post_code: "33506".to_string(),
+            town_name: "LIBOURNE CEDEX".to_string(),
+            country_sub_division: Some("FR-33".to_string()),
+            country: "FR".to_string(),
+            ..Default::default()
+        };
+
+        let internal: Address = initial_iso_address.clone().try_into().unwrap();
+
+        assert_eq!(internal.town_name, "LIBOURNE".to_string());
+        assert_eq!(internal.cedex, Some("CEDEX".to_string()));
+
+        let iso_address_from_internal: ISO20022Address = internal.into();
+
+        assert_eq!(iso_address_from_internal, initial_iso_address);
+    }
+
+    #[test]
+    fn from_internal_reports_monaco_postal_code_in_france() {
+        let converted = ISO20022Address::from_internal(Address {
+            post_code: "98000".to_string(),
+            town_name: "MONACO".to_string(),
+            ..Address::dummy()
+        });
+
+        assert_eq!(converted.address.country_sub_division, None);
+        assert!(
+            converted
+                .report
+                .warnings
+                .iter()
+                .any(|warning| warning.field == "country")
+        );
+    }
+
+    #[test]
+    fn from_internal_folds_entrance_and_staircase_into_building_name_and_back() {
+        let internal = Address {
+            name: None,
+            unit: Some("Appartement 12".to_string()),
+            staircase: Some("Escalier B".to_string()),
+            entrance: Some("Entrée A".to_string()),
+            building_name: Some("Bâtiment C".to_string()),
+            ..Address::dummy()
+        };
+
+        let converted = ISO20022Address::from_internal(internal);
+
+        assert_eq!(
+            converted.address.unit_number,
+            Some("Appartement 12".to_string())
+        );
+        assert_eq!(
+            converted.address.building_name,
+            Some("Bâtiment C, Entrée A, Escalier B".to_string())
+        );
+        assert!(converted.report.is_lossless());
+
+        let internal = converted.address.into_internal().unwrap().address;
+        assert_eq!(internal.building_name, Some("Bâtiment C".to_string()));
+        assert_eq!(internal.entrance, Some("Entrée A".to_string()));
+        assert_eq!(internal.staircase, Some("Escalier B".to_string()));
+    }
 }
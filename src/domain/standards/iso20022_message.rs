@@ -0,0 +1,684 @@
+//! ISO 20022 payment messages (pain.001 and pacs.008) and statements (camt.053 and camt.054), from which the postal
+//! addresses of every party are extracted
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    ops::Range,
+};
+
+use anyhow::{Context, anyhow};
+use quick_xml::{Reader, events::Event};
+
+use crate::{
+    AnyhowResult,
+    domain::{Address, Converted, ISO20022Address, ISO20022Party, ISO20022Version},
+};
+
+use super::iso20022_xml::{POSTAL_ADDRESS_TAG, local_name, read_postal_address, read_text_content};
+
+/// Tag of the root element of a message, whose namespace gives its version
+const DOCUMENT_TAG: &str = "Document";
+
+/// Tag of the name of a party or financial institution
+const NAME_TAG: &str = "Nm";
+
+/// Tag of the identification of a statement or notification
+const IDENTIFICATION_TAG: &str = "Id";
+
+/// Elements between a party or agent and its postal address, which do not identify the party
+const PARTY_WRAPPER_TAGS: [&str; 3] = ["FinInstnId", "BrnchId", "Pty"];
+
+/// Statement (camt.053) and notification (camt.054) elements, whose `<Id>` references the addresses they hold
+const STATEMENT_TAGS: [&str; 2] = ["Stmt", "Ntfctn"];
+
+/// Element grouping the parties related to a statement entry
+const RELATED_PARTIES_TAG: &str = "RltdPties";
+
+/// Type of an ISO 20022 payment message or statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ISO20022MessageType {
+    /// Customer credit transfer initiation (`<CstmrCdtTrfInitn>`)
+    Pain001,
+
+    /// FI to FI customer credit transfer (`<FIToFICstmrCdtTrf>`)
+    Pacs008,
+
+    /// Bank to customer statement (`<BkToCstmrStmt>`)
+    Camt053,
+
+    /// Bank to customer debit credit notification (`<BkToCstmrDbtCdtNtfctn>`)
+    Camt054,
+}
+
+impl ISO20022MessageType {
+    /// Message type of the root element of a message, inside `<Document>`
+    fn from_tag(tag: &str) -> Option<ISO20022MessageType> {
+        match tag {
+            "CstmrCdtTrfInitn" => Some(ISO20022MessageType::Pain001),
+            "FIToFICstmrCdtTrf" => Some(ISO20022MessageType::Pacs008),
+            "BkToCstmrStmt" => Some(ISO20022MessageType::Camt053),
+            "BkToCstmrDbtCdtNtfctn" => Some(ISO20022MessageType::Camt054),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ISO20022MessageType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ISO20022MessageType::Pain001 => write!(f, "pain.001"),
+            ISO20022MessageType::Pacs008 => write!(f, "pacs.008"),
+            ISO20022MessageType::Camt053 => write!(f, "camt.053"),
+            ISO20022MessageType::Camt054 => write!(f, "camt.054"),
+        }
+    }
+}
+
+/// Postal address of a party or agent found in a payment message
+#[derive(Debug, Clone, PartialEq)]
+pub struct ISO20022MessageAddress {
+    /// XPath of the `<PstlAdr>` in the message, such as `/Document/CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf[2]/Cdtr/PstlAdr`
+    pub xpath: String,
+
+    /// Tag of the party or agent element holding the address, such as `Cdtr` or `DbtrAgt`
+    pub party: String,
+
+    /// Name (`<Nm>`) of the party or agent
+    pub name: Option<String>,
+
+    /// Postal address
+    pub postal_address: ISO20022Address,
+
+    /// Identification (`<Id>`) of the statement or notification holding the address, in a camt.053 or camt.054
+    pub statement_reference: Option<String>,
+
+    /// Byte range of the `<PstlAdr>` element in the message, from its start tag to its end tag
+    pub span: Range<usize>,
+}
+
+impl ISO20022MessageAddress {
+    /// Whether the address is the one of a party related to a statement entry (`<RltdPties>`)
+    pub fn is_related_party(&self) -> bool {
+        self.xpath.contains(&format!("/{RELATED_PARTIES_TAG}/"))
+    }
+
+    /// Party (name and postal address) of the address
+    pub fn to_party(&self) -> ISO20022Party {
+        ISO20022Party {
+            name: self.name.clone(),
+            postal_address: self.postal_address.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Convert the address to the internal model, the name included, and report the alterations made
+    pub fn into_internal(self) -> AnyhowResult<Converted<Address>> {
+        self.to_party().into_internal()
+    }
+}
+
+/// Postal addresses of an ISO 20022 payment message
+#[derive(Debug, Clone, PartialEq)]
+pub struct ISO20022Message {
+    /// Type of the message
+    pub message_type: ISO20022MessageType,
+
+    /// Version of the postal address type, when the namespace of the `<Document>` tells it
+    pub version: Option<ISO20022Version>,
+
+    /// Postal addresses of the parties and agents, in document order
+    pub addresses: Vec<ISO20022MessageAddress>,
+}
+
+impl ISO20022Message {
+    /// Parse a pain.001, pacs.008, camt.053 or camt.054 message and extract every `<PstlAdr>` with its XPath, party and
+    /// name, and the reference of its statement.
+    ///
+    /// Namespace prefixes are ignored, and a message wrapped in a business message envelope (`<AppHdr>` and
+    /// `<Document>`) is accepted as well. A positional predicate is only written for repeated elements.
+    pub fn from_xml(xml: &str) -> AnyhowResult<Self> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+
+        let mut extractor = Extractor::default();
+        loop {
+            match reader.read_event().with_context(|| "Failed to read XML")? {
+                Event::Start(start) => {
+                    let tag = local_name(&start)?;
+                    match tag.as_str() {
+                        POSTAL_ADDRESS_TAG => {
+                            // The start tag, `<` and `>` included, ends at the current position
+                            let start_offset = reader.buffer_position() as usize - start.len() - 2;
+                            let step = extractor.step(tag);
+                            let postal_address = read_postal_address(&mut reader)?;
+                            let span = start_offset..reader.buffer_position() as usize;
+                            extractor.add_address(step, postal_address, span);
+                        }
+                        NAME_TAG => {
+                            extractor.step(tag);
+                            let name = read_text_content(&mut reader, NAME_TAG)?;
+                            extractor.set_name(name);
+                        }
+                        DOCUMENT_TAG => {
+                            extractor.version = start
+                                .attributes()
+                                .flatten()
+                                .filter(|attribute| attribute.key.as_ref().starts_with(b"xmlns"))
+                                .find_map(|attribute| {
+                                    ISO20022Version::from_namespace(
+                                        std::str::from_utf8(&attribute.value).ok()?,
+                                    )
+                                });
+                            extractor.open(tag);
+                        }
+                        IDENTIFICATION_TAG if extractor.in_statement() => {
+                            extractor.step(tag);
+                            let reference = read_text_content(&mut reader, IDENTIFICATION_TAG)?;
+                            extractor.set_statement_reference(reference);
+                        }
+                        _ => extractor.open(tag),
+                    }
+                }
+                Event::Empty(empty) => {
+                    extractor.step(local_name(&empty)?);
+                }
+                Event::End(_) => extractor.close(),
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        extractor.finish()
+    }
+
+    /// Replace the `<PstlAdr>` of the given addresses, in the message they were extracted from, by their postal address
+    /// serialized in the version of the message.
+    ///
+    /// Every other byte of the message is left unchanged. Each new `<PstlAdr>` keeps the namespace prefix and the
+    /// indentation of the one it replaces, or is written on a single line when the original one was.
+    pub fn replace_postal_addresses(
+        xml: &str,
+        addresses: &[ISO20022MessageAddress],
+        version: ISO20022Version,
+    ) -> String {
+        let mut addresses: Vec<&ISO20022MessageAddress> = addresses.iter().collect();
+        addresses.sort_by_key(|address| address.span.start);
+
+        let mut rewritten = String::with_capacity(xml.len());
+        let mut position = 0;
+        for address in addresses {
+            rewritten.push_str(&xml[position..address.span.start]);
+            rewritten.push_str(&postal_address_xml(xml, address, version));
+            position = address.span.end;
+        }
+        rewritten.push_str(&xml[position..]);
+
+        rewritten
+    }
+}
+
+/// XML of the postal address of a message address, laid out like the `<PstlAdr>` it replaces
+fn postal_address_xml(
+    xml: &str,
+    address: &ISO20022MessageAddress,
+    version: ISO20022Version,
+) -> String {
+    let original = &xml[address.span.clone()];
+    let prefix = original[1..]
+        .split_once(POSTAL_ADDRESS_TAG)
+        .map_or("", |(prefix, _)| prefix);
+    let generated = prefix_tags(&address.postal_address.to_versioned_xml(version), prefix);
+
+    if !original.contains('\n') {
+        return generated.lines().map(str::trim_start).collect();
+    }
+
+    // Indentation of the start tag, and the one added by each level of the original element
+    let line_start = xml[..address.span.start]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let indentation = &xml[line_start..address.span.start];
+    let indentation = if indentation.trim().is_empty() {
+        indentation
+    } else {
+        ""
+    };
+    let unit = original
+        .lines()
+        .nth(1)
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .and_then(|child_indentation| child_indentation.strip_prefix(indentation))
+        .filter(|unit| !unit.is_empty())
+        .unwrap_or("  ");
+
+    generated
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let content = line.trim_start();
+            // The generated XML is indented by 2 spaces
+            let depth = (line.len() - content.len()) / 2;
+            let base = if index == 0 { "" } else { indentation };
+
+            format!("{base}{}{content}", unit.repeat(depth))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Add a namespace prefix to every tag of a generated XML fragment, whose text content is escaped
+fn prefix_tags(xml: &str, prefix: &str) -> String {
+    if prefix.is_empty() {
+        return xml.to_string();
+    }
+
+    let mut prefixed = String::with_capacity(xml.len());
+    let mut chars = xml.chars().peekable();
+    while let Some(char) = chars.next() {
+        prefixed.push(char);
+        if char == '<' {
+            if let Some('/') = chars.peek() {
+                prefixed.push('/');
+                chars.next();
+            }
+            prefixed.push_str(prefix);
+        }
+    }
+
+    prefixed
+}
+
+/// Step of an XPath: an element with its position among the siblings of the same name
+#[derive(Debug, Clone)]
+struct Step {
+    parent: usize,
+    tag: String,
+    position: usize,
+}
+
+/// Element being read, with the addresses found in its direct children
+#[derive(Debug)]
+struct OpenElement {
+    id: usize,
+    step: Step,
+    name: Option<String>,
+    statement_reference: Option<String>,
+    addresses: Vec<usize>,
+}
+
+/// Address found in the message, whose XPath is only known once every sibling has been counted
+#[derive(Debug)]
+struct FoundAddress {
+    steps: Vec<Step>,
+    party: String,
+    name: Option<String>,
+    postal_address: ISO20022Address,
+    statement_reference: Option<String>,
+    span: Range<usize>,
+}
+
+/// State of the extraction while streaming a message
+#[derive(Debug, Default)]
+struct Extractor {
+    message_type: Option<ISO20022MessageType>,
+    version: Option<ISO20022Version>,
+    open_elements: Vec<OpenElement>,
+    last_id: usize,
+    // Number of children of each name, by parent element (0 for the document itself)
+    counts: HashMap<(usize, String), usize>,
+    addresses: Vec<FoundAddress>,
+}
+
+impl Extractor {
+    /// Count an element in its parent and return its XPath step
+    fn step(&mut self, tag: String) -> Step {
+        let parent = self.open_elements.last().map_or(0, |element| element.id);
+        let count = self.counts.entry((parent, tag.clone())).or_default();
+        *count += 1;
+
+        Step {
+            parent,
+            tag,
+            position: *count,
+        }
+    }
+
+    fn open(&mut self, tag: String) {
+        if self.message_type.is_none() {
+            self.message_type = ISO20022MessageType::from_tag(&tag);
+        }
+
+        let step = self.step(tag);
+        self.last_id += 1;
+        self.open_elements.push(OpenElement {
+            id: self.last_id,
+            step,
+            name: None,
+            statement_reference: None,
+            addresses: Vec::new(),
+        });
+    }
+
+    fn close(&mut self) {
+        self.open_elements.pop();
+    }
+
+    /// Give the name of the current element to its addresses, whichever comes first
+    fn set_name(&mut self, name: String) {
+        let Some(element) = self.open_elements.last_mut() else {
+            return;
+        };
+
+        for &index in &element.addresses {
+            self.addresses[index].name = Some(name.clone());
+        }
+        element.name = Some(name);
+    }
+
+    /// Whether the current element is a statement or notification
+    fn in_statement(&self) -> bool {
+        self.open_elements
+            .last()
+            .is_some_and(|element| STATEMENT_TAGS.contains(&element.step.tag.as_str()))
+    }
+
+    fn set_statement_reference(&mut self, reference: String) {
+        if let Some(element) = self.open_elements.last_mut() {
+            element.statement_reference = Some(reference);
+        }
+    }
+
+    fn add_address(&mut self, step: Step, postal_address: ISO20022Address, span: Range<usize>) {
+        let party = self
+            .open_elements
+            .iter()
+            .rev()
+            .map(|element| element.step.tag.as_str())
+            .find(|tag| !PARTY_WRAPPER_TAGS.contains(tag))
+            .unwrap_or_default()
+            .to_string();
+        let steps = self
+            .open_elements
+            .iter()
+            .map(|element| element.step.clone())
+            .chain([step])
+            .collect();
+
+        let statement_reference = self
+            .open_elements
+            .iter()
+            .rev()
+            .find_map(|element| element.statement_reference.clone());
+
+        let index = self.addresses.len();
+        let name = self.open_elements.last_mut().and_then(|element| {
+            element.addresses.push(index);
+            element.name.clone()
+        });
+
+        self.addresses.push(FoundAddress {
+            steps,
+            party,
+            name,
+            postal_address,
+            statement_reference,
+            span,
+        });
+    }
+
+    fn finish(self) -> AnyhowResult<ISO20022Message> {
+        let message_type = self.message_type.ok_or_else(|| {
+            anyhow!(
+                "No pain.001 (<CstmrCdtTrfInitn>), pacs.008 (<FIToFICstmrCdtTrf>), camt.053 (<BkToCstmrStmt>) or \
+                 camt.054 (<BkToCstmrDbtCdtNtfctn>) message found"
+            )
+        })?;
+
+        let addresses = self
+            .addresses
+            .into_iter()
+            .map(|found| ISO20022MessageAddress {
+                xpath: xpath(&found.steps, &self.counts),
+                party: found.party,
+                name: found.name,
+                postal_address: found.postal_address,
+                statement_reference: found.statement_reference,
+                span: found.span,
+            })
+            .collect();
+
+        Ok(ISO20022Message {
+            message_type,
+            version: self.version,
+            addresses,
+        })
+    }
+}
+
+/// XPath of a list of steps, with a position for the elements that have siblings of the same name
+fn xpath(steps: &[Step], counts: &HashMap<(usize, String), usize>) -> String {
+    steps
+        .iter()
+        .map(|step| {
+            let siblings = counts
+                .get(&(step.parent, step.tag.clone()))
+                .copied()
+                .unwrap_or_default();
+
+            if siblings > 1 {
+                format!("/{}[{}]", step.tag, step.position)
+            } else {
+                format!("/{}", step.tag)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAIN_001: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:pain.001.001.09">
+  <CstmrCdtTrfInitn>
+    <GrpHdr><MsgId>MSG-1</MsgId><InitgPty><Nm>ACME SARL</Nm></InitgPty></GrpHdr>
+    <PmtInf>
+      <Dbtr>
+        <Nm>ACME SARL</Nm>
+        <PstlAdr><StrtNm>RUE DES FLEURS</StrtNm><BldgNb>25</BldgNb><PstCd>33500</PstCd><TwnNm>LIBOURNE</TwnNm><Ctry>FR</Ctry></PstlAdr>
+      </Dbtr>
+      <DbtrAgt>
+        <FinInstnId><BICFI>BNPAFRPP</BICFI><Nm>BNP PARIBAS</Nm><PstlAdr><TwnNm>PARIS</TwnNm><Ctry>FR</Ctry></PstlAdr></FinInstnId>
+      </DbtrAgt>
+      <CdtTrfTxInf>
+        <Cdtr><Nm>Monsieur Jean DURAND</Nm><PstlAdr><PstCd>33380</PstCd><TwnNm>MIOS</TwnNm><Ctry>FR</Ctry></PstlAdr></Cdtr>
+      </CdtTrfTxInf>
+      <CdtTrfTxInf>
+        <Cdtr><PstlAdr><AdrLine>1 PLACE DU MARCHE</AdrLine><AdrLine>69001 LYON</AdrLine><Ctry>FR</Ctry></PstlAdr></Cdtr>
+        <UltmtCdtr><Nm>Madame Marie MARTIN</Nm><PstlAdr><TwnNm>LYON</TwnNm><Ctry>FR</Ctry></PstlAdr></UltmtCdtr>
+      </CdtTrfTxInf>
+    </PmtInf>
+  </CstmrCdtTrfInitn>
+</Document>"#;
+
+    #[test]
+    fn from_xml_extracts_every_party_address_with_its_xpath() {
+        let message = ISO20022Message::from_xml(PAIN_001).unwrap();
+
+        assert_eq!(message.message_type, ISO20022MessageType::Pain001);
+        assert_eq!(
+            message
+                .addresses
+                .iter()
+                .map(|address| (address.xpath.as_str(), address.party.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("/Document/CstmrCdtTrfInitn/PmtInf/Dbtr/PstlAdr", "Dbtr"),
+                (
+                    "/Document/CstmrCdtTrfInitn/PmtInf/DbtrAgt/FinInstnId/PstlAdr",
+                    "DbtrAgt"
+                ),
+                (
+                    "/Document/CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf[1]/Cdtr/PstlAdr",
+                    "Cdtr"
+                ),
+                (
+                    "/Document/CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf[2]/Cdtr/PstlAdr",
+                    "Cdtr"
+                ),
+                (
+                    "/Document/CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf[2]/UltmtCdtr/PstlAdr",
+                    "UltmtCdtr"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn from_xml_pairs_each_address_with_the_name_of_its_party() {
+        let message = ISO20022Message::from_xml(PAIN_001).unwrap();
+
+        let names: Vec<Option<&str>> = message
+            .addresses
+            .iter()
+            .map(|address| address.name.as_deref())
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                Some("ACME SARL"),
+                Some("BNP PARIBAS"),
+                Some("Monsieur Jean DURAND"),
+                None,
+                Some("Madame Marie MARTIN"),
+            ]
+        );
+        assert_eq!(message.addresses[0].postal_address.town_name, "LIBOURNE");
+    }
+
+    #[test]
+    fn from_xml_reads_prefixed_pacs008_and_rejects_other_messages() {
+        let pacs008 = r#"<doc:Document xmlns:doc="urn:iso:std:iso:20022:tech:xsd:pacs.008.001.08">
+            <doc:FIToFICstmrCdtTrf><doc:CdtTrfTxInf>
+                <doc:Dbtr><doc:PstlAdr><doc:PstBx>BP 40122</doc:PstBx><doc:Ctry>FR</doc:Ctry></doc:PstlAdr><doc:Nm>ACME SARL</doc:Nm></doc:Dbtr>
+            </doc:CdtTrfTxInf></doc:FIToFICstmrCdtTrf>
+        </doc:Document>"#;
+
+        let message = ISO20022Message::from_xml(pacs008).unwrap();
+
+        assert_eq!(message.message_type, ISO20022MessageType::Pacs008);
+        assert_eq!(message.addresses[0].name, Some("ACME SARL".to_string()));
+        assert!(
+            ISO20022Message::from_xml("<Document><BkToCstmrAcctRpt/></Document>")
+                .unwrap_err()
+                .to_string()
+                .contains("pain.001")
+        );
+    }
+
+    #[test]
+    fn from_xml_gives_the_span_of_each_postal_address() {
+        let message = ISO20022Message::from_xml(PAIN_001).unwrap();
+
+        for address in &message.addresses {
+            let element = &PAIN_001[address.span.clone()];
+            assert!(element.starts_with("<PstlAdr>"));
+            assert!(element.ends_with("</PstlAdr>"));
+        }
+    }
+
+    #[test]
+    fn replace_postal_addresses_only_rewrites_the_given_addresses() {
+        let message = ISO20022Message::from_xml(PAIN_001).unwrap();
+        let mut address = message.addresses[3].clone();
+        address.postal_address = ISO20022Address {
+            street_name: "PLACE DU MARCHE".to_string(),
+            building_number: Some("1".to_string()),
+            post_code: "69001".to_string(),
+            town_name: "LYON".to_string(),
+            country: "FR".to_string(),
+            ..Default::default()
+        };
+
+        let rewritten = ISO20022Message::replace_postal_addresses(
+            PAIN_001,
+            &[address],
+            ISO20022Version::PostalAddress24,
+        );
+
+        assert_eq!(
+            rewritten,
+            PAIN_001.replace(
+                "<AdrLine>1 PLACE DU MARCHE</AdrLine><AdrLine>69001 LYON</AdrLine><Ctry>FR</Ctry>",
+                "<StrtNm>PLACE DU MARCHE</StrtNm><BldgNb>1</BldgNb><PstCd>69001</PstCd><TwnNm>LYON</TwnNm><Ctry>FR</Ctry>"
+            )
+        );
+    }
+
+    #[test]
+    fn replace_postal_addresses_keeps_prefix_and_indentation() {
+        let xml = "<doc:Document xmlns:doc=\"urn:iso:std:iso:20022:tech:xsd:pain.001.001.09\">\n\t<doc:CstmrCdtTrfInitn>\n\t\t<doc:Cdtr>\n\t\t\t<doc:PstlAdr>\n\t\t\t\t<doc:AdrLine>69001 LYON</doc:AdrLine>\n\t\t\t</doc:PstlAdr>\n\t\t</doc:Cdtr>\n\t</doc:CstmrCdtTrfInitn>\n</doc:Document>";
+        let mut address = ISO20022Message::from_xml(xml).unwrap().addresses[0].clone();
+        address.postal_address = ISO20022Address {
+            town_name: "LYON".to_string(),
+            country: "FR".to_string(),
+            ..Default::default()
+        };
+
+        let rewritten = ISO20022Message::replace_postal_addresses(
+            xml,
+            &[address],
+            ISO20022Version::PostalAddress24,
+        );
+
+        assert!(rewritten.contains(
+            "\t\t\t<doc:PstlAdr>\n\t\t\t\t<doc:TwnNm>LYON</doc:TwnNm>\n\t\t\t\t<doc:Ctry>FR</doc:Ctry>\n\t\t\t</doc:PstlAdr>\n\t\t</doc:Cdtr>"
+        ));
+    }
+
+    #[test]
+    fn from_xml_gives_statement_reference_and_related_parties_of_camt053_entries() {
+        let camt053 = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.08">
+  <BkToCstmrStmt>
+    <GrpHdr><MsgId>STMT-MSG-1</MsgId></GrpHdr>
+    <Stmt>
+      <Id>STMT-2025-03-14</Id>
+      <Acct><Ownr><Nm>ACME SARL</Nm><PstlAdr><TwnNm>LIBOURNE</TwnNm><Ctry>FR</Ctry></PstlAdr></Ownr></Acct>
+      <Ntry>
+        <NtryDtls><TxDtls><RltdPties>
+          <Dbtr><Pty><Nm>Monsieur Jean DURAND</Nm><PstlAdr><TwnNm>MIOS</TwnNm><Ctry>FR</Ctry></PstlAdr></Pty></Dbtr>
+        </RltdPties></TxDtls></NtryDtls>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>"#;
+
+        let message = ISO20022Message::from_xml(camt053).unwrap();
+
+        assert_eq!(message.message_type, ISO20022MessageType::Camt053);
+        assert!(!message.addresses[0].is_related_party());
+        let debtor = &message.addresses[1];
+        assert!(debtor.is_related_party());
+        assert_eq!(debtor.party, "Dbtr");
+        assert_eq!(debtor.name, Some("Monsieur Jean DURAND".to_string()));
+        assert_eq!(
+            debtor.statement_reference,
+            Some("STMT-2025-03-14".to_string())
+        );
+    }
+
+    #[test]
+    fn into_internal_keeps_the_party_name() {
+        let message = ISO20022Message::from_xml(PAIN_001).unwrap();
+
+        let internal = message.addresses[2].clone().into_internal().unwrap();
+
+        assert_eq!(
+            internal.address.name,
+            Some("Monsieur Jean DURAND".to_string())
+        );
+        assert_eq!(internal.address.town_name, "MIOS");
+    }
+}
@@ -0,0 +1,388 @@
+//! ISO 20022 party, pairing the name of a creditor or debtor with its postal address
+
+use std::fmt::{self, Display, Formatter};
+
+use anyhow::{Context, anyhow};
+use quick_xml::{
+    Reader, Writer,
+    events::{BytesEnd, BytesStart, Event},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AnyhowResult,
+    domain::{
+        Address, ConversionReport, Converted, ISO20022Address, ISO20022AddressMode,
+        ISO20022Version, Recipient, RecipientKind, recipient::rendered_name,
+    },
+};
+
+use super::iso20022_xml::{
+    POSTAL_ADDRESS_TAG, local_name, read_postal_address, read_text_content, write_postal_address,
+    write_text_element,
+};
+
+/// Tag of the party name element
+const NAME_TAG: &str = "Nm";
+
+/// Role of a party in a payment message, which gives the tag of its element
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ISO20022PartyRole {
+    /// Creditor (`<Cdtr>`)
+    #[default]
+    Creditor,
+
+    /// Debtor (`<Dbtr>`)
+    Debtor,
+
+    /// Ultimate creditor (`<UltmtCdtr>`)
+    UltimateCreditor,
+
+    /// Ultimate debtor (`<UltmtDbtr>`)
+    UltimateDebtor,
+}
+
+impl ISO20022PartyRole {
+    const ALL: [ISO20022PartyRole; 4] = [
+        ISO20022PartyRole::Creditor,
+        ISO20022PartyRole::Debtor,
+        ISO20022PartyRole::UltimateCreditor,
+        ISO20022PartyRole::UltimateDebtor,
+    ];
+
+    /// Tag of the party element
+    pub fn tag(self) -> &'static str {
+        match self {
+            ISO20022PartyRole::Creditor => "Cdtr",
+            ISO20022PartyRole::Debtor => "Dbtr",
+            ISO20022PartyRole::UltimateCreditor => "UltmtCdtr",
+            ISO20022PartyRole::UltimateDebtor => "UltmtDbtr",
+        }
+    }
+
+    fn from_tag(tag: &[u8]) -> Option<ISO20022PartyRole> {
+        Self::ALL
+            .into_iter()
+            .find(|role| role.tag().as_bytes() == tag)
+    }
+}
+
+/// ISO 20022 party: the name (`<Nm>`) and postal address (`<PstlAdr>`) of a creditor, debtor or ultimate party
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ISO20022Party {
+    /// Role of the party, giving the tag of its element (not part of the JSON form)
+    #[serde(skip)]
+    pub role: ISO20022PartyRole,
+
+    /// Name of the party
+    #[serde(rename = "Nm")]
+    pub name: Option<String>,
+
+    /// Postal address of the party
+    #[serde(rename = "PstlAdr")]
+    pub postal_address: ISO20022Address,
+}
+
+impl Display for ISO20022Party {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_xml())
+    }
+}
+
+impl ISO20022Party {
+    /// Convert an internal address to an ISO 20022 party in the given mode and report the alterations made.
+    ///
+    /// Unlike [ISO20022Address::render], the name is kept in `<Nm>`, rendered from the recipient: given names before
+    /// the family name in uppercase, or the company name. A name that the rendering would only change in case, accents
+    /// or spacing is kept as given, and another rendering is reported as moved. The person reached within a business
+    /// goes to `<Dept>`.
+    pub fn render(address: Address, mode: ISO20022AddressMode) -> Converted<ISO20022Party> {
+        let mut report = ConversionReport::default();
+        let mut address = address;
+        let recipient = address.recipient();
+        if let Some(person_name) = recipient
+            .as_ref()
+            .filter(|recipient| recipient.is_company())
+            .and_then(Recipient::person_name)
+        {
+            // The person reached within a business is addressed through its department
+            if address.recipient_kind() == RecipientKind::Business && address.department.is_none() {
+                report.moved("recipient", format!("'{person_name}' moved into <Dept>"));
+                address.department = Some(person_name);
+            } else {
+                report.dropped("recipient", format!("'{person_name}' has no <Nm> element"));
+            }
+        }
+
+        let name = recipient
+            .map(|recipient| recipient.to_string())
+            .filter(|name| !name.is_empty())
+            .map(|rendered| rendered_name(address.name.as_deref(), rendered));
+        if let Some((given, name)) =
+            (address.name.as_ref().zip(name.as_ref())).filter(|(given, name)| given != name)
+        {
+            report.moved("name", format!("'{given}' rendered as '{name}' in <Nm>"));
+        }
+        let converted = ISO20022Address::render(
+            Address {
+                name: None,
+                recipient: None,
+                ..address
+            },
+            mode,
+        );
+        report.merge(converted.report);
+
+        Converted::new(
+            ISO20022Party {
+                role: ISO20022PartyRole::default(),
+                name,
+                postal_address: converted.address,
+            },
+            report,
+        )
+    }
+
+    /// Convert the party to the internal model, the name included, and report the alterations made
+    pub fn into_internal(self) -> AnyhowResult<Converted<Address>> {
+        let mut converted = self.postal_address.into_internal()?;
+        converted.address.name = self.name.filter(|name| !name.trim().is_empty());
+
+        Ok(converted)
+    }
+
+    /// Serialize the party as an ISO 20022 XML fragment such as `<Cdtr><Nm/><PstlAdr/></Cdtr>`
+    pub fn to_xml(&self) -> String {
+        self.to_versioned_xml(ISO20022Version::default())
+    }
+
+    /// Serialize the party as an ISO 20022 XML fragment whose postal address is of the given version
+    pub fn to_versioned_xml(&self, version: ISO20022Version) -> String {
+        let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+
+        // Writing into a `Vec` cannot fail
+        write_party(&mut writer, self, version).expect("Writing XML into memory should not fail");
+
+        String::from_utf8(writer.into_inner()).expect("Generated XML should be valid UTF-8")
+    }
+
+    /// Parse the first party element (`<Cdtr>`, `<Dbtr>`, `<UltmtCdtr>` or `<UltmtDbtr>`) found in an XML fragment or
+    /// document, or a bare `<PstlAdr>` as a party without name.
+    ///
+    /// Namespace prefixes are ignored, as well as the party elements other than the name and postal address.
+    pub fn from_xml(xml: &str) -> AnyhowResult<Self> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+
+        loop {
+            match reader.read_event().with_context(|| "Failed to read XML")? {
+                Event::Start(start) => {
+                    if let Some(role) = ISO20022PartyRole::from_tag(start.local_name().as_ref()) {
+                        return read_party(&mut reader, role);
+                    }
+
+                    if start.local_name().as_ref() == POSTAL_ADDRESS_TAG.as_bytes() {
+                        return Ok(ISO20022Party {
+                            postal_address: read_postal_address(&mut reader)?,
+                            ..Default::default()
+                        });
+                    }
+                }
+                Event::Eof => {
+                    return Err(anyhow!(
+                        "No party or <{}> element found",
+                        POSTAL_ADDRESS_TAG
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Write a party element with its name and postal address of the given version
+fn write_party<W: std::io::Write>(
+    writer: &mut Writer<W>,
+    party: &ISO20022Party,
+    version: ISO20022Version,
+) -> AnyhowResult<()> {
+    let tag = party.role.tag();
+    writer.write_event(Event::Start(BytesStart::new(tag)))?;
+
+    if let Some(name) = party.name.as_deref().filter(|name| !name.is_empty()) {
+        write_text_element(writer, NAME_TAG, name)?;
+    }
+    write_postal_address(writer, &party.postal_address, version)?;
+
+    writer.write_event(Event::End(BytesEnd::new(tag)))?;
+
+    Ok(())
+}
+
+/// Read the content of a party element whose start tag has just been consumed
+fn read_party(reader: &mut Reader<&[u8]>, role: ISO20022PartyRole) -> AnyhowResult<ISO20022Party> {
+    let mut party = ISO20022Party {
+        role,
+        ..Default::default()
+    };
+
+    loop {
+        match reader.read_event().with_context(|| "Failed to read XML")? {
+            Event::Start(start) => match local_name(&start)?.as_str() {
+                NAME_TAG => party.name = Some(read_text_content(reader, NAME_TAG)?),
+                POSTAL_ADDRESS_TAG => party.postal_address = read_postal_address(reader)?,
+                // Identification, country of residence, contact details...
+                _ => {
+                    reader
+                        .read_to_end(start.name())
+                        .with_context(|| "Failed to read XML")?;
+                }
+            },
+            Event::End(_) => return Ok(party),
+            Event::Eof => {
+                return Err(anyhow!("Unexpected end of XML inside <{}>", role.tag()));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::AlterationKind;
+
+    use super::*;
+
+    fn party() -> ISO20022Party {
+        ISO20022Party {
+            role: ISO20022PartyRole::Creditor,
+            name: Some("Monsieur Jean DURAND".to_string()),
+            postal_address: ISO20022Address {
+                street_name: "RUE DES FLEURS".to_string(),
+                building_number: Some("25".to_string()),
+                post_code: "33500".to_string(),
+                town_name: "LIBOURNE".to_string(),
+                country: "FR".to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn to_xml_wraps_name_and_postal_address_in_the_party_element() {
+        assert_eq!(
+            party().to_xml(),
+            "<Cdtr>\n  <Nm>Monsieur Jean DURAND</Nm>\n  <PstlAdr>\n    <StrtNm>RUE DES FLEURS</StrtNm>\n    <BldgNb>25</BldgNb>\n    <PstCd>33500</PstCd>\n    <TwnNm>LIBOURNE</TwnNm>\n    <Ctry>FR</Ctry>\n  </PstlAdr>\n</Cdtr>"
+        );
+    }
+
+    #[test]
+    fn from_xml_reads_party_role_and_skips_other_party_elements() {
+        let xml = r#"<doc:UltmtDbtr xmlns:doc="urn:iso:std:iso:20022:tech:xsd:pain.001.001.09">
+            <doc:Nm>ACME SARL</doc:Nm>
+            <doc:PstlAdr><doc:TwnNm>LIBOURNE</doc:TwnNm><doc:Ctry>FR</doc:Ctry></doc:PstlAdr>
+            <doc:Id><doc:OrgId><doc:AnyBIC>ACMEFRPP</doc:AnyBIC></doc:OrgId></doc:Id>
+        </doc:UltmtDbtr>"#;
+
+        let parsed = ISO20022Party::from_xml(xml).unwrap();
+
+        assert_eq!(parsed.role, ISO20022PartyRole::UltimateDebtor);
+        assert_eq!(parsed.name, Some("ACME SARL".to_string()));
+        assert_eq!(parsed.postal_address.town_name, "LIBOURNE".to_string());
+    }
+
+    #[test]
+    fn from_xml_round_trips_and_accepts_bare_postal_address() {
+        let party = party();
+
+        assert_eq!(ISO20022Party::from_xml(&party.to_xml()).unwrap(), party);
+        assert_eq!(
+            ISO20022Party::from_xml(&party.postal_address.to_xml())
+                .unwrap()
+                .name,
+            None
+        );
+    }
+
+    #[test]
+    fn render_keeps_name_and_into_internal_restores_it() {
+        let address = Address {
+            name: Some("Monsieur Jean DURAND".to_string()),
+            ..Address::dummy()
+        };
+
+        let rendered = ISO20022Party::render(address.clone(), ISO20022AddressMode::Structured);
+        let internal = rendered.address.clone().into_internal().unwrap();
+
+        assert!(
+            rendered
+                .report
+                .warnings
+                .iter()
+                .all(|warning| warning.field != "name")
+        );
+        assert_eq!(internal.address.name, address.name);
+    }
+
+    #[test]
+    fn render_puts_given_names_before_uppercase_family_name() {
+        let address = Address {
+            name: Some("DURAND Jean".to_string()),
+            ..Address::dummy()
+        };
+
+        let rendered = ISO20022Party::render(address, ISO20022AddressMode::Structured);
+
+        assert_eq!(rendered.address.name, Some("Jean DURAND".to_string()));
+    }
+
+    #[test]
+    fn render_keeps_name_as_given_or_reports_its_rendering() {
+        let rendered = ISO20022Party::render(Address::dummy(), ISO20022AddressMode::Structured);
+        assert_eq!(rendered.address.name, Some("John Doe".to_string()));
+        assert!(
+            rendered
+                .report
+                .warnings
+                .iter()
+                .all(|warning| warning.field != "name")
+        );
+
+        let address = Address {
+            name: Some("DURAND Jean".to_string()),
+            ..Address::dummy()
+        };
+        let rendered = ISO20022Party::render(address, ISO20022AddressMode::Structured);
+        assert_eq!(rendered.address.name, Some("Jean DURAND".to_string()));
+        assert!(
+            rendered
+                .report
+                .warnings
+                .iter()
+                .any(|warning| warning.field == "name" && warning.kind == AlterationKind::Moved)
+        );
+    }
+
+    #[test]
+    fn render_moves_person_of_a_business_into_department() {
+        let address = Address {
+            recipient: Some(Recipient {
+                given_names: Some("Anne".to_string()),
+                family_name: Some("Durand".to_string()),
+                company_name: Some("ACME SARL".to_string()),
+                ..Default::default()
+            }),
+            ..Address::dummy()
+        };
+
+        let rendered = ISO20022Party::render(address, ISO20022AddressMode::Structured);
+
+        assert_eq!(rendered.address.name, Some("ACME SARL".to_string()));
+        assert_eq!(
+            rendered.address.postal_address.department,
+            Some("Anne DURAND".to_string())
+        );
+        assert!(rendered.report.is_lossless());
+    }
+}
@@ -0,0 +1,473 @@
+//! Rendering of an internal address as a structured, hybrid or unstructured ISO 20022 postal address
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Address, Converted, ISO20022Address};
+
+use super::iso20022_version::CARE_OF_PREFIX;
+
+/// Maximum number of characters of an `<AdrLine>`
+pub const MAX_ADDRESS_LINE_LENGTH: usize = 70;
+
+/// Maximum number of `<AdrLine>` of an unstructured address
+pub const MAX_ADDRESS_LINES: usize = 7;
+
+/// Maximum number of `<AdrLine>` of a hybrid address (CBPR+ and SEPA market practice)
+pub const MAX_HYBRID_ADDRESS_LINES: usize = 2;
+
+/// Separator of the elements folded into the same `<AdrLine>`
+pub(super) const ELEMENT_SEPARATOR: &str = ", ";
+
+/// How the elements of an address are rendered in ISO 20022, depending on what the target scheme accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ISO20022AddressMode {
+    /// Every element in its own structured element
+    #[default]
+    Structured,
+
+    /// Town, postal code and country structured, the rest folded into at most 2 `<AdrLine>`
+    Hybrid,
+
+    /// Only the country structured, the rest in at most 7 `<AdrLine>`
+    Unstructured,
+}
+
+/// Structured element that can be folded into an `<AdrLine>`
+#[derive(Debug, Clone, Copy)]
+enum Element {
+    CareOf,
+    Department,
+    SubDepartment,
+    UnitNumber,
+    Room,
+    Floor,
+    BuildingName,
+    Street,
+    PostBox,
+    TownLocation,
+    PostCodeAndTown,
+    District,
+    CountrySubDivision,
+}
+
+/// Elements folded into the hybrid address lines, in reading order
+const HYBRID_ELEMENTS: [Element; 9] = [
+    Element::CareOf,
+    Element::Department,
+    Element::SubDepartment,
+    Element::UnitNumber,
+    Element::Room,
+    Element::Floor,
+    Element::BuildingName,
+    Element::Street,
+    Element::PostBox,
+];
+
+/// Elements of each unstructured address line, in reading order
+const UNSTRUCTURED_LINES: [&[Element]; 7] = [
+    &[Element::Department, Element::SubDepartment],
+    &[Element::CareOf, Element::UnitNumber, Element::Room],
+    &[Element::Floor, Element::BuildingName],
+    &[Element::Street],
+    &[Element::PostBox, Element::TownLocation],
+    &[Element::PostCodeAndTown, Element::District],
+    &[Element::CountrySubDivision],
+];
+
+impl ISO20022Address {
+    /// Convert an internal address to ISO 20022 in the given mode and report the alterations made.
+    ///
+    /// Elements folded into an `<AdrLine>` are reported as moved. In hybrid mode, the elements that do not fit in the
+    /// 2 lines stay structured, and existing lines beyond the 2nd are merged into it or dropped. In unstructured mode,
+    /// the existing lines follow the ones of the structured elements, and the lines beyond the 7th are dropped.
+    pub fn render(address: Address, mode: ISO20022AddressMode) -> Converted<ISO20022Address> {
+        let mut converted = ISO20022Address::from_internal(address);
+
+        match mode {
+            ISO20022AddressMode::Structured => {}
+            ISO20022AddressMode::Hybrid => converted.fold_hybrid(),
+            ISO20022AddressMode::Unstructured => converted.fold_unstructured(),
+        }
+
+        converted
+    }
+}
+
+impl Converted<ISO20022Address> {
+    fn fold_hybrid(&mut self) {
+        // Lines beyond the 2nd are appended to it when they fit, or else dropped
+        if self.address.address_lines.len() > MAX_HYBRID_ADDRESS_LINES {
+            let extra_lines = self
+                .address
+                .address_lines
+                .split_off(MAX_HYBRID_ADDRESS_LINES);
+            for line in extra_lines {
+                if append_to_lines(&mut self.address.address_lines, &line) {
+                    self.report.moved(
+                        "address_lines",
+                        format!("'{line}' merged into <AdrLine> {MAX_HYBRID_ADDRESS_LINES}"),
+                    );
+                } else {
+                    self.report.dropped(
+                        "address_lines",
+                        format!("'{line}' exceeds the {MAX_HYBRID_ADDRESS_LINES} <AdrLine> of a hybrid address"),
+                    );
+                }
+            }
+        }
+
+        for element in HYBRID_ELEMENTS {
+            let Some(text) = element.text(&self.address) else {
+                continue;
+            };
+
+            if append_to_lines(&mut self.address.address_lines, &text) {
+                self.fold(element, &text);
+            }
+        }
+    }
+
+    fn fold_unstructured(&mut self) {
+        // The lines of the structured elements come first, so that the existing lines are the ones left out
+        let existing_lines = std::mem::take(&mut self.address.address_lines);
+
+        for elements in UNSTRUCTURED_LINES {
+            let texts: Vec<String> = elements
+                .iter()
+                .filter_map(|element| element.text(&self.address))
+                .collect();
+            if texts.is_empty() {
+                continue;
+            }
+
+            for element in elements {
+                if let Some(text) = element.text(&self.address) {
+                    self.fold(*element, &text);
+                }
+            }
+
+            self.address
+                .address_lines
+                .extend(wrap(&texts.join(ELEMENT_SEPARATOR)));
+        }
+        self.address.address_lines.extend(existing_lines);
+
+        if self.address.address_lines.len() > MAX_ADDRESS_LINES {
+            for line in self.address.address_lines.split_off(MAX_ADDRESS_LINES) {
+                self.report.dropped(
+                    "address_lines",
+                    format!("'{line}' exceeds the {MAX_ADDRESS_LINES} <AdrLine> of an unstructured address"),
+                );
+            }
+        }
+    }
+
+    fn fold(&mut self, element: Element, text: &str) {
+        element.clear(&mut self.address);
+        self.report
+            .moved(element.field(), format!("'{text}' folded into <AdrLine>"));
+    }
+}
+
+impl Element {
+    /// Internal field reported when the element is folded
+    fn field(self) -> &'static str {
+        match self {
+            Element::CareOf => "care_of",
+            Element::Department => "department",
+            Element::SubDepartment => "sub_department",
+            Element::UnitNumber => "unit",
+            Element::Room => "room",
+            Element::Floor => "floor",
+            Element::BuildingName => "building_name",
+            Element::Street => "street_name",
+            Element::PostBox => "post_box",
+            Element::TownLocation => "town_location_name",
+            Element::PostCodeAndTown => "town_name",
+            Element::District => "district_name",
+            Element::CountrySubDivision => "country_sub_division",
+        }
+    }
+
+    /// Text of the element in an address line, if present
+    fn text(self, address: &ISO20022Address) -> Option<String> {
+        let text = match self {
+            Element::CareOf => address
+                .care_of
+                .as_ref()
+                .map(|care_of| format!("{CARE_OF_PREFIX}{care_of}")),
+            Element::Department => address.department.clone(),
+            Element::SubDepartment => address.sub_department.clone(),
+            Element::UnitNumber => address.unit_number.clone(),
+            Element::Room => address.room.clone(),
+            Element::Floor => address.floor.clone(),
+            Element::BuildingName => address.building_name.clone(),
+            Element::Street => Some(join_words([
+                address.building_number.as_deref().unwrap_or_default(),
+                &address.street_name,
+            ])),
+            Element::PostBox => address.post_box.clone(),
+            Element::TownLocation => address.town_location_name.clone(),
+            Element::PostCodeAndTown => {
+                Some(join_words([address.post_code.as_str(), &address.town_name]))
+            }
+            Element::District => address.district_name.clone(),
+            Element::CountrySubDivision => address.country_sub_division.clone(),
+        };
+
+        text.filter(|text| !text.trim().is_empty())
+    }
+
+    fn clear(self, address: &mut ISO20022Address) {
+        match self {
+            Element::CareOf => address.care_of = None,
+            Element::Department => address.department = None,
+            Element::SubDepartment => address.sub_department = None,
+            Element::UnitNumber => address.unit_number = None,
+            Element::Room => address.room = None,
+            Element::Floor => address.floor = None,
+            Element::BuildingName => address.building_name = None,
+            Element::Street => {
+                address.building_number = None;
+                address.street_name = String::new();
+            }
+            Element::PostBox => address.post_box = None,
+            Element::TownLocation => address.town_location_name = None,
+            Element::PostCodeAndTown => {
+                address.post_code = String::new();
+                address.town_name = String::new();
+            }
+            Element::District => address.district_name = None,
+            Element::CountrySubDivision => address.country_sub_division = None,
+        }
+    }
+}
+
+/// Append a text to the last hybrid line if it fits, or else to a new line if one is left
+fn append_to_lines(lines: &mut Vec<String>, text: &str) -> bool {
+    let length = text.chars().count();
+    if length > MAX_ADDRESS_LINE_LENGTH {
+        return false;
+    }
+
+    let line_count = lines.len();
+
+    match lines.last_mut() {
+        Some(last)
+            if last.chars().count() + ELEMENT_SEPARATOR.len() + length
+                <= MAX_ADDRESS_LINE_LENGTH =>
+        {
+            last.push_str(ELEMENT_SEPARATOR);
+            last.push_str(text);
+            true
+        }
+        _ if line_count < MAX_HYBRID_ADDRESS_LINES => {
+            lines.push(text.to_string());
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Split a text into lines of at most 70 characters, at word boundaries
+pub(super) fn wrap(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line)
+                if line.chars().count() + 1 + word.chars().count() <= MAX_ADDRESS_LINE_LENGTH =>
+            {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => {
+                // A word longer than a line is cut
+                let chars: Vec<char> = word.chars().collect();
+                lines.extend(
+                    chars
+                        .chunks(MAX_ADDRESS_LINE_LENGTH)
+                        .map(|chunk| chunk.iter().collect::<String>()),
+                );
+            }
+        }
+    }
+
+    lines
+}
+
+fn join_words<'a>(words: impl IntoIterator<Item = &'a str>) -> String {
+    words
+        .into_iter()
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::AlterationKind;
+
+    use super::*;
+
+    fn address() -> Address {
+        Address {
+            name: None,
+            room: Some("Appartement 2".to_string()),
+            floor: Some("Entrée A".to_string()),
+            building_name: Some("Bâtiment Jonquille".to_string()),
+            street_name: "RUE DES FLEURS".to_string(),
+            building_number: Some("25".to_string()),
+            post_code: "33380".to_string(),
+            town_name: "MIOS".to_string(),
+            town_location_name: Some("CAUDOS".to_string()),
+            country: "FR".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn render_structured_keeps_every_element_structured() {
+        let converted = ISO20022Address::render(address(), ISO20022AddressMode::Structured);
+
+        assert_eq!(converted, ISO20022Address::from_internal(address()));
+        assert!(converted.address.address_lines.is_empty());
+    }
+
+    #[test]
+    fn render_hybrid_folds_delivery_details_into_two_lines() {
+        let converted = ISO20022Address::render(address(), ISO20022AddressMode::Hybrid);
+
+        assert_eq!(
+            converted.address,
+            ISO20022Address {
+                post_code: "33380".to_string(),
+                town_name: "MIOS".to_string(),
+                town_location_name: Some("CAUDOS".to_string()),
+                country: "FR".to_string(),
+                address_lines: vec![
+                    "Appartement 2, Entrée A, Bâtiment Jonquille, 25 RUE DES FLEURS".to_string()
+                ],
+                ..Default::default()
+            }
+        );
+        assert!(converted.report.is_lossless());
+    }
+
+    #[test]
+    fn render_hybrid_keeps_structured_what_does_not_fit_in_two_lines() {
+        let long = "X".repeat(65);
+        let converted = ISO20022Address::render(
+            Address {
+                department: Some(long.clone()),
+                sub_department: Some(long.clone()),
+                ..address()
+            },
+            ISO20022AddressMode::Hybrid,
+        );
+
+        assert_eq!(converted.address.address_lines, vec![long.clone(), long]);
+        assert_eq!(converted.address.room, Some("Appartement 2".to_string()));
+        assert_eq!(converted.address.street_name, "RUE DES FLEURS".to_string());
+    }
+
+    #[test]
+    fn render_unstructured_writes_address_lines_in_reading_order() {
+        let converted = ISO20022Address::render(address(), ISO20022AddressMode::Unstructured);
+
+        assert_eq!(
+            converted.address,
+            ISO20022Address {
+                country: "FR".to_string(),
+                address_lines: vec![
+                    "Appartement 2".to_string(),
+                    "Entrée A, Bâtiment Jonquille".to_string(),
+                    "25 RUE DES FLEURS".to_string(),
+                    "CAUDOS".to_string(),
+                    "33380 MIOS".to_string(),
+                ],
+                ..Default::default()
+            }
+        );
+        assert!(converted.report.is_lossless());
+    }
+
+    #[test]
+    fn render_unstructured_puts_care_of_before_room() {
+        let converted = ISO20022Address::render(
+            Address {
+                care_of: Some("Mireille COPEAU".to_string()),
+                ..address()
+            },
+            ISO20022AddressMode::Unstructured,
+        );
+
+        assert_eq!(
+            converted.address.address_lines[0],
+            "c/o Mireille COPEAU, Appartement 2".to_string()
+        );
+        assert_eq!(converted.address.care_of, None);
+    }
+
+    #[test]
+    fn render_unstructured_drops_lines_beyond_the_seventh() {
+        let converted = ISO20022Address::render(
+            Address {
+                address_lines: (1..=6).map(|n| format!("Ligne {n}")).collect(),
+                ..address()
+            },
+            ISO20022AddressMode::Unstructured,
+        );
+
+        assert_eq!(converted.address.address_lines.len(), MAX_ADDRESS_LINES);
+        assert_eq!(converted.address.address_lines[4], "33380 MIOS".to_string());
+        assert_eq!(converted.address.address_lines[6], "Ligne 2".to_string());
+        assert!(
+            converted
+                .report
+                .warnings
+                .iter()
+                .any(|warning| warning.kind == AlterationKind::Dropped)
+        );
+    }
+
+    #[test]
+    fn render_hybrid_keeps_at_most_two_address_lines() {
+        let long = "X".repeat(60);
+        let converted = ISO20022Address::render(
+            Address {
+                address_lines: vec![
+                    long.clone(),
+                    long.clone(),
+                    "Porte 4".to_string(),
+                    long.clone(),
+                ],
+                ..address()
+            },
+            ISO20022AddressMode::Hybrid,
+        );
+
+        assert_eq!(
+            converted.address.address_lines,
+            vec![long.clone(), format!("{long}, Porte 4")]
+        );
+        assert_eq!(converted.address.street_name, "RUE DES FLEURS".to_string());
+        let dropped: Vec<&str> = converted
+            .report
+            .warnings
+            .iter()
+            .filter(|warning| warning.kind == AlterationKind::Dropped)
+            .map(|warning| warning.field.as_str())
+            .collect();
+        assert_eq!(dropped, vec!["address_lines"]);
+    }
+
+    #[test]
+    fn wrap_splits_long_text_at_word_boundaries() {
+        let text = format!("{} {}", "A".repeat(40), "B".repeat(40));
+
+        assert_eq!(wrap(&text), vec!["A".repeat(40), "B".repeat(40)]);
+        assert_eq!(wrap(&"C".repeat(75)), vec!["C".repeat(70), "C".repeat(5)]);
+    }
+}
@@ -0,0 +1,582 @@
+//! Heuristic structuring of unstructured ISO 20022 addresses, whose elements only come as `<AdrLine>`
+
+use celes::Country;
+
+use crate::domain::{FrenchStreetLine, ISO20022Address, text::fold};
+
+use super::{french_country::country_from_name, french_distribution::split_post_box};
+
+/// Confidence below which a structured element needs a human review
+pub const REVIEW_THRESHOLD: f32 = 0.7;
+
+/// Countries whose postal codes have 5 digits
+const FIVE_DIGIT_POST_CODE_COUNTRIES: [&str; 8] = ["FR", "DE", "IT", "ES", "MC", "FI", "EE", "HR"];
+
+/// Countries whose postal codes have 4 digits
+const FOUR_DIGIT_POST_CODE_COUNTRIES: [&str; 9] =
+    ["BE", "CH", "AT", "LU", "DK", "NO", "HU", "SI", "NL"];
+
+/// Post box mentions besides the French ones (BP, TSA and CS), in their folded form
+const POST_BOX_MENTIONS: [&str; 6] = [
+    "PO BOX",
+    "P O BOX",
+    "POSTFACH",
+    "APARTADO",
+    "CASELLA POSTALE",
+    "POSTBUS",
+];
+
+/// Words of a street name written before or after the building number
+const STREET_KEYWORDS: [&str; 22] = [
+    "RUE",
+    "AVENUE",
+    "AV",
+    "BOULEVARD",
+    "BD",
+    "PLACE",
+    "CHEMIN",
+    "ROUTE",
+    "IMPASSE",
+    "ALLEE",
+    "QUAI",
+    "STREET",
+    "ST",
+    "ROAD",
+    "RD",
+    "LANE",
+    "STRASSE",
+    "STR",
+    "WEG",
+    "VIA",
+    "CALLE",
+    "STRAAT",
+];
+
+/// Confidence in an element structured out of the address lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementConfidence {
+    /// XML tag of the element (e.g. `PstCd`)
+    pub element: String,
+
+    /// Value given to the element
+    pub value: String,
+
+    /// Confidence in the value, from 0 (guess) to 1 (given structured in the source)
+    pub confidence: f32,
+}
+
+/// Result of structuring an unstructured ISO 20022 address
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructuredISO20022Address {
+    /// Structured address, keeping the lines that could not be structured in `<AdrLine>`
+    pub address: ISO20022Address,
+
+    /// Confidence in each populated element
+    pub confidences: Vec<ElementConfidence>,
+
+    /// Whether a human should review the address: an element is below the review threshold, the town or country is
+    /// missing, or some lines could not be structured
+    pub needs_review: bool,
+}
+
+impl ISO20022Address {
+    /// Whether the address has no `<AdrLine>` and no value in the wrong element: a building number inside `<StrtNm>`,
+    /// or a postal code and town given together in `<TwnNm>` or `<PstCd>`
+    pub fn is_fully_structured(&self) -> bool {
+        self.address_lines.is_empty()
+            && self.misplaced_street().is_none()
+            && self.misplaced_post_code_and_town().is_none()
+    }
+
+    /// Detect the postal code, town, country, street, building number and post box in the `<AdrLine>` of the address.
+    ///
+    /// Elements already structured are kept with a confidence of 1, except a street or town holding a value of
+    /// another element, which is structured again like a line. Lines that match no element stay in `<AdrLine>`.
+    pub fn structure(mut self) -> StructuredISO20022Address {
+        let mut lines = Vec::new();
+        if let Some(street) = self.misplaced_street() {
+            lines.push(street);
+            self.street_name = String::new();
+        }
+        if let Some(post_code_and_town) = self.misplaced_post_code_and_town() {
+            lines.push(post_code_and_town);
+            self.post_code = String::new();
+            self.town_name = String::new();
+        }
+        lines.extend(
+            self.address_lines
+                .iter()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty()),
+        );
+
+        let mut structurer = Structurer {
+            lines,
+            address: ISO20022Address {
+                address_lines: Vec::new(),
+                ..self
+            },
+            confidences: Vec::new(),
+        };
+
+        structurer.keep_structured_elements();
+        structurer.find_country();
+        structurer.find_post_code_and_town();
+        structurer.find_post_box();
+        structurer.find_street();
+
+        structurer.finish()
+    }
+}
+
+impl ISO20022Address {
+    /// `<StrtNm>` carrying the building number of an address without `<BldgNb>`
+    fn misplaced_street(&self) -> Option<String> {
+        let street = self.street_name.trim();
+
+        (self.building_number.is_none() && split_building_number(street).is_some())
+            .then(|| street.to_string())
+    }
+
+    /// `<TwnNm>` carrying the postal code of an address without `<PstCd>`, or `<PstCd>` carrying the town of an
+    /// address without `<TwnNm>`
+    fn misplaced_post_code_and_town(&self) -> Option<String> {
+        let (town, post_code) = (self.town_name.trim(), self.post_code.trim());
+
+        match (town.is_empty(), post_code.is_empty()) {
+            (false, true) => split_post_code(town).map(|_| town.to_string()),
+            (true, false) => split_post_code(post_code)
+                .filter(|(_, town)| !town.is_empty())
+                .map(|_| post_code.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// State accumulated while structuring the lines
+struct Structurer {
+    address: ISO20022Address,
+    lines: Vec<String>,
+    confidences: Vec<ElementConfidence>,
+}
+
+impl Structurer {
+    fn keep_structured_elements(&mut self) {
+        let given: Vec<(&str, String)> = [
+            ("StrtNm", Some(self.address.street_name.clone())),
+            ("BldgNb", self.address.building_number.clone()),
+            ("PstBx", self.address.post_box.clone()),
+            ("PstCd", Some(self.address.post_code.clone())),
+            ("TwnNm", Some(self.address.town_name.clone())),
+            ("Ctry", Some(self.address.country.clone())),
+        ]
+        .into_iter()
+        .filter_map(|(element, value)| {
+            value
+                .filter(|value| !value.is_empty())
+                .map(|v| (element, v))
+        })
+        .collect();
+
+        for (element, value) in given {
+            self.record(element, &value, 1.0);
+        }
+    }
+
+    /// Country of the last line, as a name or an ISO code
+    fn find_country(&mut self) {
+        if !self.address.country.is_empty() {
+            return;
+        }
+
+        let Some(last) = self.lines.last() else {
+            return;
+        };
+
+        let folded = fold(last);
+        let (country, confidence) = if let Some(country) = country_from_name(&folded) {
+            (country, 0.9)
+        } else if let Ok(country) = Country::from_alpha2(&folded) {
+            (country, 0.75)
+        } else {
+            return;
+        };
+
+        self.lines.pop();
+        self.address.country = country.alpha2.to_string();
+        self.record("Ctry", country.alpha2, confidence);
+    }
+
+    /// Postal code and town of the last line carrying a postal code
+    fn find_post_code_and_town(&mut self) {
+        if !self.address.post_code.is_empty() || !self.address.town_name.is_empty() {
+            return;
+        }
+
+        let found = self
+            .lines
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, line)| {
+                split_post_code(line).map(|(post_code, town)| (index, post_code, town))
+            });
+
+        let Some((index, post_code, town)) = found else {
+            // Without a postal code, the last line is the most likely town
+            if let Some(town) = self.lines.pop() {
+                self.address.town_name = town.clone();
+                self.record("TwnNm", &town, 0.4);
+            }
+            return;
+        };
+
+        self.lines.remove(index);
+
+        let confidence = if matches_country_format(&post_code, &self.address.country) {
+            0.95
+        } else {
+            0.6
+        };
+        self.address.post_code = post_code.clone();
+        self.record("PstCd", &post_code, confidence);
+
+        if !town.is_empty() {
+            self.address.town_name = town.clone();
+            self.record("TwnNm", &town, 0.85);
+        }
+    }
+
+    /// Post box mention (BP, TSA, CS, PO Box, Postfach, ...) on its own line
+    fn find_post_box(&mut self) {
+        if self.address.post_box.is_some() {
+            return;
+        }
+
+        let found = self
+            .lines
+            .iter()
+            .enumerate()
+            .find_map(|(index, line)| post_box_mention(line).map(|post_box| (index, post_box)));
+
+        if let Some((index, post_box)) = found {
+            self.lines.remove(index);
+            self.address.post_box = Some(post_box.clone());
+            self.record("PstBx", &post_box, 0.9);
+        }
+    }
+
+    /// Street of the last line with a building number, or else of the last line with a street keyword
+    fn find_street(&mut self) {
+        if !self.address.street_name.is_empty() {
+            return;
+        }
+
+        let found = self
+            .lines
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, line)| {
+                split_building_number(line).map(|(number, street)| (index, number, street))
+            });
+
+        match found {
+            Some((index, building_number, street_name)) => {
+                let confidence = if has_street_keyword(&street_name) {
+                    0.85
+                } else {
+                    0.6
+                };
+
+                self.lines.remove(index);
+                self.address.building_number = Some(building_number.clone());
+                self.address.street_name = street_name.clone();
+                self.record("BldgNb", &building_number, confidence);
+                self.record("StrtNm", &street_name, confidence);
+            }
+            None => {
+                if let Some(index) = self.lines.iter().rposition(|line| has_street_keyword(line)) {
+                    let street_name = self.lines.remove(index);
+                    self.address.street_name = street_name.clone();
+                    self.record("StrtNm", &street_name, 0.6);
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, element: &str, value: &str, confidence: f32) {
+        self.confidences.push(ElementConfidence {
+            element: element.to_string(),
+            value: value.to_string(),
+            confidence,
+        });
+    }
+
+    fn finish(mut self) -> StructuredISO20022Address {
+        let needs_review = self.address.town_name.is_empty()
+            || self.address.country.is_empty()
+            || !self.lines.is_empty()
+            || self
+                .confidences
+                .iter()
+                .any(|confidence| confidence.confidence < REVIEW_THRESHOLD);
+
+        self.address.address_lines = self.lines;
+
+        StructuredISO20022Address {
+            address: self.address,
+            confidences: self.confidences,
+            needs_review,
+        }
+    }
+}
+
+/// Split a line into its postal code and town, the code being the first or last word (e.g. `33380 MIOS`,
+/// `F-33380 MIOS`, `1012 AB AMSTERDAM` or `LONDON SW1A 1AA`)
+fn split_post_code(line: &str) -> Option<(String, String)> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    match words.as_slice() {
+        [code, letters, town @ ..] if is_numeric_post_code(code) && is_dutch_suffix(letters) => {
+            Some((format!("{code} {letters}"), town.join(" ")))
+        }
+        [code, town @ ..] if is_numeric_post_code(code) => {
+            Some((strip_country_prefix(code).to_string(), town.join(" ")))
+        }
+        [town @ .., outward, inward] if is_uk_post_code(outward, inward) => {
+            Some((format!("{outward} {inward}"), town.join(" ")))
+        }
+        [town @ .., code] if is_numeric_post_code(code) => {
+            Some((strip_country_prefix(code).to_string(), town.join(" ")))
+        }
+        _ => None,
+    }
+}
+
+/// 4 or 5 digits, optionally prefixed with a country letter (e.g. `F-33380`)
+fn is_numeric_post_code(word: &str) -> bool {
+    let code = strip_country_prefix(word);
+
+    (4..=5).contains(&code.len()) && code.chars().all(|c| c.is_ascii_digit())
+}
+
+fn strip_country_prefix(word: &str) -> &str {
+    match word.split_once('-') {
+        Some((prefix, code))
+            if (1..=2).contains(&prefix.len())
+                && prefix.chars().all(|c| c.is_ascii_uppercase()) =>
+        {
+            code
+        }
+        _ => word,
+    }
+}
+
+fn is_dutch_suffix(word: &str) -> bool {
+    word.len() == 2 && word.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// UK postcode such as `SW1A 1AA`: an outward code with letters and digits, and a digit followed by 2 letters
+fn is_uk_post_code(outward: &str, inward: &str) -> bool {
+    let inward: Vec<char> = inward.chars().collect();
+
+    (2..=4).contains(&outward.len())
+        && outward.starts_with(|c: char| c.is_ascii_uppercase())
+        && outward.chars().any(|c| c.is_ascii_digit())
+        && outward.chars().all(|c| c.is_ascii_alphanumeric())
+        && matches!(inward.as_slice(), [digit, a, b] if digit.is_ascii_digit() && a.is_ascii_uppercase() && b.is_ascii_uppercase())
+}
+
+/// Whether a postal code has the format of the country's codes
+fn matches_country_format(post_code: &str, country: &str) -> bool {
+    let digits = post_code.chars().take_while(|c| c.is_ascii_digit()).count();
+
+    match digits {
+        5 => FIVE_DIGIT_POST_CODE_COUNTRIES.contains(&country),
+        4 => FOUR_DIGIT_POST_CODE_COUNTRIES.contains(&country),
+        _ => country == "GB",
+    }
+}
+
+fn post_box_mention(line: &str) -> Option<String> {
+    if let (Some(post_box), None) = split_post_box(line) {
+        return Some(post_box);
+    }
+
+    let folded = fold(line).replace('.', " ");
+    let folded = folded.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    POST_BOX_MENTIONS
+        .iter()
+        .any(|mention| {
+            folded.strip_prefix(mention).is_some_and(|rest| {
+                rest.trim()
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_digit())
+            })
+        })
+        .then(|| line.to_string())
+}
+
+/// Split a street line into its building number and street name, the number being first (`25 RUE DES FLEURS`) or
+/// last (`Hauptstrasse 12a`)
+fn split_building_number(line: &str) -> Option<(String, String)> {
+    let street = FrenchStreetLine::parse(line);
+    if let Some(building_number) = street.building_number {
+        return Some((building_number, street.street_name));
+    }
+
+    let (street_name, number) = line.trim().rsplit_once(' ')?;
+    let starts_with_digit = number.starts_with(|c: char| c.is_ascii_digit());
+    let is_short = number.chars().count() <= 5;
+
+    (starts_with_digit && is_short && !is_numeric_post_code(number)).then(|| {
+        (
+            number.to_string(),
+            street_name.trim_end_matches(',').to_string(),
+        )
+    })
+}
+
+fn has_street_keyword(line: &str) -> bool {
+    fold(line)
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| {
+            STREET_KEYWORDS.contains(&word)
+                || word.ends_with("STRASSE")
+                || word.ends_with("STRAAT")
+                || word.ends_with("WEG")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unstructured(lines: &[&str]) -> ISO20022Address {
+        ISO20022Address {
+            address_lines: lines.iter().map(|line| line.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn confidence(structured: &StructuredISO20022Address, element: &str) -> f32 {
+        structured
+            .confidences
+            .iter()
+            .find(|confidence| confidence.element == element)
+            .map(|confidence| confidence.confidence)
+            .unwrap()
+    }
+
+    #[test]
+    fn structure_detects_every_element_of_a_french_address() {
+        let structured =
+            unstructured(&["25 RUE DES FLEURS", "BP 40122", "33500 LIBOURNE", "FRANCE"])
+                .structure();
+
+        assert_eq!(
+            structured.address,
+            ISO20022Address {
+                street_name: "RUE DES FLEURS".to_string(),
+                building_number: Some("25".to_string()),
+                post_box: Some("BP 40122".to_string()),
+                post_code: "33500".to_string(),
+                town_name: "LIBOURNE".to_string(),
+                country: "FR".to_string(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(confidence(&structured, "PstCd"), 0.95);
+        assert!(!structured.needs_review);
+    }
+
+    #[test]
+    fn structure_detects_building_number_after_street_and_foreign_post_codes() {
+        let german = unstructured(&["Hauptstrasse 12a", "D-10115 Berlin", "Germany"]).structure();
+        let british = unstructured(&["10 Downing Street", "London SW1A 2AA", "GB"]).structure();
+
+        assert_eq!(german.address.building_number, Some("12a".to_string()));
+        assert_eq!(german.address.street_name, "Hauptstrasse".to_string());
+        assert_eq!(german.address.post_code, "10115".to_string());
+        assert_eq!(german.address.country, "DE".to_string());
+        assert_eq!(british.address.post_code, "SW1A 2AA".to_string());
+        assert_eq!(british.address.town_name, "London".to_string());
+        assert_eq!(british.address.street_name, "Downing Street".to_string());
+    }
+
+    #[test]
+    fn structure_keeps_elements_already_structured() {
+        let structured = ISO20022Address {
+            town_name: "LIBOURNE".to_string(),
+            post_code: "33500".to_string(),
+            country: "FR".to_string(),
+            address_lines: vec!["25 RUE DES FLEURS".to_string()],
+            ..Default::default()
+        }
+        .structure();
+
+        assert_eq!(structured.address.town_name, "LIBOURNE".to_string());
+        assert_eq!(confidence(&structured, "TwnNm"), 1.0);
+        assert_eq!(structured.address.building_number, Some("25".to_string()));
+        assert!(structured.address.address_lines.is_empty());
+    }
+
+    #[test]
+    fn structure_flags_review_when_lines_are_left_or_town_is_guessed() {
+        let structured = unstructured(&["ACME TRADING", "Zone du Port", "LIBOURNE"]).structure();
+
+        assert_eq!(structured.address.town_name, "LIBOURNE".to_string());
+        assert_eq!(confidence(&structured, "TwnNm"), 0.4);
+        assert_eq!(
+            structured.address.address_lines,
+            vec!["ACME TRADING".to_string(), "Zone du Port".to_string()]
+        );
+        assert!(structured.needs_review);
+    }
+
+    #[test]
+    fn structure_splits_values_given_in_the_wrong_element() {
+        let address = ISO20022Address {
+            street_name: "25 RUE DES FLEURS".to_string(),
+            town_name: "33500 LIBOURNE".to_string(),
+            country: "FR".to_string(),
+            ..Default::default()
+        };
+        assert!(!address.is_fully_structured());
+
+        let structured = address.structure();
+
+        assert_eq!(structured.address.street_name, "RUE DES FLEURS".to_string());
+        assert_eq!(structured.address.building_number, Some("25".to_string()));
+        assert_eq!(structured.address.post_code, "33500".to_string());
+        assert_eq!(structured.address.town_name, "LIBOURNE".to_string());
+        assert_eq!(confidence(&structured, "Ctry"), 1.0);
+        assert!(structured.address.is_fully_structured());
+        assert!(!structured.needs_review);
+    }
+
+    #[test]
+    fn structure_flags_review_when_post_code_does_not_match_country_format() {
+        let structured =
+            unstructured(&["25 RUE DES FLEURS", "3350 LIBOURNE", "FRANCE"]).structure();
+
+        assert_eq!(structured.address.post_code, "3350".to_string());
+        assert!(confidence(&structured, "PstCd") < REVIEW_THRESHOLD);
+        assert!(structured.needs_review);
+    }
+
+    #[test]
+    fn structure_detects_foreign_post_box_mentions() {
+        let structured = unstructured(&["P.O. Box 1234", "1012 AB Amsterdam", "NL"]).structure();
+
+        assert_eq!(
+            structured.address.post_box,
+            Some("P.O. Box 1234".to_string())
+        );
+        assert_eq!(structured.address.post_code, "1012 AB".to_string());
+        assert_eq!(structured.address.town_name, "Amsterdam".to_string());
+    }
+}
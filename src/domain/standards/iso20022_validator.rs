@@ -0,0 +1,421 @@
+use std::fmt::{self, Display, Formatter};
+
+use celes::Country;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{AddressType, ISO20022Address, ProprietaryAddressType};
+
+use super::iso20022_rendering::{
+    MAX_ADDRESS_LINE_LENGTH, MAX_ADDRESS_LINES, MAX_HYBRID_ADDRESS_LINES,
+};
+
+/// XML path of the postal address, prefixing the path of every violation
+const POSTAL_ADDRESS_PATH: &str = "PstlAdr";
+
+/// Characters of the Latin character set accepted in SEPA messages, besides letters and digits
+const SEPA_SYMBOLS: [char; 11] = ['/', '-', '?', ':', '(', ')', '.', ',', '\'', '+', ' '];
+
+/// Market practice an ISO 20022 address is checked against, on top of the schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ISO20022Profile {
+    /// Only the `PostalAddress24` schema: maximum lengths, at most 7 `<AdrLine>` and a valid country code
+    #[default]
+    Schema,
+
+    /// SWIFT CBPR+ usage guidelines: town and country mandatory, at most 2 `<AdrLine>` along with them (hybrid)
+    CbprPlus,
+
+    /// EPC SEPA rulebooks: country mandatory, town mandatory for a structured or hybrid address, at most 2
+    /// `<AdrLine>`, and only the SEPA Latin character set
+    EpcSepa,
+}
+
+/// Rule of the ISO 20022 schema or of a market practice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ISO20022Rule {
+    /// An element is longer than the schema allows
+    TooLong,
+
+    /// There are more `<AdrLine>` than allowed
+    TooManyAddressLines,
+
+    /// The country is not an ISO 3166-1 alpha-2 code
+    InvalidCountryCode,
+
+    /// The identification of a proprietary address type is not 4 letters or digits
+    InvalidAddressType,
+
+    /// An element mandatory in the profile is missing
+    MissingElement,
+
+    /// An element contains a character outside of the SEPA character set
+    UnsupportedCharacter,
+}
+
+/// Violation of an ISO 20022 rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ISO20022Violation {
+    /// XML path of the offending element (e.g. `PstlAdr/AdrLine[3]`)
+    pub path: String,
+
+    /// Violated rule
+    pub rule: ISO20022Rule,
+
+    /// Offending text
+    pub text: String,
+
+    /// Human readable explanation
+    pub message: String,
+}
+
+impl Display for ISO20022Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: '{}'", self.path, self.message, self.text)
+    }
+}
+
+impl ISO20022Address {
+    /// Check the address against the schema and the given market practice and return every violation, in element
+    /// order
+    pub fn validate(&self, profile: ISO20022Profile) -> Vec<ISO20022Violation> {
+        let mut violations = Vec::new();
+        let elements = self.elements();
+
+        for (tag, text, max_length) in &elements {
+            let length = text.chars().count();
+            if length > *max_length {
+                violations.push(violation(
+                    tag,
+                    ISO20022Rule::TooLong,
+                    text,
+                    format!("element has {length} characters, more than {max_length}"),
+                ));
+            }
+
+            if profile == ISO20022Profile::EpcSepa && text.chars().any(|c| !is_sepa_character(c)) {
+                violations.push(violation(
+                    tag,
+                    ISO20022Rule::UnsupportedCharacter,
+                    text,
+                    "element contains characters outside of the SEPA character set",
+                ));
+            }
+        }
+
+        for (index, line) in self.address_lines.iter().enumerate() {
+            let length = line.chars().count();
+            if length > MAX_ADDRESS_LINE_LENGTH {
+                violations.push(violation(
+                    &address_line_tag(index),
+                    ISO20022Rule::TooLong,
+                    line,
+                    format!("element has {length} characters, more than {MAX_ADDRESS_LINE_LENGTH}"),
+                ));
+            }
+
+            if profile == ISO20022Profile::EpcSepa && line.chars().any(|c| !is_sepa_character(c)) {
+                violations.push(violation(
+                    &address_line_tag(index),
+                    ISO20022Rule::UnsupportedCharacter,
+                    line,
+                    "element contains characters outside of the SEPA character set",
+                ));
+            }
+        }
+
+        let max_lines = match profile {
+            ISO20022Profile::Schema => MAX_ADDRESS_LINES,
+            ISO20022Profile::CbprPlus | ISO20022Profile::EpcSepa => MAX_HYBRID_ADDRESS_LINES,
+        };
+        if self.address_lines.len() > max_lines {
+            violations.push(violation(
+                &address_line_tag(max_lines),
+                ISO20022Rule::TooManyAddressLines,
+                &self.address_lines[max_lines],
+                format!(
+                    "address has {} <AdrLine>, more than {max_lines}",
+                    self.address_lines.len()
+                ),
+            ));
+        }
+
+        if let Some(AddressType::Proprietary(ref proprietary)) = self.address_type {
+            check_proprietary_address_type(proprietary, &mut violations);
+        }
+
+        if !self.country.is_empty() && Country::from_alpha2(&self.country).is_err() {
+            violations.push(violation(
+                "Ctry",
+                ISO20022Rule::InvalidCountryCode,
+                &self.country,
+                "country is not an ISO 3166-1 alpha-2 code",
+            ));
+        }
+
+        if self.country.trim().is_empty() && profile != ISO20022Profile::Schema {
+            violations.push(missing("Ctry", profile));
+        }
+
+        if self.town_name.trim().is_empty() && self.needs_town(profile) {
+            violations.push(missing("TwnNm", profile));
+        }
+
+        violations
+    }
+
+    /// Whether the address complies with the schema and the given market practice
+    pub fn is_valid(&self, profile: ISO20022Profile) -> bool {
+        self.validate(profile).is_empty()
+    }
+
+    /// Populated structured elements with their tag and schema maximum length, in schema order
+    fn elements(&self) -> Vec<(&'static str, &str, usize)> {
+        [
+            ("CareOf", self.care_of.as_deref(), 140),
+            ("Dept", self.department.as_deref(), 70),
+            ("SubDept", self.sub_department.as_deref(), 70),
+            ("StrtNm", Some(self.street_name.as_str()), 70),
+            ("BldgNb", self.building_number.as_deref(), 16),
+            ("BldgNm", self.building_name.as_deref(), 35),
+            ("Flr", self.floor.as_deref(), 70),
+            ("UnitNb", self.unit_number.as_deref(), 16),
+            ("PstBx", self.post_box.as_deref(), 16),
+            ("Rm", self.room.as_deref(), 70),
+            ("PstCd", Some(self.post_code.as_str()), 16),
+            ("TwnNm", Some(self.town_name.as_str()), 35),
+            ("TwnLctnNm", self.town_location_name.as_deref(), 35),
+            ("DstrctNm", self.district_name.as_deref(), 35),
+            ("CtrySubDvsn", self.country_sub_division.as_deref(), 35),
+        ]
+        .into_iter()
+        .filter_map(|(tag, text, max_length)| {
+            text.filter(|text| !text.is_empty())
+                .map(|text| (tag, text, max_length))
+        })
+        .collect()
+    }
+
+    /// CBPR+ always needs the town, SEPA only when the address is not fully unstructured
+    fn needs_town(&self, profile: ISO20022Profile) -> bool {
+        match profile {
+            ISO20022Profile::Schema => false,
+            ISO20022Profile::CbprPlus => true,
+            ISO20022Profile::EpcSepa => {
+                !self.elements().is_empty() || self.address_lines.is_empty()
+            }
+        }
+    }
+}
+
+impl Display for ISO20022Profile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ISO20022Profile::Schema => write!(f, "ISO 20022 schema"),
+            ISO20022Profile::CbprPlus => write!(f, "CBPR+"),
+            ISO20022Profile::EpcSepa => write!(f, "EPC SEPA"),
+        }
+    }
+}
+
+/// The identification has exactly 4 letters or digits, the issuer and scheme name at most 35 characters
+fn check_proprietary_address_type(
+    proprietary: &ProprietaryAddressType,
+    violations: &mut Vec<ISO20022Violation>,
+) {
+    let id = &proprietary.id;
+    if id.chars().count() != 4 || !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        violations.push(violation(
+            "AdrTp/Prtry/Id",
+            ISO20022Rule::InvalidAddressType,
+            id,
+            "proprietary address type is not made of 4 letters or digits",
+        ));
+    }
+
+    for (tag, text) in [
+        ("AdrTp/Prtry/Issr", Some(proprietary.issuer.as_str())),
+        ("AdrTp/Prtry/SchmeNm", proprietary.scheme_name.as_deref()),
+    ] {
+        let length = text.map_or(0, |text| text.chars().count());
+        if length > 35 {
+            violations.push(violation(
+                tag,
+                ISO20022Rule::TooLong,
+                text.unwrap_or_default(),
+                format!("element has {length} characters, more than 35"),
+            ));
+        }
+    }
+}
+
+fn is_sepa_character(c: char) -> bool {
+    c.is_ascii_alphanumeric() || SEPA_SYMBOLS.contains(&c)
+}
+
+/// Tag of the `<AdrLine>` at the given index, numbered from 1 as in XPath
+fn address_line_tag(index: usize) -> String {
+    format!("AdrLine[{}]", index + 1)
+}
+
+fn missing(tag: &str, profile: ISO20022Profile) -> ISO20022Violation {
+    violation(
+        tag,
+        ISO20022Rule::MissingElement,
+        "",
+        format!("element is mandatory in {profile}"),
+    )
+}
+
+fn violation(
+    tag: &str,
+    rule: ISO20022Rule,
+    text: &str,
+    message: impl Into<String>,
+) -> ISO20022Violation {
+    ISO20022Violation {
+        path: format!("{POSTAL_ADDRESS_PATH}/{tag}"),
+        rule,
+        text: text.to_string(),
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address() -> ISO20022Address {
+        ISO20022Address {
+            street_name: "RUE DES FLEURS".to_string(),
+            building_number: Some("25".to_string()),
+            post_code: "33500".to_string(),
+            town_name: "LIBOURNE".to_string(),
+            country: "FR".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn rules(address: &ISO20022Address, profile: ISO20022Profile) -> Vec<(String, ISO20022Rule)> {
+        address
+            .validate(profile)
+            .into_iter()
+            .map(|violation| (violation.path, violation.rule))
+            .collect()
+    }
+
+    #[test]
+    fn validate_accepts_structured_address_in_every_profile() {
+        for profile in [
+            ISO20022Profile::Schema,
+            ISO20022Profile::CbprPlus,
+            ISO20022Profile::EpcSepa,
+        ] {
+            assert!(address().is_valid(profile), "{profile}");
+        }
+    }
+
+    #[test]
+    fn validate_reports_elements_longer_than_the_schema_allows() {
+        let address = ISO20022Address {
+            building_number: Some("1".repeat(17)),
+            town_name: "T".repeat(36),
+            address_lines: vec!["L".repeat(71)],
+            ..address()
+        };
+
+        assert_eq!(
+            rules(&address, ISO20022Profile::Schema),
+            vec![
+                ("PstlAdr/BldgNb".to_string(), ISO20022Rule::TooLong),
+                ("PstlAdr/TwnNm".to_string(), ISO20022Rule::TooLong),
+                ("PstlAdr/AdrLine[1]".to_string(), ISO20022Rule::TooLong),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_reports_invalid_proprietary_address_type() {
+        let address = ISO20022Address {
+            address_type: Some(AddressType::Proprietary(ProprietaryAddressType {
+                id: "HEADQUARTERS".to_string(),
+                issuer: "ACME".to_string(),
+                scheme_name: None,
+            })),
+            ..address()
+        };
+
+        assert_eq!(
+            rules(&address, ISO20022Profile::Schema),
+            vec![(
+                "PstlAdr/AdrTp/Prtry/Id".to_string(),
+                ISO20022Rule::InvalidAddressType
+            )]
+        );
+    }
+
+    #[test]
+    fn validate_reports_invalid_country_code() {
+        let address = ISO20022Address {
+            country: "France".to_string(),
+            ..address()
+        };
+
+        assert_eq!(
+            rules(&address, ISO20022Profile::Schema),
+            vec![("PstlAdr/Ctry".to_string(), ISO20022Rule::InvalidCountryCode)]
+        );
+    }
+
+    #[test]
+    fn validate_cbpr_plus_requires_town_and_country_and_at_most_two_address_lines() {
+        let address = ISO20022Address {
+            address_lines: vec![
+                "25 RUE DES FLEURS".to_string(),
+                "BP 40122".to_string(),
+                "33500 LIBOURNE".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        assert!(address.is_valid(ISO20022Profile::Schema));
+        assert_eq!(
+            rules(&address, ISO20022Profile::CbprPlus),
+            vec![
+                (
+                    "PstlAdr/AdrLine[3]".to_string(),
+                    ISO20022Rule::TooManyAddressLines
+                ),
+                ("PstlAdr/Ctry".to_string(), ISO20022Rule::MissingElement),
+                ("PstlAdr/TwnNm".to_string(), ISO20022Rule::MissingElement),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_epc_sepa_accepts_unstructured_address_and_rejects_unsupported_characters() {
+        let unstructured = ISO20022Address {
+            country: "FR".to_string(),
+            address_lines: vec![
+                "25 RUE DES FLEURS".to_string(),
+                "33500 LIBOURNE".to_string(),
+            ],
+            ..Default::default()
+        };
+        let accented = ISO20022Address {
+            street_name: "RUE DE L’ÉGLISE".to_string(),
+            ..address()
+        };
+
+        assert!(unstructured.is_valid(ISO20022Profile::EpcSepa));
+        assert!(!unstructured.is_valid(ISO20022Profile::CbprPlus));
+        assert_eq!(
+            rules(&accented, ISO20022Profile::EpcSepa),
+            vec![(
+                "PstlAdr/StrtNm".to_string(),
+                ISO20022Rule::UnsupportedCharacter
+            )]
+        );
+    }
+}
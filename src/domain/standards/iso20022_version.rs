@@ -0,0 +1,322 @@
+//! Versions of the ISO 20022 postal address type, which differ by the elements they accept
+
+use std::fmt::{self, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{AddressType, ConversionReport, Converted, ISO20022Address};
+
+use super::iso20022_rendering::{ELEMENT_SEPARATOR, MAX_ADDRESS_LINES, wrap};
+
+/// Prefix of the `<AdrLine>` carrying the care-of party in versions without `<CareOf>`
+pub(super) const CARE_OF_PREFIX: &str = "c/o ";
+
+/// Prefix of the namespace of the ISO 20022 message schemas, followed by the message identifier
+const NAMESPACE_PREFIX: &str = "urn:iso:std:iso:20022:tech:xsd:";
+
+/// Version of the ISO 20022 postal address type used by a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ISO20022Version {
+    /// PostalAddress6 (e.g. pain.001.001.03, pacs.008.001.02): no `<BldgNm>`, `<Flr>`, `<PstBx>`, `<Rm>`,
+    /// `<TwnLctnNm>`, `<DstrctNm>` nor proprietary `<AdrTp>`
+    PostalAddress6,
+
+    /// PostalAddress24 (e.g. pain.001.001.09, pacs.008.001.08)
+    #[default]
+    PostalAddress24,
+
+    /// PostalAddress27 (e.g. pain.001.001.12, pacs.008.001.12): adds `<CareOf>` and `<UnitNb>`
+    PostalAddress27,
+}
+
+impl ISO20022Version {
+    /// Version of the postal address type of a pain.001, pacs.008, camt.053 or camt.054 message, by the namespace of
+    /// its `<Document>` (e.g. `urn:iso:std:iso:20022:tech:xsd:pain.001.001.03` for PostalAddress6)
+    pub fn from_namespace(namespace: &str) -> Option<ISO20022Version> {
+        let identifier = namespace.trim().strip_prefix(NAMESPACE_PREFIX)?;
+        let [area, message, _, version] = identifier.split('.').collect::<Vec<_>>()[..] else {
+            return None;
+        };
+        let version: u32 = version.parse().ok()?;
+
+        // First message versions using PostalAddress24 and PostalAddress27
+        let (postal_address24, postal_address27) = match (area, message) {
+            ("pain", "001") => (9, 12),
+            ("pacs", "008") => (8, 12),
+            ("camt", "053" | "054") => (8, 11),
+            _ => return None,
+        };
+
+        Some(if version >= postal_address27 {
+            ISO20022Version::PostalAddress27
+        } else if version >= postal_address24 {
+            ISO20022Version::PostalAddress24
+        } else {
+            ISO20022Version::PostalAddress6
+        })
+    }
+}
+
+impl Display for ISO20022Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ISO20022Version::PostalAddress6 => write!(f, "PostalAddress6"),
+            ISO20022Version::PostalAddress24 => write!(f, "PostalAddress24"),
+            ISO20022Version::PostalAddress27 => write!(f, "PostalAddress27"),
+        }
+    }
+}
+
+impl ISO20022Address {
+    /// Convert the address to the given version and report the alterations made.
+    ///
+    /// Elements missing from the target version are folded into the ones it accepts: the unit number into `<Rm>`, and
+    /// the care-of party and the PostalAddress24 delivery details into `<AdrLine>`. Converting to PostalAddress27 moves
+    /// a `c/o` address line back into `<CareOf>`.
+    pub fn to_version(self, version: ISO20022Version) -> Converted<ISO20022Address> {
+        let mut address = self;
+        let mut report = ConversionReport::default();
+
+        match version {
+            ISO20022Version::PostalAddress27 => address.unfold_care_of(&mut report),
+            ISO20022Version::PostalAddress24 => {
+                address.fold_unit_number(&mut report);
+                address.fold_care_of(&mut report);
+            }
+            ISO20022Version::PostalAddress6 => {
+                address.fold_unit_number(&mut report);
+                address.fold_care_of(&mut report);
+                address.fold_postal_address24_elements(&mut report);
+                address.drop_proprietary_address_type(&mut report);
+            }
+        }
+
+        Converted::new(address, report)
+    }
+
+    /// Fold `<UnitNb>` into `<Rm>`, which carries the same information before PostalAddress27
+    fn fold_unit_number(&mut self, report: &mut ConversionReport) {
+        let Some(unit_number) = self.unit_number.take() else {
+            return;
+        };
+
+        self.room = Some(match self.room.take() {
+            Some(room) => format!("{room}{ELEMENT_SEPARATOR}{unit_number}"),
+            None => unit_number.clone(),
+        });
+        report.moved("unit_number", format!("'{unit_number}' folded into <Rm>"));
+    }
+
+    /// Fold `<CareOf>` into the first `<AdrLine>`
+    fn fold_care_of(&mut self, report: &mut ConversionReport) {
+        let Some(care_of) = self.care_of.take() else {
+            return;
+        };
+
+        self.address_lines
+            .insert(0, format!("{CARE_OF_PREFIX}{care_of}"));
+        report.moved("care_of", format!("'{care_of}' folded into <AdrLine>"));
+        self.drop_extra_lines(report);
+    }
+
+    /// Move a `c/o` address line into `<CareOf>`
+    fn unfold_care_of(&mut self, report: &mut ConversionReport) {
+        if self.care_of.is_some() {
+            return;
+        }
+
+        let found = self.address_lines.iter().position(|line| {
+            line.get(..CARE_OF_PREFIX.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(CARE_OF_PREFIX))
+        });
+
+        if let Some(index) = found {
+            let line = self.address_lines.remove(index);
+            let care_of = line[CARE_OF_PREFIX.len()..].trim().to_string();
+
+            report.moved("address_lines", format!("'{line}' moved into <CareOf>"));
+            self.care_of = Some(care_of);
+        }
+    }
+
+    /// Fold the elements introduced by PostalAddress24 into `<AdrLine>`, before the existing lines and in reading order
+    fn fold_postal_address24_elements(&mut self, report: &mut ConversionReport) {
+        let groups = [
+            vec![
+                ("building_name", self.building_name.take()),
+                ("floor", self.floor.take()),
+                ("room", self.room.take()),
+            ],
+            vec![("post_box", self.post_box.take())],
+            vec![
+                ("town_location_name", self.town_location_name.take()),
+                ("district_name", self.district_name.take()),
+            ],
+        ];
+
+        let mut lines = Vec::new();
+        for group in groups {
+            let texts: Vec<String> = group
+                .into_iter()
+                .filter_map(|(field, text)| {
+                    let text = text.filter(|text| !text.trim().is_empty())?;
+                    report.moved(field, format!("'{text}' folded into <AdrLine>"));
+
+                    Some(text)
+                })
+                .collect();
+
+            if !texts.is_empty() {
+                lines.extend(wrap(&texts.join(ELEMENT_SEPARATOR)));
+            }
+        }
+
+        // The care-of line stays first
+        let position = self
+            .address_lines
+            .first()
+            .filter(|line| line.starts_with(CARE_OF_PREFIX))
+            .map_or(0, |_| 1);
+        self.address_lines.splice(position..position, lines);
+        self.drop_extra_lines(report);
+    }
+
+    /// PostalAddress6 only accepts an `<AdrTp>` code
+    fn drop_proprietary_address_type(&mut self, report: &mut ConversionReport) {
+        if let Some(address_type @ AddressType::Proprietary(_)) = &self.address_type {
+            report.dropped(
+                "address_type",
+                format!("proprietary type '{address_type}' has no PostalAddress6 element"),
+            );
+            self.address_type = None;
+        }
+    }
+
+    fn drop_extra_lines(&mut self, report: &mut ConversionReport) {
+        if self.address_lines.len() > MAX_ADDRESS_LINES {
+            for line in self.address_lines.split_off(MAX_ADDRESS_LINES) {
+                report.dropped(
+                    "address_lines",
+                    format!("'{line}' exceeds the {MAX_ADDRESS_LINES} <AdrLine> of an address"),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn postal_address27() -> ISO20022Address {
+        ISO20022Address {
+            care_of: Some("Mireille COPEAU".to_string()),
+            street_name: "RUE DES FLEURS".to_string(),
+            building_number: Some("25".to_string()),
+            building_name: Some("Bâtiment Jonquille".to_string()),
+            floor: Some("Étage 3".to_string()),
+            unit_number: Some("Appartement 2".to_string()),
+            post_box: Some("BP 40122".to_string()),
+            post_code: "33500".to_string(),
+            town_name: "LIBOURNE".to_string(),
+            town_location_name: Some("CAUDOS".to_string()),
+            country: "FR".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn to_version_24_folds_unit_number_into_room_and_care_of_into_address_line() {
+        let converted = postal_address27().to_version(ISO20022Version::PostalAddress24);
+
+        assert_eq!(converted.address.care_of, None);
+        assert_eq!(converted.address.unit_number, None);
+        assert_eq!(converted.address.room, Some("Appartement 2".to_string()));
+        assert_eq!(
+            converted.address.address_lines,
+            vec!["c/o Mireille COPEAU".to_string()]
+        );
+        assert!(converted.report.is_lossless());
+    }
+
+    #[test]
+    fn to_version_6_folds_elements_missing_from_postal_address6_into_address_lines() {
+        let converted = postal_address27().to_version(ISO20022Version::PostalAddress6);
+
+        assert_eq!(
+            converted.address,
+            ISO20022Address {
+                street_name: "RUE DES FLEURS".to_string(),
+                building_number: Some("25".to_string()),
+                post_code: "33500".to_string(),
+                town_name: "LIBOURNE".to_string(),
+                country: "FR".to_string(),
+                address_lines: vec![
+                    "c/o Mireille COPEAU".to_string(),
+                    "Bâtiment Jonquille, Étage 3, Appartement 2".to_string(),
+                    "BP 40122".to_string(),
+                    "CAUDOS".to_string(),
+                ],
+                ..Default::default()
+            }
+        );
+        assert!(converted.report.is_lossless());
+    }
+
+    #[test]
+    fn to_version_27_moves_care_of_address_line_back_into_care_of() {
+        let converted = postal_address27()
+            .to_version(ISO20022Version::PostalAddress24)
+            .address
+            .to_version(ISO20022Version::PostalAddress27);
+
+        assert_eq!(
+            converted.address.care_of,
+            Some("Mireille COPEAU".to_string())
+        );
+        assert!(converted.address.address_lines.is_empty());
+    }
+
+    #[test]
+    fn from_namespace_reads_postal_address_version_of_message() {
+        for (namespace, version) in [
+            (
+                "urn:iso:std:iso:20022:tech:xsd:pain.001.001.03",
+                Some(ISO20022Version::PostalAddress6),
+            ),
+            (
+                "urn:iso:std:iso:20022:tech:xsd:pacs.008.001.08",
+                Some(ISO20022Version::PostalAddress24),
+            ),
+            (
+                "urn:iso:std:iso:20022:tech:xsd:pain.001.001.12",
+                Some(ISO20022Version::PostalAddress27),
+            ),
+            (
+                "urn:iso:std:iso:20022:tech:xsd:camt.053.001.02",
+                Some(ISO20022Version::PostalAddress6),
+            ),
+            ("urn:iso:std:iso:20022:tech:xsd:head.001.001.02", None),
+            ("urn:example", None),
+        ] {
+            assert_eq!(
+                ISO20022Version::from_namespace(namespace),
+                version,
+                "{namespace}"
+            );
+        }
+    }
+
+    #[test]
+    fn to_version_6_drops_lines_beyond_the_seventh() {
+        let converted = ISO20022Address {
+            address_lines: (1..=6).map(|n| format!("Ligne {n}")).collect(),
+            ..postal_address27()
+        }
+        .to_version(ISO20022Version::PostalAddress6);
+
+        assert_eq!(converted.address.address_lines.len(), MAX_ADDRESS_LINES);
+        assert!(!converted.report.is_lossless());
+    }
+}
@@ -0,0 +1,516 @@
+use std::{io::Write, str::FromStr};
+
+use anyhow::{Context, anyhow};
+use quick_xml::{
+    Reader, Writer,
+    events::{BytesEnd, BytesStart, BytesText, Event},
+};
+
+use crate::{
+    AnyhowError, AnyhowResult,
+    domain::{AddressType, ISO20022Address, ISO20022Version, ProprietaryAddressType},
+};
+
+/// Tag of the ISO 20022 postal address element
+pub(crate) const POSTAL_ADDRESS_TAG: &str = "PstlAdr";
+
+/// Tag of the address type element, which holds either a `<Cd>` or a `<Prtry>` identification
+const ADDRESS_TYPE_TAG: &str = "AdrTp";
+
+/// Tag of the repeated unstructured address line element, which comes after every structured element
+const ADDRESS_LINE_TAG: &str = "AdrLine";
+
+impl ISO20022Address {
+    /// Serialize the address as an ISO 20022 `<PstlAdr>` XML fragment of the default version.
+    ///
+    /// Every populated element the version accepts is emitted in the schema order and its content is XML-escaped.
+    pub fn to_xml(&self) -> String {
+        self.to_versioned_xml(ISO20022Version::default())
+    }
+
+    /// Serialize the address as an ISO 20022 `<PstlAdr>` XML fragment of the given version.
+    ///
+    /// The address is first converted to the version, so that the elements it lacks are folded into the ones it
+    /// accepts (see [ISO20022Address::to_version]). `<AdrTp>` holds its code as text in PostalAddress6 and in a `<Cd>`
+    /// element afterwards.
+    pub fn to_versioned_xml(&self, version: ISO20022Version) -> String {
+        let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+
+        // Writing into a `Vec` cannot fail
+        write_postal_address(&mut writer, self, version)
+            .expect("Writing XML into memory should not fail");
+
+        String::from_utf8(writer.into_inner()).expect("Generated XML should be valid UTF-8")
+    }
+
+    /// Parse the first `<PstlAdr>` element found in an XML fragment or document.
+    ///
+    /// Namespace prefixes are ignored. Missing street name, post code or town name are read as empty strings.
+    pub fn from_xml(xml: &str) -> AnyhowResult<Self> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+
+        loop {
+            match reader.read_event().with_context(|| "Failed to read XML")? {
+                Event::Start(start)
+                    if start.local_name().as_ref() == POSTAL_ADDRESS_TAG.as_bytes() =>
+                {
+                    return read_postal_address(&mut reader);
+                }
+                Event::Empty(empty)
+                    if empty.local_name().as_ref() == POSTAL_ADDRESS_TAG.as_bytes() =>
+                {
+                    return Ok(ISO20022Address::default());
+                }
+                Event::Eof => return Err(anyhow!("No <{}> element found", POSTAL_ADDRESS_TAG)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Elements of the address in the ISO 20022 schema order
+    pub(crate) fn xml_elements(&self) -> [(&'static str, Option<&str>); 16] {
+        [
+            ("CareOf", self.care_of.as_deref()),
+            ("Dept", self.department.as_deref()),
+            ("SubDept", self.sub_department.as_deref()),
+            ("StrtNm", Some(self.street_name.as_str())),
+            ("BldgNb", self.building_number.as_deref()),
+            ("BldgNm", self.building_name.as_deref()),
+            ("Flr", self.floor.as_deref()),
+            ("UnitNb", self.unit_number.as_deref()),
+            ("PstBx", self.post_box.as_deref()),
+            ("Rm", self.room.as_deref()),
+            ("PstCd", Some(self.post_code.as_str())),
+            ("TwnNm", Some(self.town_name.as_str())),
+            ("TwnLctnNm", self.town_location_name.as_deref()),
+            ("DstrctNm", self.district_name.as_deref()),
+            ("CtrySubDvsn", self.country_sub_division.as_deref()),
+            ("Ctry", Some(self.country.as_str())),
+        ]
+    }
+
+    fn set_xml_element(&mut self, tag: &str, value: String) -> AnyhowResult<()> {
+        match tag {
+            "CareOf" => self.care_of = Some(value),
+            "Dept" => self.department = Some(value),
+            "SubDept" => self.sub_department = Some(value),
+            "StrtNm" => self.street_name = value,
+            "BldgNb" => self.building_number = Some(value),
+            "BldgNm" => self.building_name = Some(value),
+            "Flr" => self.floor = Some(value),
+            "UnitNb" => self.unit_number = Some(value),
+            "PstBx" => self.post_box = Some(value),
+            "Rm" => self.room = Some(value),
+            "PstCd" => self.post_code = value,
+            "TwnNm" => self.town_name = value,
+            "TwnLctnNm" => self.town_location_name = Some(value),
+            "DstrctNm" => self.district_name = Some(value),
+            "CtrySubDvsn" => self.country_sub_division = Some(value),
+            "Ctry" => self.country = value,
+            ADDRESS_LINE_TAG => self.address_lines.push(value),
+            _ => {
+                return Err(anyhow!(
+                    "Unsupported <{}> element: '{}'",
+                    POSTAL_ADDRESS_TAG,
+                    tag
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for ISO20022Address {
+    type Err = AnyhowError;
+
+    fn from_str(xml: &str) -> AnyhowResult<Self> {
+        Self::from_xml(xml)
+    }
+}
+
+/// Write an address, converted to the given version, as a `<PstlAdr>` element
+pub(crate) fn write_postal_address<W: Write>(
+    writer: &mut Writer<W>,
+    address: &ISO20022Address,
+    version: ISO20022Version,
+) -> AnyhowResult<()> {
+    let address = &address.clone().to_version(version).address;
+
+    writer.write_event(Event::Start(BytesStart::new(POSTAL_ADDRESS_TAG)))?;
+
+    if let Some(ref address_type) = address.address_type {
+        write_address_type(writer, address_type, version)?;
+    }
+
+    for (tag, value) in address.xml_elements() {
+        if let Some(value) = value.filter(|value| !value.is_empty()) {
+            write_text_element(writer, tag, value)?;
+        }
+    }
+
+    for line in address.address_lines.iter().filter(|line| !line.is_empty()) {
+        write_text_element(writer, ADDRESS_LINE_TAG, line)?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new(POSTAL_ADDRESS_TAG)))?;
+
+    Ok(())
+}
+
+/// Write an `<AdrTp>` element with its code or proprietary identification.
+///
+/// PostalAddress6 takes the code as the text of `<AdrTp>` and has no proprietary identification, which is left out.
+fn write_address_type<W: Write>(
+    writer: &mut Writer<W>,
+    address_type: &AddressType,
+    version: ISO20022Version,
+) -> AnyhowResult<()> {
+    if version == ISO20022Version::PostalAddress6 {
+        if let Some(code) = address_type.code() {
+            write_text_element(writer, ADDRESS_TYPE_TAG, code)?;
+        }
+
+        return Ok(());
+    }
+
+    writer.write_event(Event::Start(BytesStart::new(ADDRESS_TYPE_TAG)))?;
+
+    match address_type {
+        AddressType::Proprietary(proprietary) => {
+            writer.write_event(Event::Start(BytesStart::new("Prtry")))?;
+            write_text_element(writer, "Id", &proprietary.id)?;
+            write_text_element(writer, "Issr", &proprietary.issuer)?;
+            if let Some(ref scheme_name) = proprietary.scheme_name {
+                write_text_element(writer, "SchmeNm", scheme_name)?;
+            }
+            writer.write_event(Event::End(BytesEnd::new("Prtry")))?;
+        }
+        address_type => write_text_element(writer, "Cd", address_type.code().unwrap_or_default())?,
+    }
+
+    writer.write_event(Event::End(BytesEnd::new(ADDRESS_TYPE_TAG)))?;
+
+    Ok(())
+}
+
+/// Write a `<tag>value</tag>` element with an escaped value
+pub(crate) fn write_text_element<W: Write>(
+    writer: &mut Writer<W>,
+    tag: &str,
+    value: &str,
+) -> AnyhowResult<()> {
+    writer.write_event(Event::Start(BytesStart::new(tag)))?;
+    writer.write_event(Event::Text(BytesText::new(value)))?;
+    writer.write_event(Event::End(BytesEnd::new(tag)))?;
+
+    Ok(())
+}
+
+/// Read the content of a `<PstlAdr>` element whose start tag has just been consumed
+pub(crate) fn read_postal_address(reader: &mut Reader<&[u8]>) -> AnyhowResult<ISO20022Address> {
+    let mut address = ISO20022Address::default();
+
+    loop {
+        match reader.read_event().with_context(|| "Failed to read XML")? {
+            Event::Start(start) => {
+                let tag = local_name(&start)?;
+                if tag == ADDRESS_TYPE_TAG {
+                    address.address_type = Some(read_address_type(reader)?);
+                    continue;
+                }
+
+                let value = read_text_content(reader, &tag)?;
+                address.set_xml_element(&tag, value)?;
+            }
+            Event::Empty(empty) => {
+                let tag = local_name(&empty)?;
+                address.set_xml_element(&tag, String::new())?;
+            }
+            Event::End(_) => return Ok(address),
+            Event::Eof => {
+                return Err(anyhow!(
+                    "Unexpected end of XML inside <{}>",
+                    POSTAL_ADDRESS_TAG
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Read the content of an `<AdrTp>` element whose start tag has just been consumed.
+///
+/// Both the PostalAddress24 form (`<Cd>` or `<Prtry>`) and the PostalAddress6 one (the code as text) are accepted.
+fn read_address_type(reader: &mut Reader<&[u8]>) -> AnyhowResult<AddressType> {
+    let mut code = String::new();
+    let mut proprietary: Option<ProprietaryAddressType> = None;
+
+    loop {
+        match reader.read_event().with_context(|| "Failed to read XML")? {
+            Event::Text(text) => code.push_str(&text.unescape()?),
+            Event::Start(start) => match local_name(&start)?.as_str() {
+                "Prtry" => proprietary = Some(ProprietaryAddressType::default()),
+                tag => {
+                    let value = read_text_content(reader, tag)?;
+                    match (tag, proprietary.as_mut()) {
+                        ("Cd", _) => code = value,
+                        ("Id", Some(proprietary)) => proprietary.id = value,
+                        ("Issr", Some(proprietary)) => proprietary.issuer = value,
+                        ("SchmeNm", Some(proprietary)) => proprietary.scheme_name = Some(value),
+                        _ => {
+                            return Err(anyhow!(
+                                "Unsupported <{}> element: '{}'",
+                                ADDRESS_TYPE_TAG,
+                                tag
+                            ));
+                        }
+                    }
+                }
+            },
+            // End of <Prtry>
+            Event::End(end) if end.local_name().as_ref() != ADDRESS_TYPE_TAG.as_bytes() => {}
+            Event::End(_) => break,
+            Event::Eof => {
+                return Err(anyhow!(
+                    "Unexpected end of XML inside <{}>",
+                    ADDRESS_TYPE_TAG
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    match proprietary {
+        Some(proprietary) => Ok(AddressType::Proprietary(proprietary)),
+        None => AddressType::from_code(&code)
+            .ok_or_else(|| anyhow!("Unknown <{}> code: '{}'", ADDRESS_TYPE_TAG, code)),
+    }
+}
+
+/// Read the unescaped text of a simple element whose start tag has just been consumed
+pub(crate) fn read_text_content(reader: &mut Reader<&[u8]>, tag: &str) -> AnyhowResult<String> {
+    let mut value = String::new();
+
+    loop {
+        match reader.read_event().with_context(|| "Failed to read XML")? {
+            Event::Text(text) => value.push_str(&text.unescape()?),
+            Event::CData(data) => value.push_str(&String::from_utf8_lossy(&data)),
+            Event::End(_) => return Ok(value),
+            Event::Start(_) | Event::Empty(_) => {
+                return Err(anyhow!("Unexpected nested element inside <{}>", tag));
+            }
+            Event::Eof => return Err(anyhow!("Unexpected end of XML inside <{}>", tag)),
+            _ => {}
+        }
+    }
+}
+
+/// Local name (without namespace prefix) of an element
+pub(crate) fn local_name(start: &BytesStart) -> AnyhowResult<String> {
+    let name = std::str::from_utf8(start.local_name().into_inner())
+        .with_context(|| "Element name is not valid UTF-8")?;
+
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn full_address() -> ISO20022Address {
+        ISO20022Address {
+            address_type: Some(AddressType::Business),
+            care_of: Some("Mireille COPEAU".to_string()),
+            department: Some("Service Comptabilité".to_string()),
+            sub_department: Some("Fournisseurs".to_string()),
+            street_name: "RUE DES FLEURS".to_string(),
+            building_number: Some("22BIS".to_string()),
+            building_name: Some("Bâtiment Jonquille".to_string()),
+            floor: Some("3".to_string()),
+            unit_number: Some("B12".to_string()),
+            post_box: Some("BP 40122".to_string()),
+            room: Some("Appartement 2".to_string()),
+            post_code: "33506".to_string(),
+            town_name: "LIBOURNE CEDEX".to_string(),
+            town_location_name: Some("CAUDOS".to_string()),
+            district_name: Some("Libournais".to_string()),
+            country_sub_division: Some("FR-33".to_string()),
+            country: "FR".to_string(),
+            address_lines: vec!["Lieu-dit Le Bourg".to_string(), "Porte 4".to_string()],
+        }
+    }
+
+    #[test]
+    fn to_versioned_xml_emits_every_populated_element_in_schema_order() {
+        let xml = full_address().to_versioned_xml(ISO20022Version::PostalAddress27);
+
+        assert_eq!(
+            xml,
+            "<PstlAdr>\n  <AdrTp>\n    <Cd>BIZZ</Cd>\n  </AdrTp>\n  <CareOf>Mireille COPEAU</CareOf>\n  <Dept>Service Comptabilité</Dept>\n  <SubDept>Fournisseurs</SubDept>\n  <StrtNm>RUE DES FLEURS</StrtNm>\n  <BldgNb>22BIS</BldgNb>\n  <BldgNm>Bâtiment Jonquille</BldgNm>\n  <Flr>3</Flr>\n  <UnitNb>B12</UnitNb>\n  <PstBx>BP 40122</PstBx>\n  <Rm>Appartement 2</Rm>\n  <PstCd>33506</PstCd>\n  <TwnNm>LIBOURNE CEDEX</TwnNm>\n  <TwnLctnNm>CAUDOS</TwnLctnNm>\n  <DstrctNm>Libournais</DstrctNm>\n  <CtrySubDvsn>FR-33</CtrySubDvsn>\n  <Ctry>FR</Ctry>\n  <AdrLine>Lieu-dit Le Bourg</AdrLine>\n  <AdrLine>Porte 4</AdrLine>\n</PstlAdr>"
+        );
+    }
+
+    #[test]
+    fn from_xml_reads_proprietary_and_postal_address6_address_types() {
+        let proprietary = ISO20022Address {
+            address_type: Some(AddressType::Proprietary(ProprietaryAddressType {
+                id: "HQ01".to_string(),
+                issuer: "ACME".to_string(),
+                scheme_name: Some("SITES".to_string()),
+            })),
+            ..Default::default()
+        };
+        let postal_address6 = "<PstlAdr><AdrTp>PBOX</AdrTp><Ctry>FR</Ctry></PstlAdr>";
+
+        assert_eq!(
+            ISO20022Address::from_xml(&proprietary.to_xml()).unwrap(),
+            proprietary
+        );
+        assert_eq!(
+            ISO20022Address::from_xml(postal_address6)
+                .unwrap()
+                .address_type,
+            Some(AddressType::PostBox)
+        );
+        ISO20022Address::from_xml("<PstlAdr><AdrTp><Cd>XXXX</Cd></AdrTp></PstlAdr>")
+            .expect_err("Should return an error for an unknown address type code");
+    }
+
+    #[test]
+    fn to_versioned_xml_writes_postal_address6_address_type_as_text() {
+        let address = ISO20022Address {
+            address_type: Some(AddressType::Business),
+            country: "FR".to_string(),
+            ..Default::default()
+        };
+
+        let xml = address.to_versioned_xml(ISO20022Version::PostalAddress6);
+
+        assert_eq!(
+            xml,
+            "<PstlAdr>\n  <AdrTp>BIZZ</AdrTp>\n  <Ctry>FR</Ctry>\n</PstlAdr>"
+        );
+        assert_eq!(ISO20022Address::from_xml(&xml).unwrap(), address);
+        assert!(
+            address
+                .to_versioned_xml(ISO20022Version::PostalAddress24)
+                .contains("<AdrTp>\n    <Cd>BIZZ</Cd>\n  </AdrTp>")
+        );
+    }
+
+    #[test]
+    fn to_xml_skips_empty_elements() {
+        let address = ISO20022Address {
+            street_name: "".to_string(),
+            post_code: "33500".to_string(),
+            town_name: "LIBOURNE".to_string(),
+            country: "FR".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            address.to_xml(),
+            "<PstlAdr>\n  <PstCd>33500</PstCd>\n  <TwnNm>LIBOURNE</TwnNm>\n  <Ctry>FR</Ctry>\n</PstlAdr>"
+        );
+    }
+
+    #[test]
+    fn to_xml_escapes_special_characters() {
+        let address = ISO20022Address {
+            street_name: "RUE <DES> \"FLEURS\" & CIE".to_string(),
+            ..Default::default()
+        };
+
+        assert!(
+            address
+                .to_xml()
+                .contains("<StrtNm>RUE &lt;DES&gt; &quot;FLEURS&quot; &amp; CIE</StrtNm>")
+        );
+    }
+
+    #[test]
+    fn to_xml_folds_elements_missing_from_the_default_version() {
+        let xml = full_address().to_xml();
+
+        assert!(!xml.contains("<CareOf>"));
+        assert!(!xml.contains("<UnitNb>"));
+        assert!(xml.contains("<Rm>Appartement 2, B12</Rm>"));
+        assert!(xml.contains("<AdrLine>c/o Mireille COPEAU</AdrLine>"));
+    }
+
+    #[test]
+    fn to_versioned_xml_writes_only_postal_address6_elements() {
+        let xml = full_address().to_versioned_xml(ISO20022Version::PostalAddress6);
+
+        for tag in [
+            "CareOf",
+            "UnitNb",
+            "BldgNm",
+            "Flr",
+            "PstBx",
+            "Rm",
+            "TwnLctnNm",
+            "DstrctNm",
+        ] {
+            assert!(!xml.contains(&format!("<{tag}>")), "{tag}");
+        }
+        assert!(xml.contains("<AdrLine>BP 40122</AdrLine>"));
+    }
+
+    #[test]
+    fn from_xml_round_trips_every_element() {
+        let address = full_address();
+
+        let parsed =
+            ISO20022Address::from_xml(&address.to_versioned_xml(ISO20022Version::PostalAddress27))
+                .unwrap();
+
+        assert_eq!(parsed, address);
+    }
+
+    #[test]
+    fn from_xml_unescapes_and_ignores_namespace_prefixes() {
+        let xml = r#"<doc:Cdtr xmlns:doc="urn:iso:std:iso:20022:tech:xsd:pain.001.001.09">
+            <doc:Nm>ACME</doc:Nm>
+            <doc:PstlAdr>
+                <doc:StrtNm>RUE DES FLEURS &amp; CIE</doc:StrtNm>
+                <doc:PstCd>33500</doc:PstCd>
+                <doc:TwnNm>LIBOURNE</doc:TwnNm>
+                <doc:Ctry>FR</doc:Ctry>
+            </doc:PstlAdr>
+        </doc:Cdtr>"#;
+
+        let parsed: ISO20022Address = xml.parse().unwrap();
+
+        assert_eq!(
+            parsed,
+            ISO20022Address {
+                street_name: "RUE DES FLEURS & CIE".to_string(),
+                post_code: "33500".to_string(),
+                town_name: "LIBOURNE".to_string(),
+                country: "FR".to_string(),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn from_xml_returns_error_when_postal_address_is_missing() {
+        ISO20022Address::from_xml("<Cdtr><Nm>ACME</Nm></Cdtr>")
+            .expect_err("Should return an error when <PstlAdr> is missing");
+    }
+
+    #[test]
+    fn from_xml_returns_error_for_unknown_element() {
+        ISO20022Address::from_xml("<PstlAdr><Foo>bar</Foo></PstlAdr>")
+            .expect_err("Should return an error for an unknown element");
+    }
+
+    #[test]
+    fn from_xml_returns_error_for_truncated_xml() {
+        ISO20022Address::from_xml("<PstlAdr><StrtNm>RUE DES FLEURS</StrtNm>")
+            .expect_err("Should return an error for truncated XML");
+    }
+}
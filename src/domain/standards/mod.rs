@@ -1,5 +1,33 @@
 mod french_address;
+mod french_care_of;
+mod french_country;
+mod french_delivery_point;
+mod french_department;
+mod french_distribution;
+mod french_normalizer;
+mod french_parser;
+mod french_postal_reference;
+mod french_street;
+mod french_validator;
 mod iso20022_address;
+mod iso20022_message;
+mod iso20022_party;
+mod iso20022_rendering;
+mod iso20022_structurer;
+mod iso20022_validator;
+mod iso20022_version;
+mod iso20022_xml;
 
 pub use french_address::FrenchAddress;
+pub use french_department::{FrenchDepartment, FrenchRegion};
+pub use french_parser::ParsedFrenchAddress;
+pub use french_postal_reference::{FrenchPostalReference, PostalCodeCheck, PostalCommune};
+pub use french_street::FrenchStreetLine;
+pub use french_validator::*;
 pub use iso20022_address::ISO20022Address;
+pub use iso20022_message::*;
+pub use iso20022_party::*;
+pub use iso20022_rendering::*;
+pub use iso20022_structurer::*;
+pub use iso20022_validator::*;
+pub use iso20022_version::*;
@@ -0,0 +1,122 @@
+//! Text helpers shared by the address standards
+
+/// Replace accented Latin letters by their unaccented form (e.g. `Bâtiment` → `Batiment`, `Œ` → `OE`)
+pub(crate) fn strip_diacritics(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => stripped.push('A'),
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => stripped.push('a'),
+            'Æ' => stripped.push_str("AE"),
+            'æ' => stripped.push_str("ae"),
+            'Ç' | 'Ć' | 'Č' => stripped.push('C'),
+            'ç' | 'ć' | 'č' => stripped.push('c'),
+            'Ď' | 'Đ' => stripped.push('D'),
+            'ď' | 'đ' => stripped.push('d'),
+            'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => stripped.push('E'),
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => stripped.push('e'),
+            'Ğ' => stripped.push('G'),
+            'ğ' => stripped.push('g'),
+            'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' | 'İ' => stripped.push('I'),
+            'ì' | 'í' | 'î' | 'ï' | 'ī' | 'ı' => stripped.push('i'),
+            'Ł' => stripped.push('L'),
+            'ł' => stripped.push('l'),
+            'Ñ' | 'Ń' | 'Ň' => stripped.push('N'),
+            'ñ' | 'ń' | 'ň' => stripped.push('n'),
+            'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ő' => stripped.push('O'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => stripped.push('o'),
+            'Œ' => stripped.push_str("OE"),
+            'œ' => stripped.push_str("oe"),
+            'Ř' => stripped.push('R'),
+            'ř' => stripped.push('r'),
+            'Ś' | 'Ş' | 'Š' => stripped.push('S'),
+            'ś' | 'ş' | 'š' => stripped.push('s'),
+            'ß' => stripped.push_str("ss"),
+            'Ţ' | 'Ť' => stripped.push('T'),
+            'ţ' | 'ť' => stripped.push('t'),
+            'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ū' | 'Ů' | 'Ű' => stripped.push('U'),
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => stripped.push('u'),
+            'Ý' | 'Ÿ' => stripped.push('Y'),
+            'ý' | 'ÿ' => stripped.push('y'),
+            'Ź' | 'Ż' | 'Ž' => stripped.push('Z'),
+            'ź' | 'ż' | 'ž' => stripped.push('z'),
+            _ => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+/// Replace the typographic apostrophe variants (`’`, `‘`, `ʼ`, `´`, `` ` ``) by a straight apostrophe
+pub(crate) fn normalize_apostrophes(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '’' | '‘' | 'ʼ' | '´' | '`' => '\'',
+            _ => c,
+        })
+        .collect()
+}
+
+/// Trim the text and replace every run of whitespace by a single space
+pub(crate) fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Uppercase, unaccented and whitespace-collapsed form of a text, used for lenient comparisons
+pub(crate) fn fold(text: &str) -> String {
+    collapse_whitespace(&strip_diacritics(text).to_uppercase())
+}
+
+/// Number of single-character insertions, deletions or substitutions turning one text into the other (Levenshtein)
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_diacritics_removes_french_accents_and_ligatures() {
+        assert_eq!(
+            strip_diacritics("Entrée A Bâtiment Jonquille, Cœur d’Île"),
+            "Entree A Batiment Jonquille, Coeur d’Ile".to_string()
+        );
+    }
+
+    #[test]
+    fn normalize_apostrophes_replaces_typographic_variants() {
+        assert_eq!(
+            normalize_apostrophes("RUE DE L’EGLISE L‘ABBAYE"),
+            "RUE DE L'EGLISE L'ABBAYE".to_string()
+        );
+    }
+
+    #[test]
+    fn fold_ignores_case_accents_and_spacing() {
+        assert_eq!(fold("  Côte   d'Ivoire "), "COTE D'IVOIRE".to_string());
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("LIBOURNE", "LIBOURNE"), 0);
+        assert_eq!(edit_distance("LIBOUNRE", "LIBOURNE"), 2);
+        assert_eq!(edit_distance("MIO", "MIOS"), 1);
+        assert_eq!(edit_distance("", "MIOS"), 4);
+    }
+}
@@ -1,9 +1,12 @@
 use std::{path::PathBuf, str::FromStr, sync::Arc};
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use uuid::Uuid;
 
-use crate::{AddressService, AnyhowResult, repository::FileAddressRepository};
+use crate::{
+    AddressService, AddressType, AnyhowResult, ConversionOptions, FrenchPostalReference,
+    ISO20022AddressMode, ISO20022Profile, ISO20022Version, repository::FileAddressRepository,
+};
 
 use super::AddressHandler;
 
@@ -18,6 +21,10 @@ struct Cli {
     #[clap(short, long, default_value = "addresses.json")]
     database: PathBuf,
 
+    /// Path of the La Poste postal code / commune CSV (defaults to the embedded snapshot)
+    #[clap(long, global = true)]
+    postal_reference: Option<PathBuf>,
+
     #[clap(subcommand)]
     command: Command,
 }
@@ -32,13 +39,159 @@ pub enum AddressFormat {
     Iso20022,
 }
 
+/// Format of an address given as input
+#[derive(ValueEnum, Clone, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Internal address as JSON
+    Internal,
+
+    /// French address as JSON
+    French,
+
+    /// French address as a free-text block of 3 to 7 lines
+    FrenchText,
+
+    /// ISO 20022 address as JSON or as a `<PstlAdr>` XML fragment
+    Iso20022,
+}
+
+/// Rendering of ISO 20022 addresses, depending on what the target scheme accepts
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Iso20022Mode {
+    /// Every element in its own structured element
+    #[default]
+    Structured,
+
+    /// Town, postal code and country structured, the rest in at most 2 address lines
+    Hybrid,
+
+    /// Only the country structured, the rest in at most 7 address lines
+    Unstructured,
+}
+
+impl From<Iso20022Mode> for ISO20022AddressMode {
+    fn from(mode: Iso20022Mode) -> Self {
+        match mode {
+            Iso20022Mode::Structured => ISO20022AddressMode::Structured,
+            Iso20022Mode::Hybrid => ISO20022AddressMode::Hybrid,
+            Iso20022Mode::Unstructured => ISO20022AddressMode::Unstructured,
+        }
+    }
+}
+
+/// Version of the ISO 20022 postal address type of the target message
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PostalAddressVersion {
+    /// PostalAddress6 (pain.001.001.03, pacs.008.001.02)
+    #[value(name = "6")]
+    PostalAddress6,
+
+    /// PostalAddress24 (pain.001.001.09, pacs.008.001.08)
+    #[default]
+    #[value(name = "24")]
+    PostalAddress24,
+
+    /// PostalAddress27 (pain.001.001.12, pacs.008.001.12)
+    #[value(name = "27")]
+    PostalAddress27,
+}
+
+impl From<PostalAddressVersion> for ISO20022Version {
+    fn from(version: PostalAddressVersion) -> Self {
+        match version {
+            PostalAddressVersion::PostalAddress6 => ISO20022Version::PostalAddress6,
+            PostalAddressVersion::PostalAddress24 => ISO20022Version::PostalAddress24,
+            PostalAddressVersion::PostalAddress27 => ISO20022Version::PostalAddress27,
+        }
+    }
+}
+
+/// Market practice an ISO 20022 address is validated against
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Iso20022ValidationProfile {
+    /// Only the ISO 20022 schema
+    #[default]
+    Schema,
+
+    /// SWIFT CBPR+ usage guidelines
+    CbprPlus,
+
+    /// EPC SEPA rulebooks
+    EpcSepa,
+}
+
+impl From<Iso20022ValidationProfile> for ISO20022Profile {
+    fn from(profile: Iso20022ValidationProfile) -> Self {
+        match profile {
+            Iso20022ValidationProfile::Schema => ISO20022Profile::Schema,
+            Iso20022ValidationProfile::CbprPlus => ISO20022Profile::CbprPlus,
+            Iso20022ValidationProfile::EpcSepa => ISO20022Profile::EpcSepa,
+        }
+    }
+}
+
+/// Options of the conversion of an address to the output format
+#[derive(Args, Clone, Debug, Default)]
+struct ConversionArgs {
+    /// Fail when the conversion would drop or make up a value
+    #[clap(long)]
+    strict: bool,
+
+    /// Rewrite the French output into its NF Z10-011 postal-compliant form
+    #[clap(long)]
+    normalize: bool,
+
+    /// Leave out line 7 of the French output when the address is delivered within France
+    #[clap(long)]
+    omit_domestic_country: bool,
+
+    /// Fill the missing `<CtrySubDvsn>` of the ISO 20022 output of French addresses with the department of their
+    /// postal code
+    #[clap(long)]
+    derive_country_sub_division: bool,
+
+    /// Rendering of the ISO 20022 output
+    #[clap(long, value_enum, default_value = "structured")]
+    iso_mode: Iso20022Mode,
+
+    /// Version of the ISO 20022 postal address type (PostalAddress6, 24 or 27)
+    #[clap(long, value_enum, default_value = "24")]
+    iso_version: PostalAddressVersion,
+
+    /// La Poste reference the French addresses are checked against, given by the global `--postal-reference`
+    #[clap(skip)]
+    postal_reference: Option<Arc<FrenchPostalReference>>,
+}
+
+impl From<ConversionArgs> for ConversionOptions {
+    fn from(args: ConversionArgs) -> Self {
+        ConversionOptions {
+            strict: args.strict,
+            normalize: args.normalize,
+            omit_domestic_country: args.omit_domestic_country,
+            derive_country_sub_division: args.derive_country_sub_division,
+            postal_reference: args.postal_reference,
+            iso_mode: args.iso_mode.into(),
+            iso_version: args.iso_version.into(),
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Command {
     /// Add a new address
     Add {
-        /// Address internal data as JSON string
+        /// Address data, `@<path>` to read a file, or `-` to read stdin (default)
         #[clap(short, long)]
-        data: String,
+        data: Option<String>,
+
+        /// Format of the address data
+        #[clap(short, long, value_enum, default_value = "internal")]
+        format: InputFormat,
+
+        /// Fail when the conversion would drop or make up a value
+        #[clap(long)]
+        strict: bool,
     },
 
     /// Get an address by ID
@@ -50,6 +203,9 @@ enum Command {
         /// Output format (french or iso20022)
         #[clap(short, long, value_enum)]
         format: Option<AddressFormat>,
+
+        #[command(flatten)]
+        conversion: ConversionArgs,
     },
 
     /// List all addresses
@@ -57,6 +213,13 @@ enum Command {
         /// Output format (french or iso20022)
         #[clap(short, long, value_enum)]
         format: Option<AddressFormat>,
+
+        /// Only list the addresses of this ISO 20022 type (ADDR, PBOX, HOME, BIZZ, MLTO or DLVY)
+        #[clap(long, value_parser = parse_address_type)]
+        address_type: Option<AddressType>,
+
+        #[command(flatten)]
+        conversion: ConversionArgs,
     },
 
     /// Update an existing address
@@ -76,35 +239,214 @@ enum Command {
         #[clap(short, long)]
         id: String,
     },
-    // /// Convert an address between formats (not implemented yet)
-    // Convert {
-    //     /// Source
-    //     #[clap(short, long)]
-    //     data: String,
-
-    //     /// Source format
-    //     #[clap(short, long, value_enum)]
-    //     from: AddressFormat,
-
-    //     /// Target format
-    //     #[clap(short, long, value_enum)]
-    //     to: AddressFormat,
-    // },
+
+    /// Convert an address between formats without touching the database
+    Convert {
+        /// Source address as JSON string, `@<path>` to read a file, or `-` to read stdin (default)
+        #[clap(short, long)]
+        data: Option<String>,
+
+        /// Source format
+        #[clap(short, long, value_enum)]
+        from: AddressFormat,
+
+        /// Target format
+        #[clap(short, long, value_enum)]
+        to: AddressFormat,
+
+        /// Path of the file to write the converted address to (defaults to stdout)
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+
+    /// Check an address against the rules of its format without touching the database
+    Validate {
+        /// Address as JSON string, `@<path>` to read a file, or `-` to read stdin (default)
+        #[clap(short, long)]
+        data: Option<String>,
+
+        /// Format of the address
+        #[clap(short, long, value_enum)]
+        format: AddressFormat,
+
+        /// Market practice of the ISO 20022 address
+        #[clap(short, long, value_enum, default_value = "schema")]
+        profile: Iso20022ValidationProfile,
+    },
+
+    /// Detect the structured elements of an unstructured ISO 20022 address (given in `<AdrLine>`)
+    Structure {
+        /// ISO 20022 address as JSON or XML, `@<path>` to read a file, or `-` to read stdin (default)
+        #[clap(short, long)]
+        data: Option<String>,
+    },
+
+    /// Extract the postal addresses of the parties and agents of a pain.001 or pacs.008 XML file
+    Extract {
+        /// Path of the pain.001 or pacs.008 XML file
+        #[clap(long)]
+        file: PathBuf,
+
+        /// Format of the exported addresses (french or iso20022)
+        #[clap(short, long, value_enum, required_unless_present = "import")]
+        to: Option<AddressFormat>,
+
+        /// Import the addresses into the database instead of exporting them
+        #[clap(long, conflicts_with = "to")]
+        import: bool,
+
+        /// Path of the file to write the exported addresses to (defaults to stdout)
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+
+    /// Rewrite the unstructured and partially structured postal addresses of a pain.001 or pacs.008 XML file into
+    /// their structured form
+    Rewrite {
+        /// Path of the pain.001 or pacs.008 XML file
+        #[clap(long)]
+        file: PathBuf,
+
+        /// Path of the file to write the rewritten message to
+        #[clap(short, long)]
+        output: PathBuf,
+
+        /// Market practice the rewritten addresses must comply with
+        #[clap(short, long, value_enum, default_value = "schema")]
+        profile: Iso20022ValidationProfile,
+
+        /// Version of the ISO 20022 postal address type of the message (PostalAddress6, 24 or 27), read from the
+        /// namespace of the message by default
+        #[clap(long, value_enum)]
+        iso_version: Option<PostalAddressVersion>,
+    },
+
+    /// Import the postal addresses of the parties related to the entries of a camt.053 or camt.054 XML file
+    ImportStatement {
+        /// Path of the camt.053 or camt.054 XML file
+        #[clap(long)]
+        file: PathBuf,
+
+        /// Fail when a conversion would drop or make up a value
+        #[clap(long)]
+        strict: bool,
+    },
+
+    /// List the communes served by a French postal code
+    Communes {
+        /// 5-digit postal code
+        #[clap(short, long)]
+        post_code: String,
+    },
+}
+
+/// Parse an ISO 20022 address type code
+fn parse_address_type(code: &str) -> Result<AddressType, String> {
+    AddressType::from_code(code).ok_or_else(|| format!("unknown address type '{code}'"))
 }
 
 /// Run the CLI
 pub fn run() -> AnyhowResult<()> {
     let cli = Cli::parse();
-    let repository = Arc::new(FileAddressRepository::new(&cli.database)?);
-    let service = AddressService::new(repository);
-    let handler = AddressHandler::new(service);
+    // The database is only opened (and possibly created) by the commands that need it
+    let handler = || -> AnyhowResult<AddressHandler> {
+        let repository = Arc::new(FileAddressRepository::new(&cli.database)?);
+        let service = AddressService::new(repository);
+
+        Ok(AddressHandler::new(service))
+    };
+    let postal_reference = || -> AnyhowResult<FrenchPostalReference> {
+        match &cli.postal_reference {
+            Some(path) => FrenchPostalReference::load(path),
+            None => FrenchPostalReference::embedded(),
+        }
+    };
+    let options = |conversion: ConversionArgs| -> AnyhowResult<ConversionOptions> {
+        Ok(ConversionArgs {
+            postal_reference: Some(Arc::new(postal_reference()?)),
+            ..conversion
+        }
+        .into())
+    };
 
     match cli.command {
-        Command::Get { id, format } => handler.handle_get(Uuid::from_str(&id)?, format),
-        Command::List { format } => handler.handle_list(format),
-        Command::Add { data } => handler.handle_add(data),
-        Command::Update { id, data } => handler.handle_update(Uuid::from_str(&id)?, data),
-        Command::Delete { id } => handler.handle_delete(Uuid::from_str(&id)?),
-        // Command::Convert { data, from, to } => handler.handle_convert(data, from, to),
+        Command::Get {
+            id,
+            format,
+            conversion,
+        } => handler()?.handle_get(Uuid::from_str(&id)?, format, options(conversion)?),
+        Command::List {
+            format,
+            address_type,
+            conversion,
+        } => handler()?.handle_list(format, address_type, options(conversion)?),
+        Command::Add {
+            data,
+            format,
+            strict,
+        } => handler()?.handle_add(
+            data,
+            format,
+            options(ConversionArgs {
+                strict,
+                ..Default::default()
+            })?,
+        ),
+        Command::Update { id, data } => handler()?.handle_update(Uuid::from_str(&id)?, data),
+        Command::Delete { id } => handler()?.handle_delete(Uuid::from_str(&id)?),
+        Command::Convert {
+            data,
+            from,
+            to,
+            output,
+            conversion,
+        } => AddressHandler::handle_convert(data, from, to, output, options(conversion)?),
+        Command::Validate {
+            data,
+            format,
+            profile,
+        } => AddressHandler::handle_validate(data, format, profile.into(), &postal_reference()?),
+        Command::Structure { data } => AddressHandler::handle_structure(data),
+        Command::Extract {
+            file,
+            to,
+            import: _,
+            output,
+            conversion,
+        } => {
+            let options = options(conversion)?;
+            match to {
+                Some(to) => AddressHandler::handle_extract(file, to, output, options),
+                None => handler()?.handle_import_message(file, options),
+            }
+        }
+        Command::Rewrite {
+            file,
+            output,
+            profile,
+            iso_version,
+        } => AddressHandler::handle_rewrite(
+            file,
+            output,
+            profile.into(),
+            iso_version.map(Into::into),
+            options(ConversionArgs::default())?,
+        ),
+        Command::ImportStatement { file, strict } => handler()?.handle_import_statement(
+            file,
+            options(ConversionArgs {
+                strict,
+                ..Default::default()
+            })?,
+        ),
+        Command::Communes { post_code } => {
+            AddressHandler::handle_communes(&post_code, &postal_reference()?)
+        }
     }
 }
@@ -1,9 +1,22 @@
-use anyhow::Context;
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+use anyhow::{Context, anyhow};
+use serde::Serialize;
 use uuid::Uuid;
 
-use crate::{Address, AddressService, AnyhowResult, FrenchAddress, ISO20022Address};
+use crate::{
+    Address, AddressConverter, AddressService, AddressType, AnyhowResult, ConversionOptions,
+    ConversionReport, Converted, FrenchAddress, FrenchDepartment, FrenchPostalReference,
+    ISO20022Address, ISO20022Message, ISO20022MessageAddress, ISO20022MessageType, ISO20022Party,
+    ISO20022Profile, ISO20022Version, ImportOutcome, MessageRewriter, RewrittenMessage,
+    StructuredISO20022Address,
+};
 
-use super::AddressFormat;
+use super::{AddressFormat, InputFormat};
 
 /// CLI handler for address operations
 pub struct AddressHandler {
@@ -17,28 +30,34 @@ impl AddressHandler {
     }
 
     /// Get an address by id and format it accordingly.
-    pub fn handle_get(&self, id: Uuid, address_format: Option<AddressFormat>) -> AnyhowResult<()> {
-        let formatted_output = self.get(id, address_format)?;
+    pub fn handle_get(
+        &self,
+        id: Uuid,
+        address_format: Option<AddressFormat>,
+        options: ConversionOptions,
+    ) -> AnyhowResult<()> {
+        let formatted_output = self.get(id, address_format, &options)?;
 
         println!("{}", formatted_output);
 
         Ok(())
     }
 
-    fn get(&self, id: Uuid, address_format: Option<AddressFormat>) -> AnyhowResult<String> {
+    fn get(
+        &self,
+        id: Uuid,
+        address_format: Option<AddressFormat>,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<String> {
         let address = self
             .service
             .get(id)?
             .ok_or(anyhow::anyhow!("Address not found"))?;
 
         let formatted_output = match address_format {
-            Some(AddressFormat::French) => {
-                let french: FrenchAddress = address.try_into()?;
-                format!("id: {id}\n{french}")
-            }
-            Some(AddressFormat::Iso20022) => {
-                let iso: ISO20022Address = address.into();
-                format!("id: {id}\n{iso}")
+            Some(address_format) => {
+                let formatted = format_address(address, &address_format, options, &id.to_string())?;
+                format!("id: {id}\n{formatted}")
             }
             None => serde_json::to_string_pretty(&address)?,
         };
@@ -46,64 +65,75 @@ impl AddressHandler {
         Ok(formatted_output)
     }
 
-    /// List all addresses and format them accordingly.
-    pub fn handle_list(&self, address_format: Option<AddressFormat>) -> AnyhowResult<()> {
-        let formatted_output = self.list(address_format)?;
+    /// List all addresses, or only the ones of an address type, and format them accordingly.
+    pub fn handle_list(
+        &self,
+        address_format: Option<AddressFormat>,
+        address_type: Option<AddressType>,
+        options: ConversionOptions,
+    ) -> AnyhowResult<()> {
+        let formatted_output = self.list(address_format, address_type.as_ref(), &options)?;
 
         println!("{}", formatted_output);
 
         Ok(())
     }
 
-    fn list(&self, address_format: Option<AddressFormat>) -> AnyhowResult<String> {
-        let addresses = self
+    fn list(
+        &self,
+        address_format: Option<AddressFormat>,
+        address_type: Option<&AddressType>,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<String> {
+        let mut addresses = self
             .service
             .list()
             .with_context(|| "Failed to list addresses")?;
+        if let Some(address_type) = address_type {
+            addresses.retain(|(_, address)| address.address_type.as_ref() == Some(address_type));
+        }
 
         let formatted_output = match address_format {
-            Some(AddressFormat::French) => {
+            Some(address_format) => {
                 let addresses: Vec<String> = addresses
-                    .iter()
+                    .into_iter()
                     .map(|(id, address)| {
-                        address
-                            .clone()
-                            .try_into()
-                            .map(|french: FrenchAddress| format!("id: {id}\n{french}"))
+                        format_address(address, &address_format, options, &id.to_string())
+                            .map(|formatted| format!("id: {id}\n{formatted}"))
+                            .with_context(|| format!("Failed to convert address {id}"))
                     })
                     .collect::<Result<_, _>>()?;
 
                 addresses.join("\n\n")
             }
-            Some(AddressFormat::Iso20022) => {
-                let addresses: Vec<String> = addresses
-                    .iter()
-                    .map(|(id, address)| {
-                        let iso: ISO20022Address = address.clone().into();
-
-                        Ok(format!("id: {id}\n{iso}"))
-                    })
-                    .collect::<Result<_, anyhow::Error>>()?;
-
-                addresses.join("\n\n")
-            }
             None => serde_json::to_string_pretty(&addresses)
                 .with_context(|| "Failed to list addresses")?,
         };
         Ok(formatted_output)
     }
 
-    /// Add a new address
-    pub fn handle_add(&self, data: String) -> AnyhowResult<()> {
-        let id = self.add(data)?;
+    /// Add a new address, converted from the input format with the given options
+    pub fn handle_add(
+        &self,
+        data: Option<String>,
+        input_format: InputFormat,
+        options: ConversionOptions,
+    ) -> AnyhowResult<()> {
+        let input = read_input(data)?;
+        let id = self.add(&input, input_format, &options)?;
 
         println!("Address added with id: {}", id);
 
         Ok(())
     }
 
-    fn add(&self, data: String) -> AnyhowResult<Uuid> {
-        let address: Address = serde_json::from_str(&data)?;
+    fn add(
+        &self,
+        data: &str,
+        input_format: InputFormat,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Uuid> {
+        let address = parse_address(data, input_format, options)?.address;
 
         let id = self.service.add(&address)?;
 
@@ -133,47 +163,572 @@ impl AddressHandler {
         Ok(())
     }
 
-    // /// Convert an address between formats
-    // pub fn handle_convert(
-    //     &self,
-    //     data: String,
-    //     from: AddressFormat,
-    //     to: AddressFormat,
-    // ) -> AnyhowResult<()> {
-    //     if from == to {
-    //         return Err(anyhow!("Source and target formats are the same"));
-    //     }
-
-    //     match (from, to) {
-    //         (AddressFormat::French, AddressFormat::Iso20022) => {
-    //             let address: FrenchAddress = serde_json::from_str(&data)?;
-    //             let iso: ISO20022Address = AddressConverter::french_to_iso(address)?;
-
-    //             println!("{}", iso);
-    //         }
-    //         (AddressFormat::Iso20022, AddressFormat::French) => {
-    //             let address: ISO20022Address = serde_json::from_str(&data)?;
-    //             let french: FrenchAddress = AddressConverter::iso_to_french(address)?;
-
-    //             println!("{}", french);
-    //         }
-    //         _ => {
-    //             return Err(anyhow!("Unsupported format conversion"));
-    //         }
-    //     }
-
-    //     Ok(())
-    // }
+    /// Convert an address between formats and write it to the output file or stdout.
+    ///
+    /// The conversion is stateless and never touches the database.
+    pub fn handle_convert(
+        data: Option<String>,
+        from: AddressFormat,
+        to: AddressFormat,
+        output: Option<PathBuf>,
+        options: ConversionOptions,
+    ) -> AnyhowResult<()> {
+        let input = read_input(data)?;
+        let converted = Self::convert(&input, from, to, &options)?;
+
+        match output {
+            Some(path) => fs::write(&path, format!("{converted}\n"))
+                .with_context(|| format!("Failed to write converted address to {:?}", path))?,
+            None => println!("{}", converted),
+        }
+
+        Ok(())
+    }
+
+    fn convert(
+        data: &str,
+        from: AddressFormat,
+        to: AddressFormat,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<String> {
+        // An ISO 20022 address can be converted to another version of the postal address type
+        if from == to && from != AddressFormat::Iso20022 {
+            return Err(anyhow!("Source and target formats are the same"));
+        }
+
+        let formatted_output = match (from, to) {
+            (AddressFormat::French, AddressFormat::Iso20022) => {
+                let address: FrenchAddress = serde_json::from_str(data)
+                    .with_context(|| "Failed to parse French address JSON")?;
+                let party = AddressConverter::french_to_party(address, options)?;
+                print_warnings("conversion", &party.report);
+
+                party.address.to_versioned_xml(options.iso_version)
+            }
+            (AddressFormat::Iso20022, AddressFormat::French) => {
+                let party = parse_iso20022(data)?;
+                let french = AddressConverter::party_to_french(party, options)?;
+                print_warnings("conversion", &french.report);
+
+                french.address.to_string()
+            }
+            (AddressFormat::Iso20022, AddressFormat::Iso20022) => {
+                let mut party = parse_iso20022(data)?;
+                let iso = AddressConverter::iso_to_iso(party.postal_address, options)?;
+                print_warnings("conversion", &iso.report);
+
+                party.postal_address = iso.address;
+                party.to_versioned_xml(options.iso_version)
+            }
+            _ => return Err(anyhow!("Unsupported format conversion")),
+        };
+
+        Ok(formatted_output)
+    }
+
+    /// Validate an address against the rules of its format and print every violation.
+    ///
+    /// Returns an error when the address is not valid.
+    pub fn handle_validate(
+        data: Option<String>,
+        address_format: AddressFormat,
+        profile: ISO20022Profile,
+        postal_reference: &FrenchPostalReference,
+    ) -> AnyhowResult<()> {
+        let input = read_input(data)?;
+        let violations = Self::validate(&input, address_format, profile, postal_reference)?;
+
+        if violations.is_empty() {
+            println!("Address is valid");
+
+            return Ok(());
+        }
+
+        for violation in &violations {
+            println!("{}", violation);
+        }
+
+        Err(anyhow!("Address has {} violation(s)", violations.len()))
+    }
+
+    fn validate(
+        data: &str,
+        address_format: AddressFormat,
+        profile: ISO20022Profile,
+        postal_reference: &FrenchPostalReference,
+    ) -> AnyhowResult<Vec<String>> {
+        match address_format {
+            AddressFormat::French => {
+                let address: FrenchAddress = serde_json::from_str(data)
+                    .with_context(|| "Failed to parse French address JSON")?;
+
+                Ok(address
+                    .validate_with_reference(postal_reference)
+                    .iter()
+                    .map(|violation| violation.to_string())
+                    .collect())
+            }
+            AddressFormat::Iso20022 => Ok(parse_iso20022(data)?
+                .postal_address
+                .validate(profile)
+                .iter()
+                .map(|violation| violation.to_string())
+                .collect()),
+        }
+    }
+
+    /// Structure the `<AdrLine>` of an ISO 20022 address, print it as XML and the confidence of each element on stderr.
+    ///
+    /// Returns an error when the address needs a human review, after printing it.
+    pub fn handle_structure(data: Option<String>) -> AnyhowResult<()> {
+        let input = read_input(data)?;
+        let structured = parse_iso20022(&input)?.postal_address.structure();
+
+        println!("{}", structured.address.to_xml());
+        print_confidences(&structured);
+
+        if structured.needs_review {
+            return Err(anyhow!("Structured address needs a human review"));
+        }
+
+        Ok(())
+    }
+
+    /// Extract the postal addresses of the parties and agents of a pain.001 or pacs.008 file, convert them through the
+    /// internal model and write them as French or ISO 20022 JSON to the output file or stdout, with their XPath.
+    ///
+    /// The extraction is stateless and never touches the database.
+    pub fn handle_extract(
+        file: PathBuf,
+        to: AddressFormat,
+        output: Option<PathBuf>,
+        options: ConversionOptions,
+    ) -> AnyhowResult<()> {
+        let xml = read_message(&file)?;
+        let extracted = Self::extract(&xml, to, &options)?;
+
+        match output {
+            Some(path) => fs::write(&path, format!("{extracted}\n"))
+                .with_context(|| format!("Failed to write extracted addresses to {:?}", path))?,
+            None => println!("{}", extracted),
+        }
+
+        Ok(())
+    }
+
+    fn extract(xml: &str, to: AddressFormat, options: &ConversionOptions) -> AnyhowResult<String> {
+        let addresses = parse_message(xml)?.addresses;
+        let extracted: Vec<ExtractedAddress<ExportedAddress>> =
+            convert_message_addresses(addresses, options)?
+                .into_iter()
+                .map(|extracted| {
+                    let context = || format!("Failed to convert address at {}", extracted.xpath);
+                    let converted = match to {
+                        AddressFormat::French => {
+                            let french =
+                                AddressConverter::internal_to_french(extracted.address, options)
+                                    .with_context(context)?;
+                            print_warnings(&extracted.xpath, &french.report);
+
+                            ExportedAddress::French(french.address)
+                        }
+                        AddressFormat::Iso20022 => {
+                            let party =
+                                AddressConverter::internal_to_party(extracted.address, options)
+                                    .with_context(context)?;
+                            print_warnings(&extracted.xpath, &party.report);
+
+                            ExportedAddress::Iso20022(Box::new(party.address))
+                        }
+                    };
+
+                    Ok(ExtractedAddress {
+                        xpath: extracted.xpath,
+                        party: extracted.party,
+                        address: converted,
+                    })
+                })
+                .collect::<AnyhowResult<_>>()?;
+
+        serde_json::to_string_pretty(&extracted).with_context(|| "Failed to export addresses")
+    }
+
+    /// Rewrite the unstructured and partially structured postal addresses of a pain.001 or pacs.008 file into their
+    /// structured form, write the new message to the output file and print the change log of every address.
+    ///
+    /// Returns an error when the rewrite of an address was refused, after writing the message.
+    pub fn handle_rewrite(
+        file: PathBuf,
+        output: PathBuf,
+        profile: ISO20022Profile,
+        iso_version: Option<ISO20022Version>,
+        options: ConversionOptions,
+    ) -> AnyhowResult<()> {
+        let xml = read_message(&file)?;
+        let rewritten = Self::rewrite(&xml, profile, iso_version, &options)?;
+
+        fs::write(&output, &rewritten.xml)
+            .with_context(|| format!("Failed to write rewritten message to {:?}", output))?;
+        for change in &rewritten.changes {
+            println!("{}", change);
+        }
+
+        match rewritten.refused() {
+            0 => Ok(()),
+            refused => Err(anyhow!(
+                "{refused} address(es) left unchanged, they need a human review"
+            )),
+        }
+    }
+
+    fn rewrite(
+        xml: &str,
+        profile: ISO20022Profile,
+        iso_version: Option<ISO20022Version>,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<RewrittenMessage> {
+        MessageRewriter::rewrite(xml, profile, iso_version, options)
+            .with_context(|| "Failed to rewrite message addresses")
+    }
+
+    /// Import the postal addresses of the parties and agents of a pain.001 or pacs.008 file into the database.
+    ///
+    /// Every address is converted before the first one is added, so that a failed conversion imports nothing.
+    pub fn handle_import_message(
+        &self,
+        file: PathBuf,
+        options: ConversionOptions,
+    ) -> AnyhowResult<()> {
+        let xml = read_message(&file)?;
+
+        println!("{}", self.import_message(&xml, &options)?);
+
+        Ok(())
+    }
+
+    fn import_message(&self, xml: &str, options: &ConversionOptions) -> AnyhowResult<String> {
+        let addresses = parse_message(xml)?.addresses;
+        let imported: Vec<String> = convert_message_addresses(addresses, options)?
+            .into_iter()
+            .map(|extracted| {
+                let id = self.service.add(&extracted.address)?;
+
+                Ok(format!(
+                    "{} ({}): address added with id: {id}",
+                    extracted.xpath, extracted.party
+                ))
+            })
+            .collect::<AnyhowResult<_>>()?;
+
+        Ok(imported.join("\n"))
+    }
+
+    /// Import the postal addresses of the parties related to the entries (`<RltdPties>`) of a camt.053 or camt.054 file
+    /// into the database, with the reference of their statement.
+    ///
+    /// An address already stored is not added again but given the statement reference. Every address is converted
+    /// before the first one is imported, so that a failed conversion imports nothing.
+    pub fn handle_import_statement(
+        &self,
+        file: PathBuf,
+        options: ConversionOptions,
+    ) -> AnyhowResult<()> {
+        let xml = read_message(&file)?;
+
+        println!("{}", self.import_statement(&xml, &options)?);
+
+        Ok(())
+    }
+
+    fn import_statement(&self, xml: &str, options: &ConversionOptions) -> AnyhowResult<String> {
+        let message = parse_message(xml)?;
+        if !matches!(
+            message.message_type,
+            ISO20022MessageType::Camt053 | ISO20022MessageType::Camt054
+        ) {
+            return Err(anyhow!(
+                "A {} message is not a camt.053 or camt.054 statement",
+                message.message_type
+            ));
+        }
+
+        let related_parties = message
+            .addresses
+            .into_iter()
+            .filter(ISO20022MessageAddress::is_related_party)
+            .collect();
+        let imported: Vec<String> = convert_message_addresses(related_parties, options)?
+            .into_iter()
+            .map(|extracted| {
+                let outcome = match self.service.import(&extracted.address)? {
+                    ImportOutcome::Added(id) => format!("address added with id: {id}"),
+                    ImportOutcome::Merged(id) => format!("address already stored with id: {id}"),
+                };
+
+                Ok(format!(
+                    "{} ({}): {outcome}",
+                    extracted.xpath, extracted.party
+                ))
+            })
+            .collect::<AnyhowResult<_>>()?;
+
+        Ok(imported.join("\n"))
+    }
+
+    /// Print the department and the communes served by a French postal code in the La Poste reference
+    pub fn handle_communes(
+        post_code: &str,
+        postal_reference: &FrenchPostalReference,
+    ) -> AnyhowResult<()> {
+        println!("{}", Self::communes(post_code, postal_reference)?);
+
+        Ok(())
+    }
+
+    fn communes(post_code: &str, postal_reference: &FrenchPostalReference) -> AnyhowResult<String> {
+        let communes = postal_reference.communes(post_code);
+        if communes.is_empty() {
+            return Err(anyhow!(
+                "Postal code {post_code} is not in the La Poste reference"
+            ));
+        }
+
+        let department = FrenchDepartment::from_post_code(post_code).map_or_else(
+            || "no department".to_string(),
+            |department| department.to_string(),
+        );
+        let communes = communes.iter().map(|commune| {
+            format!(
+                "{} {} ({}, INSEE {})",
+                commune.post_code, commune.routing_label, commune.name, commune.insee_code
+            )
+        });
+
+        Ok([department]
+            .into_iter()
+            .chain(communes)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+/// Address extracted from a payment message, with the XPath of its `<PstlAdr>` and the tag of its party
+#[derive(Debug, Serialize)]
+struct ExtractedAddress<T> {
+    xpath: String,
+    party: String,
+    address: T,
+}
+
+/// Address exported in the requested format
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum ExportedAddress {
+    French(FrenchAddress),
+    Iso20022(Box<ISO20022Party>),
+}
+
+/// Convert an internal address to the requested format, printing the alterations made on stderr
+fn format_address(
+    address: Address,
+    address_format: &AddressFormat,
+    options: &ConversionOptions,
+    context: &str,
+) -> AnyhowResult<String> {
+    let formatted = match address_format {
+        AddressFormat::French => {
+            let french = AddressConverter::internal_to_french(address, options)?;
+            print_warnings(context, &french.report);
+
+            french.address.to_string()
+        }
+        AddressFormat::Iso20022 => {
+            let party = AddressConverter::internal_to_party(address, options)?;
+            print_warnings(context, &party.report);
+
+            party.address.to_versioned_xml(options.iso_version)
+        }
+    };
+
+    Ok(formatted)
+}
+
+/// Print the alterations of a conversion on stderr, so that stdout only contains addresses
+fn print_warnings(context: &str, report: &ConversionReport) {
+    for warning in &report.warnings {
+        eprintln!("Warning ({context}): {warning}");
+    }
+}
+
+/// Print the confidence of each element of a structured ISO 20022 address on stderr
+fn print_confidences(structured: &StructuredISO20022Address) {
+    for confidence in &structured.confidences {
+        eprintln!(
+            "Confidence {:.2}: <{}> '{}'",
+            confidence.confidence, confidence.element, confidence.value
+        );
+    }
+    for line in &structured.address.address_lines {
+        eprintln!("Warning (structuring): line left in <AdrLine>: '{line}'");
+    }
+}
+
+/// Parse an address in the given input format and convert it to the internal model, printing the alterations made
+fn parse_address(
+    data: &str,
+    input_format: InputFormat,
+    options: &ConversionOptions,
+) -> AnyhowResult<Converted<Address>> {
+    let converted = match input_format {
+        InputFormat::Internal => {
+            return Ok(Converted::new(
+                serde_json::from_str(data)?,
+                ConversionReport::default(),
+            ));
+        }
+        InputFormat::French => {
+            let address: FrenchAddress = serde_json::from_str(data)
+                .with_context(|| "Failed to parse French address JSON")?;
+
+            AddressConverter::french_to_internal(address, options)?
+        }
+        InputFormat::FrenchText => {
+            let parsed = FrenchAddress::parse_text(data)
+                .with_context(|| "Failed to parse French address text")?;
+
+            eprintln!("Parsing confidence: {:.2}", parsed.confidence);
+            for line in &parsed.unclassified_lines {
+                eprintln!("Warning (parsing): unclassified line ignored: '{line}'");
+            }
+
+            AddressConverter::french_to_internal(parsed.address, options)?
+        }
+        InputFormat::Iso20022 => {
+            let mut party = parse_iso20022(data)?;
+            party.postal_address = structure_unstructured(party.postal_address, "structuring");
+
+            AddressConverter::party_to_internal(party, options)?
+        }
+    };
+
+    print_warnings("conversion", &converted.report);
+
+    Ok(converted)
+}
+
+/// Structure an unstructured ISO 20022 address, which only has its elements in `<AdrLine>`, printing the confidences
+fn structure_unstructured(address: ISO20022Address, context: &str) -> ISO20022Address {
+    if !address.town_name.is_empty() || address.address_lines.is_empty() {
+        return address;
+    }
+
+    let structured = address.structure();
+    print_confidences(&structured);
+    if structured.needs_review {
+        eprintln!("Warning ({context}): address needs a human review");
+    }
+
+    structured.address
+}
+
+/// Parse a payment message or statement, printing the number of postal addresses found on stderr
+fn parse_message(xml: &str) -> AnyhowResult<ISO20022Message> {
+    let message = ISO20022Message::from_xml(xml).with_context(|| "Failed to parse message XML")?;
+    eprintln!(
+        "Found {} postal address(es) in {} message",
+        message.addresses.len(),
+        message.message_type
+    );
+
+    Ok(message)
+}
+
+/// Convert postal addresses of a message to the internal model, with their XPath and party.
+///
+/// The reference of the statement holding an address is added to its source references.
+fn convert_message_addresses(
+    addresses: Vec<ISO20022MessageAddress>,
+    options: &ConversionOptions,
+) -> AnyhowResult<Vec<ExtractedAddress<Address>>> {
+    addresses
+        .into_iter()
+        .map(|mut address| {
+            address.postal_address = structure_unstructured(address.postal_address, &address.xpath);
+
+            let mut internal = AddressConverter::party_to_internal(address.to_party(), options)
+                .with_context(|| format!("Failed to convert address at {}", address.xpath))?;
+            print_warnings(&address.xpath, &internal.report);
+            internal
+                .address
+                .source_references
+                .extend(address.statement_reference);
+
+            Ok(ExtractedAddress {
+                xpath: address.xpath,
+                party: address.party,
+                address: internal.address,
+            })
+        })
+        .collect()
+}
+
+/// Read a payment message or statement file
+fn read_message(file: &PathBuf) -> AnyhowResult<String> {
+    fs::read_to_string(file).with_context(|| format!("Failed to read message from file {:?}", file))
+}
+
+/// Parse an ISO 20022 party (`<Cdtr>`, `<Dbtr>`, ...) or a bare postal address, given either as XML or as JSON
+fn parse_iso20022(data: &str) -> AnyhowResult<ISO20022Party> {
+    if data.trim_start().starts_with('<') {
+        return ISO20022Party::from_xml(data)
+            .with_context(|| "Failed to parse ISO 20022 address XML");
+    }
+
+    let json: serde_json::Value =
+        serde_json::from_str(data).with_context(|| "Failed to parse ISO 20022 address JSON")?;
+    let party = if json.get("PstlAdr").is_some() {
+        serde_json::from_value(json)
+    } else {
+        serde_json::from_value(json).map(|postal_address| ISO20022Party {
+            postal_address,
+            ..Default::default()
+        })
+    };
+
+    party.with_context(|| "Failed to parse ISO 20022 address JSON")
+}
+
+/// Read the command input: inline data, `@<path>` to read a file, or stdin when absent or `-`.
+fn read_input(data: Option<String>) -> AnyhowResult<String> {
+    match data.as_deref() {
+        None | Some("-") => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .with_context(|| "Failed to read address from stdin")?;
+
+            Ok(input)
+        }
+        Some(data) => match data.strip_prefix('@') {
+            Some(path) => fs::read_to_string(path)
+                .with_context(|| format!("Failed to read address from file {:?}", path)),
+            None => Ok(data.to_string()),
+        },
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
-    use crate::{Address, AddressRepository, InMemoryAddressRepository};
+    use crate::{Address, AddressRepository, ISO20022Version, InMemoryAddressRepository};
 
     use super::*;
 
+    fn postal_reference() -> FrenchPostalReference {
+        FrenchPostalReference::embedded().unwrap()
+    }
+
     #[test]
     fn get_existing_address_with_french_format() {
         let repository = Arc::new(InMemoryAddressRepository::default());
@@ -182,7 +737,13 @@ mod tests {
         let service = AddressService::new(repository);
         let handler = AddressHandler::new(service);
 
-        let output = handler.get(id, Some(AddressFormat::French)).unwrap();
+        let output = handler
+            .get(
+                id,
+                Some(AddressFormat::French),
+                &ConversionOptions::default(),
+            )
+            .unwrap();
 
         let expected_french = FrenchAddress::try_from(address.clone()).unwrap();
         assert!(output.contains(&id.to_string()));
@@ -197,11 +758,17 @@ mod tests {
         let service = AddressService::new(repository);
         let handler = AddressHandler::new(service);
 
-        let output = handler.get(id, Some(AddressFormat::Iso20022)).unwrap();
+        let output = handler
+            .get(
+                id,
+                Some(AddressFormat::Iso20022),
+                &ConversionOptions::default(),
+            )
+            .unwrap();
 
-        let expected_iso = ISO20022Address::from(address.clone());
         assert!(output.contains(&id.to_string()));
-        assert!(output.contains(&expected_iso.to_string()));
+        assert!(output.contains("<Cdtr>\n  <Nm>John Doe</Nm>\n  <PstlAdr>"));
+        assert!(output.contains("<TwnNm>Anytown</TwnNm>"));
     }
 
     #[test]
@@ -212,7 +779,9 @@ mod tests {
         let service = AddressService::new(repository);
         let handler = AddressHandler::new(service);
 
-        let output = handler.get(id, None).unwrap();
+        let output = handler
+            .get(id, None, &ConversionOptions::default())
+            .unwrap();
 
         let expected_json = serde_json::to_string_pretty(&address).unwrap();
         assert!(output.contains(&expected_json));
@@ -224,10 +793,40 @@ mod tests {
         let handler = AddressHandler::new(service);
 
         handler
-            .handle_get(Uuid::new_v4(), None)
+            .handle_get(Uuid::new_v4(), None, ConversionOptions::default())
             .expect_err("Should return an error");
     }
 
+    #[test]
+    fn get_address_with_french_format_fails_in_strict_mode_when_lossy() {
+        let repository = Arc::new(InMemoryAddressRepository::default());
+        let address = Address {
+            district_name: Some("Libournais".to_string()),
+            ..Address::dummy()
+        };
+        let id = repository.save(&address).unwrap();
+        let service = AddressService::new(repository);
+        let handler = AddressHandler::new(service);
+
+        handler
+            .get(
+                id,
+                Some(AddressFormat::French),
+                &ConversionOptions::default(),
+            )
+            .expect("Should succeed when not strict");
+        handler
+            .get(
+                id,
+                Some(AddressFormat::French),
+                &ConversionOptions {
+                    strict: true,
+                    ..Default::default()
+                },
+            )
+            .expect_err("Should fail in strict mode when the district name is dropped");
+    }
+
     #[test]
     fn list_addresses_with_french_format() {
         let repository = Arc::new(InMemoryAddressRepository::default());
@@ -236,13 +835,43 @@ mod tests {
         let service = AddressService::new(repository);
         let handler = AddressHandler::new(service);
 
-        let output = handler.list(Some(AddressFormat::French)).unwrap();
+        let output = handler
+            .list(
+                Some(AddressFormat::French),
+                None,
+                &ConversionOptions::default(),
+            )
+            .unwrap();
 
         let expected_french = FrenchAddress::try_from(address.clone()).unwrap();
         assert!(output.contains(&id.to_string()));
         assert!(output.contains(&expected_french.to_string()));
     }
 
+    #[test]
+    fn list_addresses_of_an_address_type() {
+        let repository = Arc::new(InMemoryAddressRepository::default());
+        let business = Address {
+            name: Some("ACME SARL".to_string()),
+            address_type: Some(AddressType::Business),
+            ..Address::dummy()
+        };
+        repository.save(&business).unwrap();
+        repository.save(&Address::dummy()).unwrap();
+        let handler = AddressHandler::new(AddressService::new(repository));
+
+        let output = handler
+            .list(
+                Some(AddressFormat::French),
+                Some(&AddressType::Business),
+                &ConversionOptions::default(),
+            )
+            .unwrap();
+
+        assert!(output.contains("ACME SARL"));
+        assert!(!output.contains("John Doe"));
+    }
+
     #[test]
     fn list_addresses_with_iso_format() {
         let repository = Arc::new(InMemoryAddressRepository::default());
@@ -251,11 +880,17 @@ mod tests {
         let service = AddressService::new(repository);
         let handler = AddressHandler::new(service);
 
-        let output = handler.list(Some(AddressFormat::Iso20022)).unwrap();
+        let output = handler
+            .list(
+                Some(AddressFormat::Iso20022),
+                None,
+                &ConversionOptions::default(),
+            )
+            .unwrap();
 
-        let expected_iso = ISO20022Address::from(address.clone());
         assert!(output.contains(&id.to_string()));
-        assert!(output.contains(&expected_iso.to_string()));
+        assert!(output.contains("<Cdtr>\n  <Nm>John Doe</Nm>\n  <PstlAdr>"));
+        assert!(output.contains("<TwnNm>Anytown</TwnNm>"));
     }
 
     #[test]
@@ -271,7 +906,13 @@ mod tests {
             "country": "FR"
         }"#;
 
-        let id = handler.add(address.to_string()).unwrap();
+        let id = handler
+            .add(
+                address,
+                InputFormat::Internal,
+                &ConversionOptions::default(),
+            )
+            .unwrap();
 
         let address = repository.get(id).unwrap().unwrap();
 
@@ -289,10 +930,87 @@ mod tests {
         let handler = AddressHandler::new(service);
 
         handler
-            .add("invalid internal address format".to_string())
+            .add(
+                "invalid internal address format",
+                InputFormat::Internal,
+                &ConversionOptions::default(),
+            )
             .expect_err("Should return an error for invalid internal address format");
     }
 
+    #[test]
+    fn add_stores_address_parsed_from_french_text() {
+        let repository = Arc::new(InMemoryAddressRepository::default());
+        let service = AddressService::new(repository.clone());
+        let handler = AddressHandler::new(service);
+        let text = "Monsieur Jean DURAND\n25D RUE DES FLEURS\n33500 LIBOURNE\nFrance";
+
+        let id = handler
+            .add(text, InputFormat::FrenchText, &ConversionOptions::default())
+            .unwrap();
+
+        let address = repository.get(id).unwrap().unwrap();
+        assert_eq!(address.name, Some("Monsieur Jean DURAND".to_string()));
+        assert_eq!(address.building_number, Some("25D".to_string()));
+        assert_eq!(address.street_name, "RUE DES FLEURS".to_string());
+        assert_eq!(address.post_code, "33500".to_string());
+        assert_eq!(address.country, "FR".to_string());
+    }
+
+    #[test]
+    fn parse_address_checks_french_town_against_postal_reference() {
+        let options = ConversionOptions {
+            postal_reference: Some(Arc::new(postal_reference())),
+            ..Default::default()
+        };
+        let text = "Monsieur Jean DURAND\n25D RUE DES FLEURS\n33500 PARIS\nFrance";
+
+        let converted = parse_address(text, InputFormat::FrenchText, &options).unwrap();
+
+        let warning = &converted.report.warnings[0];
+        assert_eq!(warning.kind, crate::AlterationKind::Unverified);
+        assert_eq!(warning.field, "town_name");
+        assert!(warning.detail.contains("LIBOURNE"));
+    }
+
+    #[test]
+    fn add_stores_address_from_iso20022_xml() {
+        let repository = Arc::new(InMemoryAddressRepository::default());
+        let service = AddressService::new(repository.clone());
+        let handler = AddressHandler::new(service);
+        let iso = ISO20022Address::from(Address::dummy());
+
+        let id = handler
+            .add(
+                &iso.to_xml(),
+                InputFormat::Iso20022,
+                &ConversionOptions::default(),
+            )
+            .unwrap();
+
+        let address = repository.get(id).unwrap().unwrap();
+        assert_eq!(ISO20022Address::from(address), iso);
+    }
+
+    #[test]
+    fn add_structures_unstructured_iso20022_address() {
+        let repository = Arc::new(InMemoryAddressRepository::default());
+        let service = AddressService::new(repository.clone());
+        let handler = AddressHandler::new(service);
+        let xml = "<PstlAdr><AdrLine>25 RUE DES FLEURS</AdrLine><AdrLine>33500 LIBOURNE</AdrLine><AdrLine>FRANCE</AdrLine></PstlAdr>";
+
+        let id = handler
+            .add(xml, InputFormat::Iso20022, &ConversionOptions::default())
+            .unwrap();
+
+        let address = repository.get(id).unwrap().unwrap();
+        assert_eq!(address.building_number, Some("25".to_string()));
+        assert_eq!(address.street_name, "RUE DES FLEURS".to_string());
+        assert_eq!(address.town_name, "LIBOURNE".to_string());
+        assert_eq!(address.country, "FR".to_string());
+        assert!(address.address_lines.is_empty());
+    }
+
     #[test]
     fn update_stores_new_address_values() {
         let repository = Arc::new(InMemoryAddressRepository::default());
@@ -347,4 +1065,460 @@ mod tests {
 
         assert!(repository.get(id).unwrap().is_none());
     }
+
+    #[test]
+    fn convert_french_to_iso20022() {
+        let french = FrenchAddress::try_from(Address::dummy()).unwrap();
+        let data = serde_json::to_string(&french).unwrap();
+
+        let output = AddressHandler::convert(
+            &data,
+            AddressFormat::French,
+            AddressFormat::Iso20022,
+            &ConversionOptions::default(),
+        )
+        .unwrap();
+
+        let expected_party =
+            AddressConverter::french_to_party(french, &ConversionOptions::default()).unwrap();
+        assert_eq!(output, expected_party.address.to_string());
+        assert!(output.contains("<Nm>John Doe</Nm>"));
+    }
+
+    #[test]
+    fn convert_iso20022_to_french_synthesizes_missing_name() {
+        let iso = ISO20022Address::from(Address::dummy());
+        let data = serde_json::to_string(&iso).unwrap();
+
+        let output = AddressHandler::convert(
+            &data,
+            AddressFormat::Iso20022,
+            AddressFormat::French,
+            &ConversionOptions::default(),
+        )
+        .unwrap();
+
+        assert!(output.contains("12345 Anytown"));
+    }
+
+    #[test]
+    fn convert_returns_error_in_strict_mode_when_name_is_missing() {
+        let iso = ISO20022Address::from(Address::dummy());
+        let data = serde_json::to_string(&iso).unwrap();
+
+        // The ISO 20022 postal address carries no recipient name, which the French format requires
+        AddressHandler::convert(
+            &data,
+            AddressFormat::Iso20022,
+            AddressFormat::French,
+            &ConversionOptions {
+                strict: true,
+                ..Default::default()
+            },
+        )
+        .expect_err("Should return an error in strict mode when name is missing");
+    }
+
+    #[test]
+    fn convert_returns_error_when_formats_are_the_same() {
+        AddressHandler::convert(
+            "{}",
+            AddressFormat::French,
+            AddressFormat::French,
+            &ConversionOptions::default(),
+        )
+        .expect_err("Should return an error when formats are the same");
+    }
+
+    #[test]
+    fn convert_iso20022_to_another_postal_address_version() {
+        let data = "<PstlAdr><Flr>3</Flr><PstCd>33500</PstCd><TwnNm>LIBOURNE</TwnNm><Ctry>FR</Ctry></PstlAdr>";
+
+        let converted = AddressHandler::convert(
+            data,
+            AddressFormat::Iso20022,
+            AddressFormat::Iso20022,
+            &ConversionOptions {
+                iso_version: ISO20022Version::PostalAddress6,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(!converted.contains("<Flr>"));
+        assert!(converted.contains("<AdrLine>3</AdrLine>"));
+    }
+
+    #[test]
+    fn convert_french_business_to_postal_address6_writes_address_type_as_text() {
+        let data = r#"{"name": "ACME SARL", "recipient_info": null, "geographic_info": null, "street": "25 RUE DES FLEURS", "special_mentions": null, "postal_info": "33500 LIBOURNE", "country": "FRANCE"}"#;
+
+        let converted = AddressHandler::convert(
+            data,
+            AddressFormat::French,
+            AddressFormat::Iso20022,
+            &ConversionOptions {
+                iso_version: ISO20022Version::PostalAddress6,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(converted.contains("<AdrTp>BIZZ</AdrTp>"));
+        assert!(!converted.contains("<Cd>"));
+    }
+
+    #[test]
+    fn convert_returns_error_for_invalid_source_data() {
+        AddressHandler::convert(
+            "invalid french address format",
+            AddressFormat::French,
+            AddressFormat::Iso20022,
+            &ConversionOptions::default(),
+        )
+        .expect_err("Should return an error for invalid source data");
+    }
+
+    #[test]
+    fn validate_french_address_lists_violations() {
+        let french = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            None,
+            "25 rue des Fleurs".to_string(),
+            None,
+            "33500 LIBOURNE".to_string(),
+            "France".to_string(),
+        );
+        let data = serde_json::to_string(&french).unwrap();
+
+        let violations = AddressHandler::validate(
+            &data,
+            AddressFormat::French,
+            ISO20022Profile::default(),
+            &postal_reference(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            violations,
+            vec!["line 4: line is not in uppercase: '25 rue des Fleurs'".to_string()]
+        );
+    }
+
+    #[test]
+    fn validate_french_address_flags_town_not_served_by_postal_code() {
+        let french = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            None,
+            "25 RUE DES FLEURS".to_string(),
+            None,
+            "33500 PARIS".to_string(),
+            "France".to_string(),
+        );
+        let data = serde_json::to_string(&french).unwrap();
+
+        let violations = AddressHandler::validate(
+            &data,
+            AddressFormat::French,
+            ISO20022Profile::default(),
+            &postal_reference(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            violations,
+            vec![
+                "line 6: locality is not served by postal code 33500, expected ARVEYRES, LES BILLAUX, LIBOURNE, POMEROL: '33500 PARIS'"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_iso20022_address_against_cbpr_plus_profile() {
+        let data = "<PstlAdr><PstCd>33500</PstCd><Ctry>FR</Ctry></PstlAdr>";
+
+        let violations = AddressHandler::validate(
+            data,
+            AddressFormat::Iso20022,
+            ISO20022Profile::CbprPlus,
+            &postal_reference(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            violations,
+            vec!["PstlAdr/TwnNm: element is mandatory in CBPR+: ''".to_string()]
+        );
+    }
+
+    #[test]
+    fn validate_returns_error_for_invalid_source_data() {
+        AddressHandler::validate(
+            "invalid french address format",
+            AddressFormat::French,
+            ISO20022Profile::default(),
+            &postal_reference(),
+        )
+        .expect_err("Should return an error for invalid source data");
+    }
+
+    const PAIN_001: &str = include_str!("../../../assets/examples/pain001_01.xml");
+
+    #[test]
+    fn extract_exports_message_addresses_as_french_json_with_their_xpath() {
+        let output = AddressHandler::extract(
+            PAIN_001,
+            AddressFormat::French,
+            &ConversionOptions::default(),
+        )
+        .unwrap();
+
+        let extracted: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+        assert_eq!(extracted.len(), 3);
+        assert_eq!(
+            extracted[1]["xpath"],
+            "/Document/CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf[1]/Cdtr/PstlAdr"
+        );
+        assert_eq!(extracted[1]["party"], "Cdtr");
+        assert_eq!(extracted[1]["address"]["name"], "Monsieur Jean DELHOURME");
+        assert_eq!(extracted[2]["address"]["postal_info"], "69001 LYON");
+    }
+
+    #[test]
+    fn extract_exports_message_addresses_as_iso20022_json_with_their_name() {
+        let output = AddressHandler::extract(
+            PAIN_001,
+            AddressFormat::Iso20022,
+            &ConversionOptions::default(),
+        )
+        .unwrap();
+
+        let extracted: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+        assert_eq!(extracted[0]["party"], "Dbtr");
+        assert_eq!(extracted[0]["address"]["Nm"], "ACME SARL");
+        assert_eq!(extracted[0]["address"]["PstlAdr"]["TwnNm"], "LIBOURNE");
+    }
+
+    #[test]
+    fn extract_returns_error_with_xpath_in_strict_mode_when_name_is_missing() {
+        let options = ConversionOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let xml = PAIN_001.replace("<Nm>Madame Marie MARTIN</Nm>", "");
+
+        let error = AddressHandler::extract(&xml, AddressFormat::French, &options).unwrap_err();
+
+        assert!(error.to_string().contains("CdtTrfTxInf[2]/Cdtr/PstlAdr"));
+    }
+
+    #[test]
+    fn rewrite_structures_unstructured_message_addresses() {
+        let rewritten = AddressHandler::rewrite(
+            PAIN_001,
+            ISO20022Profile::CbprPlus,
+            None,
+            &ConversionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(rewritten.refused(), 0);
+        assert!(!rewritten.xml.contains("<AdrLine>"));
+        assert!(
+            rewritten
+                .xml
+                .contains("<StrtNm>PLACE DU MARCHE</StrtNm>\n            <BldgNb>1</BldgNb>")
+        );
+        assert!(rewritten.changes[2].to_string().starts_with(
+            "/Document/CstmrCdtTrfInitn/PmtInf/CdtTrfTxInf[2]/Cdtr/PstlAdr (Cdtr): rewritten"
+        ));
+    }
+
+    #[test]
+    fn import_message_adds_every_address_to_the_repository() {
+        let repository = Arc::new(InMemoryAddressRepository::default());
+        let service = AddressService::new(repository.clone());
+        let handler = AddressHandler::new(service);
+
+        let output = handler
+            .import_message(PAIN_001, &ConversionOptions::default())
+            .unwrap();
+
+        let addresses = repository.list().unwrap();
+        assert_eq!(addresses.len(), 3);
+        assert!(output.contains("/Document/CstmrCdtTrfInitn/PmtInf/Dbtr/PstlAdr (Dbtr)"));
+        assert!(
+            addresses
+                .iter()
+                .any(|(_, address)| address.town_name == "LYON"
+                    && address.street_name.contains("MARCHE"))
+        );
+    }
+
+    #[test]
+    fn import_message_imports_nothing_when_an_address_cannot_be_converted() {
+        let repository = Arc::new(InMemoryAddressRepository::default());
+        let service = AddressService::new(repository.clone());
+        let handler = AddressHandler::new(service);
+        let xml = PAIN_001.replace(
+            "<AdrLine>69001 LYON</AdrLine>\n            <Ctry>FR</Ctry>",
+            "<AdrLine>69001 LYON</AdrLine>\n            <Ctry>XX</Ctry>",
+        );
+
+        let error = handler
+            .import_message(&xml, &ConversionOptions::default())
+            .unwrap_err();
+
+        assert!(error.to_string().contains("CdtTrfTxInf[2]"));
+        assert!(repository.list().unwrap().is_empty());
+    }
+
+    const CAMT_053: &str = include_str!("../../../assets/examples/camt053_01.xml");
+
+    #[test]
+    fn import_statement_adds_related_parties_once_with_statement_reference() {
+        let repository = Arc::new(InMemoryAddressRepository::default());
+        let service = AddressService::new(repository.clone());
+        let handler = AddressHandler::new(service);
+
+        let output = handler
+            .import_statement(CAMT_053, &ConversionOptions::default())
+            .unwrap();
+
+        let addresses = repository.list().unwrap();
+        assert_eq!(addresses.len(), 2);
+        assert!(
+            addresses
+                .iter()
+                .all(|(_, address)| address.source_references == vec!["STMT-2025-03-14"])
+        );
+        assert!(
+            addresses
+                .iter()
+                .all(|(_, address)| address.name.as_deref() != Some("ACME SARL"))
+        );
+        assert!(output.contains(
+            "/Ntry[3]/NtryDtls/TxDtls/RltdPties/Dbtr/Pty/PstlAdr (Dbtr): address already stored"
+        ));
+    }
+
+    #[test]
+    fn import_statement_adds_statement_reference_to_stored_address() {
+        let repository = Arc::new(InMemoryAddressRepository::default());
+        let service = AddressService::new(repository.clone());
+        let handler = AddressHandler::new(service);
+        let next_statement = CAMT_053.replace("STMT-2025-03-14", "STMT-2025-03-15");
+
+        handler
+            .import_statement(CAMT_053, &ConversionOptions::default())
+            .unwrap();
+        handler
+            .import_statement(&next_statement, &ConversionOptions::default())
+            .unwrap();
+
+        let addresses = repository.list().unwrap();
+        assert_eq!(addresses.len(), 2);
+        assert_eq!(
+            addresses[0].1.source_references,
+            vec!["STMT-2025-03-14", "STMT-2025-03-15"]
+        );
+    }
+
+    #[test]
+    fn import_statement_returns_error_for_payment_message() {
+        let repository = Arc::new(InMemoryAddressRepository::default());
+        let handler = AddressHandler::new(AddressService::new(repository));
+
+        let error = handler
+            .import_statement(PAIN_001, &ConversionOptions::default())
+            .unwrap_err();
+
+        assert!(error.to_string().contains("not a camt.053"));
+    }
+
+    #[test]
+    fn communes_lists_communes_of_postal_code() {
+        let output = AddressHandler::communes("33380", &postal_reference()).unwrap();
+
+        assert_eq!(
+            output,
+            "Gironde (FR-33), Nouvelle-Aquitaine\n33380 BIGANOS (BIGANOS, INSEE 33051)\n33380 MIOS (MIOS, INSEE 33284)\n33380 MARCHEPRIME (MARCHEPRIME, INSEE 33555)"
+        );
+    }
+
+    #[test]
+    fn communes_returns_error_for_unknown_postal_code() {
+        AddressHandler::communes("00000", &postal_reference())
+            .expect_err("Should return an error for an unknown postal code");
+    }
+
+    #[test]
+    fn read_input_returns_inline_data() {
+        let input = read_input(Some("{}".to_string())).unwrap();
+
+        assert_eq!(input, "{}");
+    }
+
+    #[test]
+    fn read_input_reads_file_when_data_starts_with_at_sign() {
+        let dir = std::env::temp_dir().join("handlers_read_input");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("address.json");
+        fs::write(&path, "{\"name\": \"Alice\"}").unwrap();
+
+        let input = read_input(Some(format!("@{}", path.display()))).unwrap();
+
+        assert_eq!(input, "{\"name\": \"Alice\"}");
+    }
+
+    #[test]
+    fn parse_iso20022_accepts_xml_and_json() {
+        let iso = ISO20022Address::from(Address::dummy());
+
+        let from_xml = parse_iso20022(&iso.to_xml()).unwrap();
+        let from_json = parse_iso20022(&serde_json::to_string(&iso).unwrap()).unwrap();
+
+        assert_eq!(from_xml.postal_address, iso);
+        assert_eq!(from_json.postal_address, iso);
+    }
+
+    #[test]
+    fn parse_iso20022_accepts_party_xml_and_json() {
+        let party = ISO20022Party {
+            name: Some("John Doe".to_string()),
+            postal_address: ISO20022Address::from(Address::dummy()),
+            ..Default::default()
+        };
+
+        let from_xml = parse_iso20022(&party.to_xml()).unwrap();
+        let from_json = parse_iso20022(&serde_json::to_string(&party).unwrap()).unwrap();
+
+        assert_eq!(from_xml, party);
+        assert_eq!(from_json, party);
+    }
+
+    #[test]
+    fn get_existing_address_with_normalized_french_format() {
+        let repository = Arc::new(InMemoryAddressRepository::default());
+        let address = Address {
+            town_name: "Saint-Émilion".to_string(),
+            ..Address::dummy()
+        };
+        let id = repository.save(&address).unwrap();
+        let service = AddressService::new(repository);
+        let handler = AddressHandler::new(service);
+        let options = ConversionOptions {
+            normalize: true,
+            ..Default::default()
+        };
+
+        let output = handler
+            .get(id, Some(AddressFormat::French), &options)
+            .unwrap();
+
+        assert!(output.contains("12345 SAINT-EMILION"));
+    }
 }
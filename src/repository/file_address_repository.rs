@@ -185,6 +185,46 @@ mod tests {
         assert_eq!(loaded_address.unwrap(), address);
     }
 
+    #[test]
+    fn new_repository_should_load_file_written_before_iso_elements_were_added() {
+        let target_directory = create_temp_dir("new_legacy_file");
+        let file_path = target_directory.join("addresses.json");
+        let id = Uuid::new_v4();
+        let legacy_content = format!(
+            r#"{{
+                "addresses": {{
+                    "{id}": {{
+                        "name": "Monsieur Jean DURAND",
+                        "floor": null,
+                        "post_box": null,
+                        "room": null,
+                        "street_name": "25D RUE DES FLEURS",
+                        "town_name": "LIBOURNE",
+                        "town_location_name": null,
+                        "post_code": "33500",
+                        "country": "FR"
+                    }}
+                }}
+            }}"#
+        );
+        fs::write(&file_path, legacy_content).unwrap();
+
+        let repository = FileAddressRepository::new(&file_path).unwrap();
+
+        let loaded_address = repository.get(id).unwrap().unwrap();
+        assert_eq!(
+            loaded_address,
+            Address {
+                name: Some("Monsieur Jean DURAND".to_string()),
+                street_name: "25D RUE DES FLEURS".to_string(),
+                town_name: "LIBOURNE".to_string(),
+                post_code: "33500".to_string(),
+                country: "FR".to_string(),
+                ..Default::default()
+            }
+        );
+    }
+
     #[test]
     fn list_should_return_empty_collection_when_no_addresses() {
         let target_directory = create_temp_dir("list_empty");
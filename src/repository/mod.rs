@@ -1,4 +1,5 @@
 mod file_address_repository;
+#[cfg(test)]
 mod in_memory_address_repository;
 
 pub use file_address_repository::*;
@@ -1,25 +1,596 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+
 use crate::{
     AnyhowResult,
     domain::{
-        Address, {FrenchAddress, ISO20022Address},
+        Address, ConversionReport, Converted, FrenchPostalReference, ISO20022AddressMode,
+        ISO20022Party, ISO20022Version, PostalCodeCheck, {FrenchAddress, ISO20022Address},
     },
 };
 
+/// ISO code of the country whose communes are in the La Poste reference
+const FRANCE: &str = "FR";
+
+/// Options applied to every conversion
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConversionOptions {
+    /// Fail when a value would be dropped or synthesized instead of reporting it
+    pub strict: bool,
+
+    /// Rewrite French addresses into their NF Z10-011 postal-compliant form
+    pub normalize: bool,
+
+    /// Leave line 7 of French addresses empty when they are delivered within France
+    pub omit_domestic_country: bool,
+
+    /// Fill the missing ISO 20022 `<CtrySubDvsn>` of French addresses with the department of their postal code
+    pub derive_country_sub_division: bool,
+
+    /// La Poste reference used to report French towns that do not belong to their postal code
+    pub postal_reference: Option<Arc<FrenchPostalReference>>,
+
+    /// Rendering of ISO 20022 addresses: structured, hybrid or unstructured
+    pub iso_mode: ISO20022AddressMode,
+
+    /// Version of the ISO 20022 postal address type the output must conform to
+    pub iso_version: ISO20022Version,
+}
+
 /// Convert addresses between different formats
 pub struct AddressConverter;
 
 impl AddressConverter {
     /// Convert a [FrenchAddress] to an [ISO20022Address]
-    pub fn french_to_iso(address: FrenchAddress) -> AnyhowResult<ISO20022Address> {
-        let internal_address: Address = address.try_into()?;
+    pub fn french_to_iso(
+        address: FrenchAddress,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Converted<ISO20022Address>> {
+        let mut internal = address.into_internal()?;
+        internal
+            .report
+            .merge(verify_commune(&internal.address, options));
+        let iso = render_iso(internal.address, options);
+
+        let mut report = internal.report;
+        report.merge(iso.report);
+
+        check(Converted::new(iso.address, report), options)
+    }
+
+    /// Convert a [FrenchAddress] to an [ISO20022Party], line 1 being kept as the party name
+    pub fn french_to_party(
+        address: FrenchAddress,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Converted<ISO20022Party>> {
+        let mut internal = address.into_internal()?;
+        internal
+            .report
+            .merge(verify_commune(&internal.address, options));
+        let party = render_party(internal.address, options);
 
-        Ok(internal_address.into())
+        let mut report = internal.report;
+        report.merge(party.report);
+
+        check(Converted::new(party.address, report), options)
     }
 
     /// Convert a [ISO20022Address] to a [FrenchAddress]
-    pub fn iso_to_french(address: ISO20022Address) -> AnyhowResult<FrenchAddress> {
-        let internal_address: Address = address.try_into()?;
+    pub fn iso_to_french(
+        address: ISO20022Address,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Converted<FrenchAddress>> {
+        iso_internal_to_french(address.into_internal()?, options)
+    }
+
+    /// Convert an [ISO20022Party] to a [FrenchAddress], the party name giving line 1
+    pub fn party_to_french(
+        party: ISO20022Party,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Converted<FrenchAddress>> {
+        iso_internal_to_french(party.into_internal()?, options)
+    }
+
+    /// Convert a [FrenchAddress] to an internal [Address]
+    pub fn french_to_internal(
+        address: FrenchAddress,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Converted<Address>> {
+        let mut internal = address.into_internal()?;
+        internal
+            .report
+            .merge(verify_commune(&internal.address, options));
+
+        check(internal, options)
+    }
+
+    /// Convert an [ISO20022Party] to an internal [Address], the party name included
+    pub fn party_to_internal(
+        party: ISO20022Party,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Converted<Address>> {
+        check(party.into_internal()?, options)
+    }
+
+    /// Convert an [ISO20022Address] to an internal [Address]
+    pub fn iso_to_internal(
+        address: ISO20022Address,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Converted<Address>> {
+        check(address.into_internal()?, options)
+    }
+
+    /// Convert an internal [Address] to a [FrenchAddress]
+    pub fn internal_to_french(
+        address: Address,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Converted<FrenchAddress>> {
+        let commune_report = verify_commune(&address, options);
+        let mut french = FrenchAddress::from_internal(address)?;
+        french.report.merge(commune_report);
+
+        check(
+            Converted::new(finish_french(french.address, options), french.report),
+            options,
+        )
+    }
+
+    /// Convert an internal [Address] to an [ISO20022Address]
+    pub fn internal_to_iso(
+        address: Address,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Converted<ISO20022Address>> {
+        check(render_iso(address, options), options)
+    }
+
+    /// Convert an internal [Address] to an [ISO20022Party], the name being kept in `<Nm>`
+    pub fn internal_to_party(
+        address: Address,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Converted<ISO20022Party>> {
+        check(render_party(address, options), options)
+    }
+
+    /// Convert an [ISO20022Address] to another version of the postal address type
+    pub fn iso_to_iso(
+        address: ISO20022Address,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<Converted<ISO20022Address>> {
+        check(address.to_version(options.iso_version), options)
+    }
+}
+
+/// Apply the opt-in derivation of the country sub-division from the French postal code, except in unstructured mode
+/// where it would take an `<AdrLine>` to repeat the postal code
+fn derive(address: Address, options: &ConversionOptions) -> Address {
+    if options.derive_country_sub_division && options.iso_mode != ISO20022AddressMode::Unstructured
+    {
+        address.with_derived_country_sub_division()
+    } else {
+        address
+    }
+}
+
+/// Render an internal address in the ISO 20022 mode and version of the options
+fn render_iso(address: Address, options: &ConversionOptions) -> Converted<ISO20022Address> {
+    let mut rendered = ISO20022Address::render(derive(address, options), options.iso_mode);
+    let versioned = rendered.address.to_version(options.iso_version);
+    rendered.report.merge(versioned.report);
+
+    Converted::new(versioned.address, rendered.report)
+}
+
+/// Render an internal address as an ISO 20022 party in the mode and version of the options
+fn render_party(address: Address, options: &ConversionOptions) -> Converted<ISO20022Party> {
+    let mut rendered = ISO20022Party::render(derive(address, options), options.iso_mode);
+    let versioned = rendered
+        .address
+        .postal_address
+        .to_version(options.iso_version);
+    rendered.address.postal_address = versioned.address;
+    rendered.report.merge(versioned.report);
+
+    rendered
+}
+
+/// Convert an internal address read from ISO 20022 to the French format, keeping the alterations already reported
+fn iso_internal_to_french(
+    internal: Converted<Address>,
+    options: &ConversionOptions,
+) -> AnyhowResult<Converted<FrenchAddress>> {
+    let commune_report = verify_commune(&internal.address, options);
+    let french = FrenchAddress::from_internal(internal.address)?;
+
+    let mut report = internal.report;
+    report.merge(french.report);
+    report.merge(commune_report);
+
+    check(
+        Converted::new(finish_french(french.address, options), report),
+        options,
+    )
+}
+
+/// Apply the opt-in NF Z10-011 normalization and domestic country omission to a converted French address
+fn finish_french(address: FrenchAddress, options: &ConversionOptions) -> FrenchAddress {
+    let address = if options.normalize {
+        address.normalize()
+    } else {
+        address
+    };
+
+    if options.omit_domestic_country {
+        address.without_domestic_country()
+    } else {
+        address
+    }
+}
+
+/// Report a French town that is not one of the communes of its postal code in the La Poste reference.
+///
+/// CEDEX postal codes and the codes missing from the reference (such as the ones outside the embedded excerpt) are not
+/// reported, the `validate` command checks them.
+pub(super) fn verify_commune(address: &Address, options: &ConversionOptions) -> ConversionReport {
+    let mut report = ConversionReport::default();
+
+    let Some(reference) = &options.postal_reference else {
+        return report;
+    };
+    if address.country != FRANCE || address.cedex.is_some() {
+        return report;
+    }
+
+    match reference.check(&address.post_code, &address.town_name) {
+        PostalCodeCheck::Misspelled { spelling } => report.unverified(
+            "town_name",
+            format!(
+                "'{}' does not match the La Poste spelling '{spelling}'",
+                address.town_name
+            ),
+        ),
+        PostalCodeCheck::Mismatch { communes } => report.unverified(
+            "town_name",
+            format!(
+                "'{}' is not served by postal code {}, expected {}",
+                address.town_name,
+                address.post_code,
+                communes.join(", ")
+            ),
+        ),
+        PostalCodeCheck::Match | PostalCodeCheck::UnknownPostCode => {}
+    }
+
+    report
+}
+
+/// Reject a lossy conversion in strict mode
+fn check<T>(converted: Converted<T>, options: &ConversionOptions) -> AnyhowResult<Converted<T>> {
+    if options.strict && !converted.report.is_lossless() {
+        return Err(lossy_conversion_error(&converted.report));
+    }
+
+    Ok(converted)
+}
+
+fn lossy_conversion_error(report: &ConversionReport) -> anyhow::Error {
+    anyhow!("Strict conversion failed, the address would be altered:\n{report}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strict() -> ConversionOptions {
+        ConversionOptions {
+            strict: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn french_to_iso_merges_reports_of_both_steps() {
+        let french = FrenchAddress::try_from(Address::dummy()).unwrap();
+
+        let converted =
+            AddressConverter::french_to_iso(french, &ConversionOptions::default()).unwrap();
+
+        assert_eq!(converted.report.warnings.len(), 1);
+        assert_eq!(converted.report.warnings[0].field, "name");
+    }
+
+    #[test]
+    fn french_to_iso_fails_in_strict_mode_when_name_is_dropped() {
+        let french = FrenchAddress::try_from(Address::dummy()).unwrap();
+
+        AddressConverter::french_to_iso(french, &strict())
+            .expect_err("Should fail in strict mode when the name is dropped");
+    }
+
+    #[test]
+    fn iso_to_french_synthesizes_name_unless_strict() {
+        let iso = ISO20022Address::from(Address::dummy());
+
+        let converted =
+            AddressConverter::iso_to_french(iso.clone(), &ConversionOptions::default()).unwrap();
+        assert!(!converted.report.is_lossless());
+
+        AddressConverter::iso_to_french(iso, &strict())
+            .expect_err("Should fail in strict mode when line 1 is synthesized");
+    }
+
+    #[test]
+    fn internal_to_french_succeeds_in_strict_mode_when_lossless() {
+        let converted = AddressConverter::internal_to_french(Address::dummy(), &strict()).unwrap();
+
+        assert!(converted.report.is_empty());
+    }
+
+    #[test]
+    fn internal_to_french_normalizes_when_requested() {
+        let address = Address {
+            street_name: "rue de l’Église".to_string(),
+            ..Address::dummy()
+        };
+        let options = ConversionOptions {
+            normalize: true,
+            ..Default::default()
+        };
+
+        let converted = AddressConverter::internal_to_french(address, &options).unwrap();
+
+        assert_eq!(converted.address.street, "RUE DE L EGLISE".to_string());
+        assert_eq!(converted.address.country, "FRANCE".to_string());
+    }
+
+    fn with_postal_reference() -> ConversionOptions {
+        ConversionOptions {
+            postal_reference: Some(Arc::new(FrenchPostalReference::embedded().unwrap())),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn french_to_iso_reports_town_not_served_by_postal_code() {
+        let french = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            None,
+            "25D RUE DES FLEURS".to_string(),
+            None,
+            "33500 PARIS".to_string(),
+            "France".to_string(),
+        );
+
+        let converted = AddressConverter::french_to_iso(french, &with_postal_reference()).unwrap();
+
+        let warning = &converted.report.warnings[0];
+        assert_eq!(warning.kind, crate::AlterationKind::Unverified);
+        assert_eq!(warning.field, "town_name");
+        assert!(warning.detail.contains("LIBOURNE"));
+    }
+
+    #[test]
+    fn french_to_party_round_trips_line_1_in_strict_mode() {
+        let french = FrenchAddress::new(
+            "Monsieur Jean DURAND".to_string(),
+            None,
+            None,
+            "25 RUE DES FLEURS".to_string(),
+            None,
+            "33500 LIBOURNE".to_string(),
+            "FRANCE".to_string(),
+        );
+
+        let party = AddressConverter::french_to_party(french.clone(), &strict()).unwrap();
+        let back = AddressConverter::party_to_french(party.address, &strict()).unwrap();
+
+        assert_eq!(back.address, french);
+        assert!(back.report.is_lossless());
+    }
+
+    #[test]
+    fn french_business_to_party_round_trips_line_2_through_department_in_strict_mode() {
+        let french = FrenchAddress::new(
+            "ACME SARL".to_string(),
+            Some("Service Achats".to_string()),
+            Some("ZI de la Ballastrière".to_string()),
+            "25 RUE DES FLEURS".to_string(),
+            None,
+            "33500 LIBOURNE".to_string(),
+            "FRANCE".to_string(),
+        );
+
+        let party = AddressConverter::french_to_party(french.clone(), &strict()).unwrap();
+        assert_eq!(
+            party.address.postal_address.department,
+            Some("Service Achats".to_string())
+        );
+        assert_eq!(
+            party.address.postal_address.building_name,
+            Some("ZI de la Ballastrière".to_string())
+        );
+
+        let back = AddressConverter::party_to_french(party.address, &strict()).unwrap();
+        assert_eq!(back.address, french);
+    }
+
+    #[test]
+    fn french_to_party_maps_care_of_to_care_of_element_or_address_line() {
+        let french = FrenchAddress::new(
+            "Monsieur Jean DELHOURME".to_string(),
+            Some("c/o Mireille COPEAU Appartement 2".to_string()),
+            None,
+            "25 RUE DE L’EGLISE".to_string(),
+            None,
+            "33380 MIOS".to_string(),
+            "France".to_string(),
+        );
+        let version = |iso_version| ConversionOptions {
+            iso_version,
+            ..Default::default()
+        };
+
+        let party = AddressConverter::french_to_party(
+            french.clone(),
+            &version(ISO20022Version::PostalAddress27),
+        )
+        .unwrap()
+        .address;
+        assert_eq!(
+            party.postal_address.care_of,
+            Some("Mireille COPEAU".to_string())
+        );
+        assert_eq!(
+            party.postal_address.unit_number,
+            Some("Appartement 2".to_string())
+        );
+
+        let party =
+            AddressConverter::french_to_party(french, &version(ISO20022Version::PostalAddress24))
+                .unwrap()
+                .address;
+        assert_eq!(
+            party.postal_address.address_lines,
+            vec!["c/o Mireille COPEAU".to_string()]
+        );
+        assert_eq!(party.postal_address.room, Some("Appartement 2".to_string()));
+
+        let back = AddressConverter::party_to_french(party, &ConversionOptions::default()).unwrap();
+        assert_eq!(
+            back.address.recipient_info,
+            Some("Chez Mireille COPEAU Appartement 2".to_string())
+        );
+    }
+
+    #[test]
+    fn internal_to_french_suggests_spelling_of_misspelled_town() {
+        let address = Address {
+            post_code: "33500".to_string(),
+            town_name: "LIBOUNRE".to_string(),
+            ..Address::dummy()
+        };
+
+        let converted =
+            AddressConverter::internal_to_french(address, &with_postal_reference()).unwrap();
+
+        assert_eq!(
+            converted.report.warnings[0].detail,
+            "'LIBOUNRE' does not match the La Poste spelling 'LIBOURNE'".to_string()
+        );
+    }
+
+    #[test]
+    fn internal_to_french_does_not_verify_cedex_or_unknown_postal_codes() {
+        let cedex = Address {
+            post_code: "33506".to_string(),
+            town_name: "LIBOURNE".to_string(),
+            cedex: Some("CEDEX".to_string()),
+            ..Address::dummy()
+        };
+
+        let converted =
+            AddressConverter::internal_to_french(cedex, &with_postal_reference()).unwrap();
+
+        assert!(converted.report.is_empty());
+    }
+
+    #[test]
+    fn internal_to_iso_renders_address_in_requested_mode() {
+        let options = ConversionOptions {
+            iso_mode: ISO20022AddressMode::Hybrid,
+            ..Default::default()
+        };
+
+        let converted = AddressConverter::internal_to_iso(Address::dummy(), &options).unwrap();
+
+        assert_eq!(
+            converted.address.address_lines,
+            vec!["123 Main St".to_string()]
+        );
+        assert_eq!(converted.address.street_name, "".to_string());
+    }
+
+    #[test]
+    fn internal_to_iso_folds_elements_missing_from_requested_version() {
+        let options = ConversionOptions {
+            iso_version: ISO20022Version::PostalAddress6,
+            ..Default::default()
+        };
+        let address = Address {
+            name: None,
+            floor: Some("Étage 3".to_string()),
+            ..Address::dummy()
+        };
+
+        let converted = AddressConverter::internal_to_iso(address, &options).unwrap();
+
+        assert_eq!(converted.address.floor, None);
+        assert_eq!(converted.address.address_lines, vec!["Étage 3".to_string()]);
+        assert!(converted.report.is_lossless());
+    }
+
+    #[test]
+    fn internal_to_french_omits_domestic_country_when_requested() {
+        let options = ConversionOptions {
+            omit_domestic_country: true,
+            ..Default::default()
+        };
+
+        let domestic = AddressConverter::internal_to_french(Address::dummy(), &options).unwrap();
+        assert_eq!(domestic.address.country, "".to_string());
+        assert!(domestic.report.is_lossless());
+
+        let address = Address {
+            country: "DE".to_string(),
+            ..Address::dummy()
+        };
+        let international = AddressConverter::internal_to_french(address, &options).unwrap();
+        assert_eq!(international.address.country, "ALLEMAGNE".to_string());
+    }
+
+    #[test]
+    fn internal_to_iso_derives_country_sub_division_only_when_requested() {
+        let address = Address {
+            post_code: "33380".to_string(),
+            ..Address::dummy()
+        };
+        let options = ConversionOptions {
+            derive_country_sub_division: true,
+            ..Default::default()
+        };
+
+        let plain =
+            AddressConverter::internal_to_iso(address.clone(), &Default::default()).unwrap();
+        let derived = AddressConverter::internal_to_iso(address, &options).unwrap();
+
+        assert_eq!(plain.address.country_sub_division, None);
+        assert_eq!(
+            derived.address.country_sub_division,
+            Some("FR-33".to_string())
+        );
+    }
+
+    #[test]
+    fn internal_to_iso_does_not_derive_country_sub_division_in_unstructured_mode() {
+        let address = Address {
+            post_code: "33380".to_string(),
+            ..Address::dummy()
+        };
+        let options = ConversionOptions {
+            derive_country_sub_division: true,
+            iso_mode: ISO20022AddressMode::Unstructured,
+            ..Default::default()
+        };
+
+        let converted = AddressConverter::internal_to_iso(address, &options).unwrap();
 
-        internal_address.try_into()
+        assert!(
+            converted
+                .address
+                .address_lines
+                .iter()
+                .all(|line| !line.contains("FR-33"))
+        );
     }
 }
@@ -4,6 +4,16 @@ use uuid::Uuid;
 
 use crate::{AnyhowResult, domain::Address, use_cases::repository::AddressRepository};
 
+/// Outcome of the import of an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportOutcome {
+    /// The address was added with this id
+    Added(Uuid),
+
+    /// The address duplicates the one with this id, which was given its source references
+    Merged(Uuid),
+}
+
 /// Address service
 pub struct AddressService {
     repository: Arc<dyn AddressRepository>,
@@ -30,6 +40,32 @@ impl AddressService {
         self.repository.save(address)
     }
 
+    /// Add an address unless it duplicates a stored one, which is then given the source references of the address
+    pub fn import(&self, address: &Address) -> AnyhowResult<ImportOutcome> {
+        let duplicate = self
+            .repository
+            .list()?
+            .into_iter()
+            .find(|(_, stored)| stored.is_duplicate_of(address));
+
+        let Some((id, mut stored)) = duplicate else {
+            return Ok(ImportOutcome::Added(self.repository.save(address)?));
+        };
+
+        let new_references: Vec<String> = address
+            .source_references
+            .iter()
+            .filter(|reference| !stored.source_references.contains(reference))
+            .cloned()
+            .collect();
+        if !new_references.is_empty() {
+            stored.source_references.extend(new_references);
+            self.repository.update(id, &stored)?;
+        }
+
+        Ok(ImportOutcome::Merged(id))
+    }
+
     /// Update an address
     pub fn update(&self, id: Uuid, address: &Address) -> AnyhowResult<()> {
         self.repository.update(id, address)
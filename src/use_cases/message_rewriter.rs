@@ -0,0 +1,446 @@
+use std::fmt::{self, Display, Formatter};
+
+use anyhow::anyhow;
+
+use crate::{
+    AnyhowResult,
+    domain::{
+        AlterationKind, ConversionReport, ElementConfidence, ISO20022Address, ISO20022AddressMode,
+        ISO20022Message, ISO20022MessageAddress, ISO20022Profile, ISO20022Version,
+        REVIEW_THRESHOLD, StructuredISO20022Address,
+    },
+};
+
+use super::{AddressConverter, ConversionOptions, address_converter::verify_commune};
+
+/// Outcome of the rewrite of a postal address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressChangeStatus {
+    /// The address is fully structured and is left as is
+    AlreadyStructured,
+
+    /// The address was replaced by its structured form
+    Rewritten,
+
+    /// The address could not be structured with confidence and is left as is
+    Refused,
+}
+
+impl Display for AddressChangeStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressChangeStatus::AlreadyStructured => write!(f, "already structured"),
+            AddressChangeStatus::Rewritten => write!(f, "rewritten"),
+            AddressChangeStatus::Refused => write!(f, "refused"),
+        }
+    }
+}
+
+/// Change log entry of a postal address of a rewritten message
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressChange {
+    /// XPath of the `<PstlAdr>` in the message
+    pub xpath: String,
+
+    /// Tag of the party or agent element holding the address
+    pub party: String,
+
+    /// Outcome of the rewrite
+    pub status: AddressChangeStatus,
+
+    /// Elements given to a rewritten address, or reasons of a refusal
+    pub details: Vec<String>,
+}
+
+impl Display for AddressChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.xpath, self.party, self.status)?;
+        for detail in &self.details {
+            write!(f, "\n  - {detail}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Message whose postal addresses were rewritten, with the change log of every address
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewrittenMessage {
+    /// XML of the message
+    pub xml: String,
+
+    /// Change log, in document order
+    pub changes: Vec<AddressChange>,
+}
+
+impl RewrittenMessage {
+    /// Number of addresses left unstructured because their rewrite was refused
+    pub fn refused(&self) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.status == AddressChangeStatus::Refused)
+            .count()
+    }
+}
+
+/// Upgrade the unstructured and partially structured postal addresses of payment messages
+pub struct MessageRewriter;
+
+impl MessageRewriter {
+    /// Rewrite every `<PstlAdr>` of a pain.001 or pacs.008 message that is not fully structured into its structured
+    /// form: the ones with `<AdrLine>`, a building number inside `<StrtNm>` or a postal code inside `<TwnNm>`.
+    ///
+    /// The address is structured, then the address is converted through the internal model into a structured
+    /// address of the version of the message, read from the namespace of its `<Document>`, or else of the requested
+    /// version, PostalAddress24 by default. A requested version contradicting the namespace fails. The rewrite is refused, and the address left as is, when an element needs
+    /// a human review, a value would be dropped, made up or not match the La Poste reference, or the new address is not
+    /// valid for the profile. The rest of the message is left unchanged.
+    pub fn rewrite(
+        xml: &str,
+        profile: ISO20022Profile,
+        iso_version: Option<ISO20022Version>,
+        options: &ConversionOptions,
+    ) -> AnyhowResult<RewrittenMessage> {
+        let message = ISO20022Message::from_xml(xml)?;
+        let iso_version = match (message.version, iso_version) {
+            (Some(message_version), Some(iso_version)) if message_version != iso_version => {
+                return Err(anyhow!(
+                    "The {} message uses {message_version}, not {iso_version}",
+                    message.message_type
+                ));
+            }
+            (message_version, iso_version) => message_version.or(iso_version).unwrap_or_default(),
+        };
+        let options = ConversionOptions {
+            strict: false,
+            iso_mode: ISO20022AddressMode::Structured,
+            iso_version,
+            ..options.clone()
+        };
+
+        let mut rewritten = Vec::new();
+        let mut changes = Vec::new();
+        for address in message.addresses {
+            let mut change = AddressChange {
+                xpath: address.xpath.clone(),
+                party: address.party.clone(),
+                status: AddressChangeStatus::AlreadyStructured,
+                details: Vec::new(),
+            };
+
+            if !address.postal_address.is_fully_structured() {
+                match rewrite_address(address, profile, &options) {
+                    Ok((address, details)) => {
+                        change.status = AddressChangeStatus::Rewritten;
+                        change.details = details;
+                        rewritten.push(address);
+                    }
+                    Err(reasons) => {
+                        change.status = AddressChangeStatus::Refused;
+                        change.details = reasons;
+                    }
+                }
+            }
+
+            changes.push(change);
+        }
+
+        Ok(RewrittenMessage {
+            xml: ISO20022Message::replace_postal_addresses(xml, &rewritten, options.iso_version),
+            changes,
+        })
+    }
+}
+
+/// Structure a postal address, returning it with the elements it was given, or the reasons of the refusal
+fn rewrite_address(
+    address: ISO20022MessageAddress,
+    profile: ISO20022Profile,
+    options: &ConversionOptions,
+) -> Result<(ISO20022MessageAddress, Vec<String>), Vec<String>> {
+    let original = address.postal_address.clone();
+    let structured = original.clone().structure();
+    if structured.needs_review {
+        return Err(review_reasons(&structured));
+    }
+
+    let party = ISO20022MessageAddress {
+        postal_address: structured.address.clone(),
+        ..address.clone()
+    }
+    .to_party();
+    let (postal_address, report) = AddressConverter::party_to_internal(party, options)
+        .and_then(|internal| {
+            let mut report = internal.report;
+            report.merge(verify_commune(&internal.address, options));
+            let party = AddressConverter::internal_to_party(internal.address, options)?;
+            report.merge(party.report);
+
+            Ok((party.address.postal_address, report))
+        })
+        .map_err(|error| vec![format!("{error:#}")])?;
+
+    let mut reasons = uncertain_alterations(&report);
+    reasons.extend(
+        postal_address
+            .validate(profile)
+            .iter()
+            .map(|violation| violation.to_string()),
+    );
+    if !reasons.is_empty() {
+        return Err(reasons);
+    }
+
+    let details = original
+        .address_lines
+        .iter()
+        .map(|line| format!("<AdrLine> '{line}' removed"))
+        .chain(added_elements(
+            &original,
+            &postal_address,
+            &structured.confidences,
+        ))
+        .chain(report.warnings.iter().map(|warning| warning.to_string()))
+        .collect();
+
+    Ok((
+        ISO20022MessageAddress {
+            postal_address,
+            ..address
+        },
+        details,
+    ))
+}
+
+/// Elements of the rewritten address that were not in the original one, with their structuring confidence
+fn added_elements(
+    original: &ISO20022Address,
+    rewritten: &ISO20022Address,
+    confidences: &[ElementConfidence],
+) -> Vec<String> {
+    rewritten
+        .xml_elements()
+        .into_iter()
+        .zip(original.xml_elements())
+        .filter_map(|((element, value), (_, original_value))| {
+            let value =
+                value.filter(|value| !value.is_empty() && Some(*value) != original_value)?;
+            let confidence = confidences
+                .iter()
+                .find(|confidence| confidence.element == element && confidence.value == value);
+
+            Some(match confidence {
+                Some(confidence) => format!(
+                    "<{element}> '{value}' added (confidence {:.2})",
+                    confidence.confidence
+                ),
+                None => format!("<{element}> '{value}' added"),
+            })
+        })
+        .collect()
+}
+
+/// Reasons why a structured address needs a human review
+fn review_reasons(structured: &StructuredISO20022Address) -> Vec<String> {
+    let address = &structured.address;
+    let mut reasons: Vec<String> = structured
+        .confidences
+        .iter()
+        .filter(|confidence| confidence.confidence < REVIEW_THRESHOLD)
+        .map(|confidence| {
+            format!(
+                "<{}> '{}' has a confidence of {:.2}, below {REVIEW_THRESHOLD}",
+                confidence.element, confidence.value, confidence.confidence
+            )
+        })
+        .collect();
+
+    if address.town_name.is_empty() {
+        reasons.push("no town found".to_string());
+    }
+    if address.country.is_empty() {
+        reasons.push("no country found".to_string());
+    }
+    reasons.extend(
+        address
+            .address_lines
+            .iter()
+            .map(|line| format!("<AdrLine> '{line}' matches no element")),
+    );
+
+    reasons
+}
+
+/// Alterations that make a rewrite uncertain: every one but the values moved to another element
+fn uncertain_alterations(report: &ConversionReport) -> Vec<String> {
+    report
+        .warnings
+        .iter()
+        .filter(|warning| warning.kind != AlterationKind::Moved)
+        .map(|warning| warning.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::FrenchPostalReference;
+
+    use super::*;
+
+    fn with_postal_reference() -> ConversionOptions {
+        ConversionOptions {
+            postal_reference: Some(Arc::new(FrenchPostalReference::embedded().unwrap())),
+            ..Default::default()
+        }
+    }
+
+    fn message(postal_addresses: &[&str]) -> String {
+        versioned_message("pain.001.001.09", postal_addresses)
+    }
+
+    fn versioned_message(identifier: &str, postal_addresses: &[&str]) -> String {
+        let transactions: String = postal_addresses
+            .iter()
+            .map(|postal_address| {
+                format!(
+                    "\n      <CdtTrfTxInf>\n        <Cdtr>\n          <Nm>Monsieur Jean DURAND</Nm>\n          <PstlAdr>{postal_address}</PstlAdr>\n        </Cdtr>\n      </CdtTrfTxInf>"
+                )
+            })
+            .collect();
+
+        format!(
+            "<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:{identifier}\">\n  <CstmrCdtTrfInitn>\n    <PmtInf>{transactions}\n    </PmtInf>\n  </CstmrCdtTrfInitn>\n</Document>"
+        )
+    }
+
+    #[test]
+    fn rewrite_structures_address_lines_and_logs_the_new_elements() {
+        let xml = message(&[
+            "<AdrLine>25 RUE DES FLEURS</AdrLine><AdrLine>33500 LIBOURNE</AdrLine><Ctry>FR</Ctry>",
+        ]);
+
+        let rewritten = MessageRewriter::rewrite(
+            &xml,
+            ISO20022Profile::CbprPlus,
+            None,
+            &with_postal_reference(),
+        )
+        .unwrap();
+
+        let change = &rewritten.changes[0];
+        assert_eq!(change.status, AddressChangeStatus::Rewritten);
+        assert!(
+            change
+                .details
+                .contains(&"<AdrLine> '25 RUE DES FLEURS' removed".to_string())
+        );
+        assert!(rewritten.xml.contains("<StrtNm>RUE DES FLEURS</StrtNm>"));
+        assert!(rewritten.xml.contains("<TwnNm>LIBOURNE</TwnNm>"));
+        assert!(!rewritten.xml.contains("<AdrLine>"));
+        assert!(rewritten.xml.contains("<Nm>Monsieur Jean DURAND</Nm>"));
+    }
+
+    #[test]
+    fn rewrite_structures_partially_structured_addresses() {
+        let xml = message(&[
+            "<StrtNm>25 RUE DES FLEURS</StrtNm><TwnNm>33500 LIBOURNE</TwnNm><Ctry>FR</Ctry>",
+        ]);
+
+        let rewritten = MessageRewriter::rewrite(
+            &xml,
+            ISO20022Profile::CbprPlus,
+            None,
+            &with_postal_reference(),
+        )
+        .unwrap();
+
+        let change = &rewritten.changes[0];
+        assert_eq!(change.status, AddressChangeStatus::Rewritten);
+        assert!(
+            change
+                .details
+                .contains(&"<BldgNb> '25' added (confidence 0.85)".to_string())
+        );
+        assert!(rewritten.xml.contains("<StrtNm>RUE DES FLEURS</StrtNm>"));
+        assert!(rewritten.xml.contains("<BldgNb>25</BldgNb>"));
+        assert!(rewritten.xml.contains("<PstCd>33500</PstCd>"));
+        assert!(rewritten.xml.contains("<TwnNm>LIBOURNE</TwnNm>"));
+    }
+
+    #[test]
+    fn rewrite_refuses_addresses_that_need_a_review_and_leaves_them_unchanged() {
+        let xml = message(&[
+            "<TwnNm>LIBOURNE</TwnNm><Ctry>FR</Ctry>",
+            "<AdrLine>QUELQUE PART</AdrLine><Ctry>FR</Ctry>",
+        ]);
+
+        let rewritten = MessageRewriter::rewrite(
+            &xml,
+            ISO20022Profile::Schema,
+            None,
+            &with_postal_reference(),
+        )
+        .unwrap();
+
+        assert_eq!(rewritten.xml, xml);
+        assert_eq!(
+            rewritten.changes[0].status,
+            AddressChangeStatus::AlreadyStructured
+        );
+        assert_eq!(rewritten.changes[1].status, AddressChangeStatus::Refused);
+        assert_eq!(rewritten.refused(), 1);
+    }
+
+    #[test]
+    fn rewrite_refuses_town_not_served_by_postal_code() {
+        let xml = message(&[
+            "<AdrLine>25 RUE DES FLEURS</AdrLine><AdrLine>33500 PARIS</AdrLine><Ctry>FR</Ctry>",
+        ]);
+
+        let rewritten = MessageRewriter::rewrite(
+            &xml,
+            ISO20022Profile::Schema,
+            None,
+            &with_postal_reference(),
+        )
+        .unwrap();
+
+        let change = &rewritten.changes[0];
+        assert_eq!(change.status, AddressChangeStatus::Refused);
+        assert!(change.details[0].contains("unverified town_name"));
+        assert_eq!(rewritten.xml, xml);
+    }
+
+    #[test]
+    fn rewrite_writes_postal_address6_into_postal_address6_message() {
+        let xml = versioned_message(
+            "pain.001.001.03",
+            &[
+                "<AdrLine>25 RUE DES FLEURS</AdrLine><AdrLine>BP 40122</AdrLine><AdrLine>33500 LIBOURNE</AdrLine><Ctry>FR</Ctry>",
+            ],
+        );
+
+        let rewritten = MessageRewriter::rewrite(
+            &xml,
+            ISO20022Profile::Schema,
+            None,
+            &with_postal_reference(),
+        )
+        .unwrap();
+
+        assert_eq!(rewritten.changes[0].status, AddressChangeStatus::Rewritten);
+        assert!(rewritten.xml.contains("<StrtNm>RUE DES FLEURS</StrtNm>"));
+        assert!(rewritten.xml.contains("<AdrLine>BP 40122</AdrLine>"));
+        assert!(!rewritten.xml.contains("<PstBx>"));
+
+        MessageRewriter::rewrite(
+            &xml,
+            ISO20022Profile::Schema,
+            Some(ISO20022Version::PostalAddress24),
+            &with_postal_reference(),
+        )
+        .expect_err("Should refuse a version contradicting the message namespace");
+    }
+}
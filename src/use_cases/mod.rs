@@ -1,7 +1,9 @@
 mod address_converter;
 mod address_service;
+mod message_rewriter;
 mod repository;
 
-pub use address_converter::AddressConverter;
-pub use address_service::AddressService;
+pub use address_converter::{AddressConverter, ConversionOptions};
+pub use address_service::{AddressService, ImportOutcome};
+pub use message_rewriter::*;
 pub use repository::*;
@@ -1,6 +1,8 @@
 use std::fs;
 
-use postal_address_converter::{Address, AddressService, FileAddressRepository};
+use postal_address_converter::{
+    Address, AddressService, AddressType, FileAddressRepository, ISO20022Address, ImportOutcome,
+};
 
 #[test]
 fn service_add_update_delete() {
@@ -20,10 +22,7 @@ fn service_add_update_delete() {
         town_name: "Paris".to_string(),
         post_code: "75000".to_string(),
         country: "FR".to_string(),
-        floor: None,
-        post_box: None,
-        room: None,
-        town_location_name: None,
+        ..Default::default()
     };
 
     let id = service.add(&address).unwrap();
@@ -50,3 +49,88 @@ fn service_add_update_delete() {
             .contains_key(&id.to_string())
     );
 }
+
+#[test]
+fn service_stores_iso20022_address_without_loss() {
+    let dir = std::env::temp_dir().join("service_iso20022_integration_test");
+    if dir.exists() {
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    fs::create_dir_all(&dir).unwrap();
+    let db_path = dir.join("addresses-database.json");
+
+    let iso_address = ISO20022Address {
+        address_type: Some(AddressType::Business),
+        care_of: None,
+        department: Some("Service Comptabilité".to_string()),
+        sub_department: Some("Fournisseurs".to_string()),
+        street_name: "RUE DES FLEURS".to_string(),
+        building_number: Some("22BIS".to_string()),
+        building_name: Some("Bâtiment Jonquille".to_string()),
+        floor: Some("3".to_string()),
+        unit_number: None,
+        post_box: Some("BP 40122".to_string()),
+        room: Some("Appartement 2".to_string()),
+        post_code: "33506".to_string(),
+        town_name: "LIBOURNE CEDEX".to_string(),
+        town_location_name: Some("CAUDOS".to_string()),
+        district_name: Some("Libournais".to_string()),
+        country_sub_division: Some("FR-33".to_string()),
+        country: "FR".to_string(),
+        address_lines: vec!["Lieu-dit Le Bourg".to_string()],
+    };
+
+    let service = AddressService::new(std::sync::Arc::new(
+        FileAddressRepository::new(&db_path).unwrap(),
+    ));
+    let address: Address = iso_address.clone().try_into().unwrap();
+    let id = service.add(&address).unwrap();
+
+    // Reload the database from disk to go through the JSON file format
+    let service = AddressService::new(std::sync::Arc::new(
+        FileAddressRepository::new(&db_path).unwrap(),
+    ));
+    let retrieved = service.get(id).unwrap().expect("Address must be found");
+
+    assert_eq!(ISO20022Address::from(retrieved), iso_address);
+}
+
+#[test]
+fn service_import_merges_duplicate_into_stored_address() {
+    let dir = std::env::temp_dir().join("service_import_integration_test");
+    if dir.exists() {
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    fs::create_dir_all(&dir).unwrap();
+    let db_path = dir.join("addresses-database.json");
+
+    let repository = FileAddressRepository::new(&db_path).unwrap();
+    let service = AddressService::new(std::sync::Arc::new(repository));
+
+    let address = Address {
+        name: Some("Monsieur Jean DURAND".to_string()),
+        street_name: "RUE DES FLEURS".to_string(),
+        post_code: "33500".to_string(),
+        town_name: "LIBOURNE".to_string(),
+        country: "FR".to_string(),
+        source_references: vec!["STMT-1".to_string()],
+        ..Default::default()
+    };
+    let duplicate = Address {
+        name: Some("MONSIEUR JEAN DURAND".to_string()),
+        source_references: vec!["STMT-2".to_string()],
+        ..address.clone()
+    };
+
+    let ImportOutcome::Added(id) = service.import(&address).unwrap() else {
+        panic!("Address must be added");
+    };
+    assert_eq!(
+        service.import(&duplicate).unwrap(),
+        ImportOutcome::Merged(id)
+    );
+
+    let stored = service.get(id).unwrap().expect("Address must be found");
+    assert_eq!(stored.source_references, vec!["STMT-1", "STMT-2"]);
+    assert_eq!(service.list().unwrap().len(), 1);
+}